@@ -121,6 +121,57 @@ impl Child {
         }
     }
 
+    /// The OS-assigned process identifier, if the child has not been reaped yet
+    pub fn id(&self) -> Option<u32> {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "async-std-runtime")] {
+                Some(self.inner.id())
+            } else if #[cfg(feature = "tokio-runtime")] {
+                self.inner.id()
+            }
+        }
+    }
+
+    /// Ask the child process to terminate.
+    ///
+    /// On Unix this sends `SIGTERM`, giving the process a chance to shut down
+    /// cleanly. On other platforms there is no such signal and this behaves
+    /// like [`Child::start_kill`].
+    pub fn terminate(&mut self) -> std::io::Result<()> {
+        #[cfg(unix)]
+        {
+            let pid = match self.id() {
+                Some(pid) => pid,
+                // already reaped
+                None => return Ok(()),
+            };
+            // SAFETY: `kill` has no memory safety requirements; the pid belongs
+            // to our own, not yet reaped, child process.
+            if unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) } == 0 {
+                Ok(())
+            } else {
+                Err(std::io::Error::last_os_error())
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            self.start_kill()
+        }
+    }
+
+    /// Forcibly kill the child process without waiting for it to exit.
+    ///
+    /// This is `SIGKILL` on Unix and `TerminateProcess` on Windows.
+    pub fn start_kill(&mut self) -> std::io::Result<()> {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "async-std-runtime")] {
+                self.inner.kill()
+            } else if #[cfg(feature = "tokio-runtime")] {
+                self.inner.start_kill()
+            }
+        }
+    }
+
     /// Return a mutable reference to the inner process
     ///
     /// `stderr` may not be available.
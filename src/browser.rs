@@ -1,4 +1,5 @@
 use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 use std::{
     collections::HashMap,
@@ -9,15 +10,15 @@ use std::{
 use futures::channel::mpsc::{channel, unbounded, Sender};
 use futures::channel::oneshot::channel as oneshot_channel;
 use futures::select;
-use futures::SinkExt;
+use futures::{SinkExt, StreamExt};
 
 use chromiumoxide_cdp::cdp::browser_protocol::network::{Cookie, CookieParam};
 use chromiumoxide_cdp::cdp::browser_protocol::storage::{
     ClearCookiesParams, GetCookiesParams, SetCookiesParams,
 };
 use chromiumoxide_cdp::cdp::browser_protocol::target::{
-    CreateBrowserContextParams, CreateTargetParams, DisposeBrowserContextParams, TargetId,
-    TargetInfo,
+    CloseTargetParams, CreateBrowserContextParams, CreateTargetParams, DisposeBrowserContextParams,
+    TargetId, TargetInfo,
 };
 use chromiumoxide_cdp::cdp::{CdpEventMessage, IntoEventKind};
 use chromiumoxide_types::*;
@@ -29,9 +30,12 @@ use crate::detection::{self, DetectionOptions};
 use crate::error::{BrowserStderr, CdpError, Result};
 use crate::handler::browser::BrowserContext;
 use crate::handler::viewport::Viewport;
-use crate::handler::{Handler, HandlerConfig, HandlerMessage, REQUEST_TIMEOUT};
+use crate::handler::{
+    Handler, HandlerConfig, HandlerMessage, COMMAND_QUEUE_DEPTH, REQUEST_TIMEOUT,
+};
 use crate::listeners::{EventListenerRequest, EventStream};
-use crate::page::Page;
+use crate::media::FakeMediaConfig;
+use crate::page::{Page, ScreenshotParams};
 use crate::utils;
 use chromiumoxide_cdp::cdp::browser_protocol::browser::{
     BrowserContextId, CloseReturns, GetVersionParams, GetVersionReturns,
@@ -40,6 +44,14 @@ use chromiumoxide_cdp::cdp::browser_protocol::browser::{
 /// Default `Browser::launch` timeout in MS
 pub const LAUNCH_TIMEOUT: u64 = 20_000;
 
+/// How long `Browser::screenshot_all` waits for a page captured in the
+/// background before activating it
+const BACKGROUND_SCREENSHOT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long dropping a [`Browser`] blocks waiting for the killed chromium
+/// instance to exit
+const DROP_KILL_TIMEOUT: Duration = Duration::from_millis(500);
+
 /// A [`Browser`] is created when chromiumoxide connects to a Chromium instance.
 #[derive(Debug)]
 pub struct Browser {
@@ -54,6 +66,9 @@ pub struct Browser {
     debug_ws_url: String,
     /// The context of the browser
     browser_context: BrowserContext,
+    /// The user data directory chromiumoxide created for the spawned
+    /// instance, removed once it exited
+    temp_user_data_dir: Option<PathBuf>,
 }
 
 /// Browser connection information.
@@ -79,6 +94,65 @@ pub struct BrowserConnection {
     pub web_socket_debugger_url: String,
 }
 
+/// Version information about the browser, captured once when connecting,
+/// see [`Browser::version_info`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrowserVersion {
+    /// The product name and version, like `HeadlessChrome/120.0.6099.71`
+    pub product: String,
+    /// The product revision
+    pub revision: String,
+    /// The version of the DevTools protocol, like `1.3`
+    pub protocol_version: String,
+    /// The default user agent
+    pub user_agent: String,
+    /// The V8 version
+    pub js_version: String,
+}
+
+impl BrowserVersion {
+    /// The major version of the product, like `120`.
+    pub fn major(&self) -> Option<u32> {
+        self.product
+            .split_once('/')?
+            .1
+            .split('.')
+            .next()?
+            .parse()
+            .ok()
+    }
+
+    /// Whether the protocol version is at least `min`, like `1.3`.
+    pub fn supports_protocol(&self, min: &str) -> bool {
+        utils::version_at_least(&self.protocol_version, min)
+    }
+
+    /// Fails with [`CdpError::UnsupportedByBrowser`] if the major version of
+    /// the product is known to be older than `min_major`.
+    pub fn require(&self, feature: impl Into<String>, min_major: u32) -> Result<()> {
+        match self.major() {
+            Some(major) if major < min_major => Err(CdpError::UnsupportedByBrowser {
+                feature: feature.into(),
+                needs: format!("Chrome {min_major}"),
+                have: self.product.clone(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl From<GetVersionReturns> for BrowserVersion {
+    fn from(version: GetVersionReturns) -> Self {
+        Self {
+            product: version.product,
+            revision: version.revision,
+            protocol_version: version.protocol_version,
+            user_agent: version.user_agent,
+            js_version: version.js_version,
+        }
+    }
+}
+
 impl Browser {
     /// Connect to an already running chromium instance via the given URL.
     ///
@@ -145,6 +219,7 @@ impl Browser {
             child: None,
             debug_ws_url,
             browser_context,
+            temp_user_data_dir: None,
         };
         Ok((browser, fut))
     }
@@ -161,8 +236,21 @@ impl Browser {
         // Canonalize paths to reduce issues with sandboxing
         config.executable = utils::canonicalize_except_snap(config.executable).await?;
 
+        // Every instance gets its own fresh profile, so that closing one
+        // browser never removes the profile of another one
+        let temp_user_data_dir = config.user_data_dir.is_none().then(unique_user_data_dir);
+        if let Some(dir) = &temp_user_data_dir {
+            config.user_data_dir = Some(dir.clone());
+        }
+
         // Launch a new chromium instance
-        let mut child = config.launch()?;
+        let mut child = match config.launch() {
+            Ok(child) => child,
+            Err(err) => {
+                remove_user_data_dir(temp_user_data_dir.as_deref());
+                return Err(err.into());
+            }
+        };
 
         /// Faillible initialization to run once the child process is created.
         ///
@@ -199,6 +287,7 @@ impl Browser {
                     child.kill().await.expect("`Browser::launch` failed but could not clean-up the child process (`kill`)");
                     child.wait().await.expect("`Browser::launch` failed but could not clean-up the child process (`wait`)");
                 }
+                remove_user_data_dir(temp_user_data_dir.as_deref());
                 return Err(e);
             }
         };
@@ -215,6 +304,7 @@ impl Browser {
             request_timeout: config.request_timeout,
             request_intercept: config.request_intercept,
             cache_enabled: config.cache_enabled,
+            command_queue_depth: config.command_queue_depth,
         };
 
         let fut = Handler::new(conn, rx, handler_config);
@@ -226,6 +316,7 @@ impl Browser {
             child: Some(child),
             debug_ws_url,
             browser_context,
+            temp_user_data_dir,
         };
 
         Ok((browser, fut))
@@ -331,6 +422,115 @@ impl Browser {
         }
     }
 
+    /// Tear down the browser, escalating until the spawned chromium instance
+    /// is gone.
+    ///
+    /// This consumes the [`Browser`], so no further commands can be issued
+    /// through it. All open pages are closed without running their
+    /// `beforeunload` handlers, `Browser.close` is sent and the websocket
+    /// connection is given the chance to disconnect. If the spawned process
+    /// has not exited within `timeout` it is sent `SIGTERM` (Unix only) and,
+    /// after another `timeout`, killed forcibly (`SIGKILL` or
+    /// `TerminateProcess`). Finally the child is reaped and the temporary
+    /// user data directory is removed if it was chosen by chromiumoxide
+    /// rather than configured via [`BrowserConfigBuilder::user_data_dir`].
+    ///
+    /// Use [`Browser::shutdown_with_before_unload`] to give pages a chance to
+    /// run their `beforeunload` handlers first.
+    pub async fn shutdown(self, timeout: Duration) -> Result<ShutdownReport> {
+        self.shutdown_inner(timeout, false).await
+    }
+
+    /// Same as [`Browser::shutdown`], but closes every page with
+    /// `Page.close`, which runs its `beforeunload` handlers.
+    pub async fn shutdown_with_before_unload(self, timeout: Duration) -> Result<ShutdownReport> {
+        self.shutdown_inner(timeout, true).await
+    }
+
+    async fn shutdown_inner(
+        mut self,
+        timeout: Duration,
+        run_before_unload: bool,
+    ) -> Result<ShutdownReport> {
+        let deadline = std::time::Instant::now() + timeout;
+        let remaining = || deadline.saturating_duration_since(std::time::Instant::now());
+
+        if let Some(Ok(pages)) = utils::with_timeout(self.pages(), remaining()).await {
+            let closing = futures::future::join_all(pages.into_iter().map(|page| async move {
+                if run_before_unload {
+                    page.close().await
+                } else {
+                    page.execute(CloseTargetParams::new(page.target_id().clone()))
+                        .await
+                        .map(|_| ())
+                }
+            }));
+            if utils::with_timeout(closing, remaining()).await.is_none() {
+                tracing::debug!("Timed out closing pages during shutdown");
+            }
+        }
+
+        match utils::with_timeout(self.close(), remaining()).await {
+            Some(Ok(_)) => {}
+            Some(Err(err)) => tracing::debug!("Failed to send Browser.close: {err:?}"),
+            None => tracing::debug!("Timed out sending Browser.close"),
+        }
+
+        // the handler drops its receiver once the websocket is disconnected
+        while !self.sender.is_closed() && !remaining().is_zero() {
+            futures_timer::Delay::new(remaining().min(Duration::from_millis(50))).await;
+        }
+
+        let Some(mut child) = self.child.take() else {
+            return Ok(ShutdownReport {
+                exit_status: None,
+                escalation: ShutdownEscalation::Graceful,
+                user_data_dir_removed: false,
+            });
+        };
+
+        let mut escalation = ShutdownEscalation::Graceful;
+        let mut exit_status = utils::with_timeout(child.wait(), remaining())
+            .await
+            .transpose()?;
+
+        if exit_status.is_none() {
+            escalation = ShutdownEscalation::Terminated;
+            // if the signal can't be sent, kill the process right away
+            match child.terminate() {
+                Ok(()) => {
+                    exit_status = utils::with_timeout(child.wait(), timeout)
+                        .await
+                        .transpose()?;
+                }
+                Err(err) => tracing::debug!("Failed to terminate the browser process: {err}"),
+            }
+        }
+
+        let exit_status = match exit_status {
+            Some(status) => status,
+            None => {
+                escalation = ShutdownEscalation::Killed;
+                child.kill().await?;
+                child.wait().await?
+            }
+        };
+
+        let user_data_dir_removed = self.remove_temp_user_data_dir();
+
+        Ok(ShutdownReport {
+            exit_status: Some(exit_status),
+            escalation,
+            user_data_dir_removed,
+        })
+    }
+
+    /// Removes the user data directory if chromiumoxide created it at
+    /// launch.
+    fn remove_temp_user_data_dir(&self) -> bool {
+        remove_user_data_dir(self.temp_user_data_dir.as_deref())
+    }
+
     /// If not launched as incognito this creates a new incognito browser
     /// context. After that this browser exists within the incognito session.
     /// New pages created while being in incognito mode will also run in the
@@ -418,6 +618,83 @@ impl Browser {
         Ok(self.version().await?.user_agent)
     }
 
+    /// Version information about the browser, which the [`Handler`] requests
+    /// right after connecting and keeps for the lifetime of the connection.
+    ///
+    /// In contrast to [`Browser::version`] this does not send a new request
+    /// once the version is known.
+    pub async fn version_info(&self) -> Result<BrowserVersion> {
+        let (tx, rx) = oneshot_channel();
+        self.sender
+            .clone()
+            .send(HandlerMessage::GetVersionInfo(tx))
+            .await?;
+        rx.await?
+    }
+
+    /// Fails with [`CdpError::UnsupportedByBrowser`] if the DevTools protocol
+    /// version of the browser is older than `min`, like `1.3`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::browser::Browser;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(browser: Browser) -> Result<()> {
+    ///     browser.require_protocol("1.3").await?;
+    ///     let version = browser.version_info().await?;
+    ///     println!("connected to {}", version.product);
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn require_protocol(&self, min: &str) -> Result<()> {
+        let version = self.version_info().await?;
+        if version.supports_protocol(min) {
+            Ok(())
+        } else {
+            Err(CdpError::UnsupportedByBrowser {
+                feature: "The client".to_string(),
+                needs: format!("protocol {min}"),
+                have: format!(
+                    "{} (protocol {})",
+                    version.product, version.protocol_version
+                ),
+            })
+        }
+    }
+
+    /// Limits the commands of pages that are awaiting a response from the
+    /// browser, per page session and over all pages, `None` is unlimited.
+    ///
+    /// Commands over the limit are held back by the [`Handler`] until
+    /// responses arrive, and once the queue of a page is full, senders wait
+    /// for capacity, see [`Page::command_stats`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::browser::Browser;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(browser: Browser) -> Result<()> {
+    ///     browser.set_max_inflight_commands(Some(8), Some(64)).await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_max_inflight_commands(
+        &self,
+        per_session: Option<usize>,
+        global: Option<usize>,
+    ) -> Result<()> {
+        self.sender
+            .clone()
+            .send(HandlerMessage::SetMaxInflightCommands {
+                per_session,
+                global,
+            })
+            .await?;
+        Ok(())
+    }
+
     /// Call a browser method.
     pub async fn execute<T: Command>(&self, cmd: T) -> Result<CommandResponse<T::Response>> {
         let (tx, rx) = oneshot_channel();
@@ -442,6 +719,69 @@ impl Browser {
         Ok(rx.await?)
     }
 
+    /// Takes a screenshot of every page of the browser, at most
+    /// `concurrency` at a time, and returns them in the order of
+    /// [`Browser::pages`].
+    ///
+    /// Pages are captured in the background first, without activating them.
+    /// A page that does not produce a screenshot within a few seconds, like
+    /// a background tab of a headful browser that does not render, is
+    /// activated and captured again, one page at a time. A page that fails,
+    /// e.g. because it crashed, is reported with its error without failing
+    /// the other pages.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::browser::Browser;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::page::ScreenshotParams;
+    /// # async fn demo(browser: Browser) -> Result<()> {
+    ///     for (target_id, png) in browser.screenshot_all(ScreenshotParams::default(), 4).await? {
+    ///         match png {
+    ///             Ok(png) => println!("{target_id:?}: {} bytes", png.len()),
+    ///             Err(err) => println!("{target_id:?} failed: {err}"),
+    ///         }
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn screenshot_all(
+        &self,
+        params: impl Into<ScreenshotParams>,
+        concurrency: usize,
+    ) -> Result<Vec<(TargetId, Result<Vec<u8>>)>> {
+        let mut params = params.into();
+        params.cdp_params.from_surface.get_or_insert(true);
+        let activation = futures::lock::Mutex::new(());
+        let pages = self.pages().await?;
+        Ok(futures::stream::iter(pages)
+            .map(|page| {
+                let params = params.clone();
+                let activation = &activation;
+                async move {
+                    let inner = page.inner();
+                    let background = utils::with_timeout(
+                        inner.capture_screenshot(params.clone()),
+                        BACKGROUND_SCREENSHOT_TIMEOUT,
+                    )
+                    .await;
+                    let screenshot = match background {
+                        Some(Ok(screenshot)) => Ok(screenshot),
+                        _ => {
+                            // activated pages take the focus from each other
+                            let _activation = activation.lock().await;
+                            inner.screenshot(params).await
+                        }
+                    };
+                    (page.target_id().clone(), screenshot)
+                }
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await)
+    }
+
     /// Return page of given target_id
     pub async fn get_page(&self, target_id: TargetId) -> Result<Page> {
         let (tx, rx) = oneshot_channel();
@@ -516,18 +856,93 @@ impl Browser {
 impl Drop for Browser {
     fn drop(&mut self) {
         if let Some(child) = self.child.as_mut() {
-            if let Ok(Some(_)) = child.try_wait() {
-                // Already exited, do nothing. Usually occurs after using the method close or kill.
-            } else {
-                // We set the `kill_on_drop` property for the child process, so no need to explicitely
-                // kill it here. It can't really be done anyway since the method is async.
+            // Unless it already exited, usually after using the method close or kill.
+            if !matches!(child.try_wait(), Ok(Some(_))) {
+                // We set the `kill_on_drop` property for the child process, but we can't await it
+                // here since the method is async, so request the kill right away and poll for the
+                // exit for a short while.
                 //
                 // On Unix, the process will be reaped in the background by the runtime automatically
                 // so it won't leave any resources locked. It is, however, a better practice for the user to
-                // do it himself since the runtime doesn't provide garantees as to when the reap occurs, so we
-                // warn him here.
+                // do it himself, e.g. with `Browser::shutdown`, since the runtime doesn't provide garantees
+                // as to when the reap occurs, so we warn him here.
+                let _ = child.start_kill();
+                let deadline = std::time::Instant::now() + DROP_KILL_TIMEOUT;
+                while !matches!(child.try_wait(), Ok(Some(_)))
+                    && std::time::Instant::now() < deadline
+                {
+                    std::thread::sleep(Duration::from_millis(10));
+                }
                 tracing::warn!("Browser was not closed manually, it will be killed automatically in the background");
             }
+            // Removing the files of a still exiting process is fine on Unix, elsewhere this is
+            // best-effort.
+            self.remove_temp_user_data_dir();
+        }
+    }
+}
+
+/// How far [`Browser::shutdown`] had to escalate before the spawned chromium
+/// instance exited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownEscalation {
+    /// The process exited on its own after `Browser.close`.
+    Graceful,
+    /// The process had to be sent `SIGTERM`.
+    Terminated,
+    /// The process had to be killed forcibly.
+    Killed,
+}
+
+/// Outcome of [`Browser::shutdown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// Exit status of the spawned chromium instance, `None` if this
+    /// [`Browser`] did not spawn one.
+    pub exit_status: Option<ExitStatus>,
+    /// Whether the teardown needed to escalate.
+    pub escalation: ShutdownEscalation,
+    /// Whether the temporary user data directory was removed.
+    pub user_data_dir_removed: bool,
+}
+
+impl ShutdownReport {
+    /// Whether the process exited on its own and successfully.
+    pub fn is_clean(&self) -> bool {
+        self.escalation == ShutdownEscalation::Graceful
+            && self.exit_status.map_or(true, |status| status.success())
+    }
+}
+
+/// The user data directory used by [`BrowserConfig::launch`] when none was
+/// configured.
+fn default_user_data_dir() -> PathBuf {
+    std::env::temp_dir().join("chromiumoxide-runner")
+}
+
+/// A user data directory of its own for an instance spawned by
+/// [`Browser::launch`] without a configured one.
+fn unique_user_data_dir() -> PathBuf {
+    static LAUNCHES: AtomicUsize = AtomicUsize::new(0);
+    std::env::temp_dir().join(format!(
+        "chromiumoxide-runner-{}-{}",
+        std::process::id(),
+        LAUNCHES.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Removes the temporary user data directory, if any, and returns whether it
+/// was removed.
+fn remove_user_data_dir(dir: Option<&Path>) -> bool {
+    let Some(dir) = dir else {
+        return false;
+    };
+    match std::fs::remove_dir_all(dir) {
+        Ok(()) => true,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => false,
+        Err(err) => {
+            tracing::warn!("Failed to remove temporary user data dir: {err}");
+            false
         }
     }
 }
@@ -657,6 +1072,9 @@ pub struct BrowserConfig {
 
     /// Whether to enable cache
     pub cache_enabled: bool,
+
+    /// How many commands a page queues before senders have to wait
+    command_queue_depth: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
@@ -679,6 +1097,7 @@ pub struct BrowserConfigBuilder {
     disable_default_args: bool,
     request_intercept: bool,
     cache_enabled: bool,
+    command_queue_depth: Option<usize>,
 }
 
 impl BrowserConfig {
@@ -712,6 +1131,7 @@ impl Default for BrowserConfigBuilder {
             disable_default_args: false,
             request_intercept: false,
             cache_enabled: true,
+            command_queue_depth: Some(COMMAND_QUEUE_DEPTH),
         }
     }
 }
@@ -767,6 +1187,13 @@ impl BrowserConfigBuilder {
         self
     }
 
+    /// How many commands a page queues before senders have to wait for the
+    /// handler to submit them, defaults to `64`. `None` queues without bound.
+    pub fn command_queue_depth(mut self, depth: impl Into<Option<usize>>) -> Self {
+        self.command_queue_depth = depth.into();
+        self
+    }
+
     /// Configures the viewport of the browser, which defaults to `800x600`.
     /// `None` disables viewport emulation (i.e., it uses the browsers default
     /// configuration, which fills the available space. This is similar to what
@@ -843,6 +1270,12 @@ impl BrowserConfigBuilder {
         self
     }
 
+    /// Launches the browser with fake camera and microphone devices and
+    /// accepts the permission prompt for them, see [`FakeMediaConfig`].
+    pub fn fake_media(self, config: FakeMediaConfig) -> Self {
+        self.args(config.args())
+    }
+
     pub fn disable_default_args(mut self) -> Self {
         self.disable_default_args = true;
         self
@@ -893,6 +1326,7 @@ impl BrowserConfigBuilder {
             disable_default_args: self.disable_default_args,
             request_intercept: self.request_intercept,
             cache_enabled: self.cache_enabled,
+            command_queue_depth: self.command_queue_depth,
         })
     }
 }
@@ -929,7 +1363,7 @@ impl BrowserConfig {
             // a temp dir just for chromiumoxide instead.
             cmd.arg(format!(
                 "--user-data-dir={}",
-                std::env::temp_dir().join("chromiumoxide-runner").display()
+                default_user_data_dir().display()
             ));
         }
 
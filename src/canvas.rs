@@ -0,0 +1,173 @@
+use std::ops::Deref;
+
+use crate::element::Element;
+use crate::error::{CdpError, Result};
+use crate::js::{call_function_on, value_or_null, JsObject};
+use crate::utils;
+
+/// Represents a [HTMLCanvasElement](https://developer.mozilla.org/en-US/docs/Web/API/HTMLCanvasElement),
+/// the `<canvas>` element.
+///
+/// Derefs to the underlying [`Element`].
+#[derive(Debug)]
+pub struct CanvasElement {
+    element: Element,
+}
+
+impl CanvasElement {
+    /// Creates a new `CanvasElement` from the `element`, fails if the element
+    /// is not an `HTMLCanvasElement`.
+    ///
+    /// # Example Capture a chart
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::canvas::CanvasElement;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let chart = CanvasElement::new(page.find_element("canvas#chart").await?).await?;
+    ///     let png = chart.to_blob(None, None).await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn new(element: Element) -> Result<Self> {
+        element.ensure_instance_of("HTMLCanvasElement").await?;
+        Ok(Self { element })
+    }
+
+    /// Returns the underlying `Element`.
+    pub fn into_element(self) -> Element {
+        self.element
+    }
+
+    async fn call<T: serde::de::DeserializeOwned>(
+        &self,
+        function_declaration: &str,
+        args: Vec<serde_json::Value>,
+    ) -> Result<T> {
+        let value = call_function_on(
+            self.element.tab(),
+            &self.element.remote_object_id,
+            function_declaration,
+            args,
+            true,
+        )
+        .await?;
+        Ok(value_or_null(value)?)
+    }
+
+    /// The width of the canvas in pixels.
+    pub async fn width(&self) -> Result<u32> {
+        self.call("function() { return this.width; }", vec![]).await
+    }
+
+    /// Sets the width of the canvas in pixels, which clears its content.
+    pub async fn set_width(&self, width: u32) -> Result<&Self> {
+        self.call::<serde_json::Value>(
+            "function(width) { this.width = width; }",
+            vec![width.into()],
+        )
+        .await?;
+        Ok(self)
+    }
+
+    /// The height of the canvas in pixels.
+    pub async fn height(&self) -> Result<u32> {
+        self.call("function() { return this.height; }", vec![])
+            .await
+    }
+
+    /// Sets the height of the canvas in pixels, which clears its content.
+    pub async fn set_height(&self, height: u32) -> Result<&Self> {
+        self.call::<serde_json::Value>(
+            "function(height) { this.height = height; }",
+            vec![height.into()],
+        )
+        .await?;
+        Ok(self)
+    }
+
+    /// Returns the content of the canvas as `data:` URL, see
+    /// [toDataURL](https://developer.mozilla.org/en-US/docs/Web/API/HTMLCanvasElement/toDataURL).
+    ///
+    /// `mime_type` defaults to `image/png`, `quality` from `0.0` to `1.0`
+    /// applies to lossy formats like `image/jpeg` only.
+    ///
+    /// Fails for canvases tainted by cross-origin content.
+    pub async fn to_data_url(
+        &self,
+        mime_type: Option<&str>,
+        quality: Option<f64>,
+    ) -> Result<String> {
+        self.call(
+            "function(type, quality) { return this.toDataURL(type ?? undefined, quality ?? undefined); }",
+            vec![mime_type.into(), quality.into()],
+        )
+        .await
+    }
+
+    /// Returns the encoded content of the canvas, see
+    /// [toBlob](https://developer.mozilla.org/en-US/docs/Web/API/HTMLCanvasElement/toBlob).
+    ///
+    /// `mime_type` defaults to `image/png`, `quality` from `0.0` to `1.0`
+    /// applies to lossy formats like `image/jpeg` only.
+    pub async fn to_blob(&self, mime_type: Option<&str>, quality: Option<f64>) -> Result<Vec<u8>> {
+        let resp = self
+            .element
+            .call_js_fn(
+                format!(
+                    "function() {{
+                        return new Promise(resolve => this.toBlob(blob => {{
+                            if (!blob) return resolve(null);
+                            const reader = new FileReader();
+                            reader.onload = () => resolve(reader.result);
+                            reader.onerror = () => resolve(null);
+                            reader.readAsDataURL(blob);
+                        }}, {}, {}));
+                    }}",
+                    serde_json::to_string(&mime_type.unwrap_or("image/png"))?,
+                    serde_json::to_string(&quality)?
+                ),
+                true,
+            )
+            .await?;
+        if let Some(exception) = resp.exception_details {
+            return Err(CdpError::JavascriptException(Box::new(exception)));
+        }
+        let data_url: Option<String> = value_or_null(resp.result)?;
+        let data_url = data_url.ok_or_else(|| CdpError::msg("Canvas could not be encoded"))?;
+        let (_, data) = data_url
+            .split_once(";base64,")
+            .ok_or_else(|| CdpError::msg("Canvas could not be encoded"))?;
+        Ok(utils::base64::decode(data)?)
+    }
+
+    /// Returns the drawing context of the canvas, e.g. `2d` or `webgl`.
+    ///
+    /// Fails if the context type is not supported or the canvas already has
+    /// a context of another type.
+    pub async fn context(&self, context_type: &str) -> Result<JsObject> {
+        let context = call_function_on(
+            self.element.tab(),
+            &self.element.remote_object_id,
+            "function(type) { return this.getContext(type); }",
+            vec![context_type.into()],
+            false,
+        )
+        .await?;
+        let object_id = context
+            .object_id
+            .ok_or_else(|| CdpError::msg(format!("Canvas has no {context_type} context")))?;
+        let class = context.class_name.as_deref().unwrap_or("Object");
+        Ok(JsObject::new(self.element.tab().clone(), object_id, class)
+            .with_description(context.description))
+    }
+}
+
+impl Deref for CanvasElement {
+    type Target = Element;
+
+    fn deref(&self) -> &Self::Target {
+        &self.element
+    }
+}
@@ -84,6 +84,44 @@ impl<T> CommandMessage<T> {
     }
 }
 
+/// A command that is not covered by the protocol definitions, identified by
+/// its `method` and with the `params` sent as they are.
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct RawCommand {
+    #[serde(skip)]
+    pub method: MethodId,
+    pub params: serde_json::Value,
+}
+
+impl RawCommand {
+    pub fn new(method: impl Into<MethodId>, params: serde_json::Value) -> Self {
+        Self {
+            method: method.into(),
+            params,
+        }
+    }
+}
+
+impl Method for RawCommand {
+    fn identifier(&self) -> MethodId {
+        self.method.clone()
+    }
+}
+
+impl Command for RawCommand {
+    type Response = serde_json::Value;
+}
+
+/// Whether the `method` is a valid protocol method identifier like
+/// `DOM.removeNode`
+pub(crate) fn is_valid_method(method: &str) -> bool {
+    method
+        .split_once('.')
+        .map(|(domain, name)| !domain.is_empty() && !name.is_empty())
+        .unwrap_or_default()
+}
+
 impl Method for CommandMessage {
     fn identifier(&self) -> MethodId {
         self.method.clone()
@@ -165,3 +203,17 @@ impl Default for CommandChain {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_methods() {
+        assert!(is_valid_method("DOM.removeNode"));
+        assert!(!is_valid_method(""));
+        assert!(!is_valid_method("removeNode"));
+        assert!(!is_valid_method(".removeNode"));
+        assert!(!is_valid_method("DOM."));
+    }
+}
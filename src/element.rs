@@ -1,30 +1,247 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::path::Path;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
 use futures::{future, Future, FutureExt, Stream};
+use serde::{Deserialize, Serialize};
 
+use chromiumoxide_cdp::cdp::browser_protocol::accessibility::{AxNode, GetPartialAxTreeParams};
 use chromiumoxide_cdp::cdp::browser_protocol::dom::{
     BackendNodeId, DescribeNodeParams, GetBoxModelParams, GetContentQuadsParams, Node, NodeId,
-    ResolveNodeParams,
+    RequestNodeParams, ResolveNodeParams,
 };
 use chromiumoxide_cdp::cdp::browser_protocol::page::{
     CaptureScreenshotFormat, CaptureScreenshotParams, Viewport,
 };
 use chromiumoxide_cdp::cdp::js_protocol::runtime::{
-    CallFunctionOnReturns, GetPropertiesParams, PropertyDescriptor, RemoteObjectId,
-    RemoteObjectType,
+    CallArgument, CallFunctionOnParams, CallFunctionOnReturns, GetPropertiesParams,
+    PropertyDescriptor, ReleaseObjectParams, RemoteObject, RemoteObjectId, RemoteObjectType,
 };
 
 use crate::error::{CdpError, Result};
 use crate::handler::PageInner;
+use crate::js::data_transfer::{DataTransferInit, NEW_DATA_TRANSFER_FN};
+use crate::js::event::JsEvent;
+use crate::js::markdown::{MarkdownOptions, MarkdownOutput, TO_MARKDOWN_FN};
+use crate::js::registry::ObjectEntry;
+use crate::js::table::{TableMatrix, TableOptions, TABLE_MATRIX_FN};
+use crate::js::{
+    await_function_on, call_function_on, call_function_on_with, value_or_null, JsDocumentFragment,
+    JsDomTokenList, Preview, INSTANCE_OF_NAMED_FN,
+};
 use crate::layout::{BoundingBox, BoxModel, ElementQuad, Point};
 use crate::utils;
 
+/// The options of [`Element::scroll_into_view_and_wait`], see
+/// [scrollIntoView](https://developer.mozilla.org/en-US/docs/Web/API/Element/scrollIntoView).
+///
+/// Prefer [`ScrollIntoViewOptions::builder`] for typed values; the raw
+/// fields accept any string the browser understands.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ScrollIntoViewOptions {
+    /// `auto`, `instant` or `smooth`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub behavior: Option<String>,
+    /// `start`, `center`, `end` or `nearest`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<String>,
+    /// `start`, `center`, `end` or `nearest`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inline: Option<String>,
+}
+
+impl ScrollIntoViewOptions {
+    pub fn builder() -> ScrollIntoViewOptionsBuilder {
+        ScrollIntoViewOptionsBuilder::default()
+    }
+}
+
+/// Builder for [`ScrollIntoViewOptions`].
+#[derive(Debug, Default)]
+pub struct ScrollIntoViewOptionsBuilder {
+    options: ScrollIntoViewOptions,
+}
+
+impl ScrollIntoViewOptionsBuilder {
+    /// Whether scrolling is instant or animates smoothly.
+    pub fn behavior(mut self, behavior: ScrollBehavior) -> Self {
+        self.options.behavior = Some(behavior.as_str().to_string());
+        self
+    }
+
+    /// The vertical alignment of the element.
+    pub fn block(mut self, block: ScrollLogicalPosition) -> Self {
+        self.options.block = Some(block.as_str().to_string());
+        self
+    }
+
+    /// The horizontal alignment of the element.
+    pub fn inline(mut self, inline: ScrollLogicalPosition) -> Self {
+        self.options.inline = Some(inline.as_str().to_string());
+        self
+    }
+
+    pub fn build(self) -> ScrollIntoViewOptions {
+        self.options
+    }
+}
+
+/// How scrolling moves to its destination, see
+/// [ScrollBehavior](https://developer.mozilla.org/en-US/docs/Web/API/Element/scrollIntoView#behavior).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScrollBehavior {
+    /// Determined by the `scroll-behavior` CSS property
+    #[default]
+    Auto,
+    /// Jump to the destination in a single step
+    Instant,
+    /// Animate to the destination
+    Smooth,
+}
+
+impl ScrollBehavior {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScrollBehavior::Auto => "auto",
+            ScrollBehavior::Instant => "instant",
+            ScrollBehavior::Smooth => "smooth",
+        }
+    }
+}
+
+/// Where an element is aligned within the scrolling area, see
+/// [scrollIntoView](https://developer.mozilla.org/en-US/docs/Web/API/Element/scrollIntoView#block).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScrollLogicalPosition {
+    Start,
+    Center,
+    End,
+    Nearest,
+}
+
+impl ScrollLogicalPosition {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScrollLogicalPosition::Start => "start",
+            ScrollLogicalPosition::Center => "center",
+            ScrollLogicalPosition::End => "end",
+            ScrollLogicalPosition::Nearest => "nearest",
+        }
+    }
+}
+
+/// The options of [`Element::wait_for_intersection`], see
+/// [IntersectionObserver](https://developer.mozilla.org/en-US/docs/Web/API/IntersectionObserver/IntersectionObserver).
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntersectionOptions {
+    /// The ratio of the element that has to be visible, between `0.0` and
+    /// `1.0`
+    pub threshold: f64,
+    /// Grows or shrinks the viewport before computing intersections, like
+    /// the CSS `margin` property, e.g. `"0px 0px -100px 0px"`
+    pub root_margin: String,
+    /// Whether to wait for the threshold to be reached or to be left
+    pub direction: IntersectionDirection,
+    /// How long to wait for the threshold to be crossed
+    pub timeout: Duration,
+}
+
+impl IntersectionOptions {
+    /// Waits for at least `threshold` of the element to enter the viewport.
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            threshold,
+            ..Default::default()
+        }
+    }
+
+    pub fn root_margin(mut self, root_margin: impl Into<String>) -> Self {
+        self.root_margin = root_margin.into();
+        self
+    }
+
+    pub fn direction(mut self, direction: IntersectionDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl Default for IntersectionOptions {
+    fn default() -> Self {
+        Self {
+            threshold: 0.,
+            root_margin: "0px".to_string(),
+            direction: IntersectionDirection::Enter,
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Which crossing of the threshold [`Element::wait_for_intersection`] waits
+/// for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum IntersectionDirection {
+    /// At least the threshold of the element is visible
+    #[default]
+    Enter,
+    /// Less than the threshold of the element is visible
+    Leave,
+}
+
+/// The intersection reported by [`Element::wait_for_intersection`], see
+/// [IntersectionObserverEntry](https://developer.mozilla.org/en-US/docs/Web/API/IntersectionObserverEntry).
+#[derive(Debug, Clone)]
+pub struct IntersectionInfo {
+    /// The visible ratio of the element, between `0.0` and `1.0`
+    pub intersection_ratio: f64,
+    /// Whether the element intersects the viewport at all
+    pub is_intersecting: bool,
+    /// The bounds of the element
+    pub bounding_client_rect: BoundingBox,
+    /// The visible part of the element
+    pub intersection_rect: BoundingBox,
+    /// The viewport, grown or shrunk by the root margin
+    pub root_bounds: Option<BoundingBox>,
+}
+
+bitflags::bitflags! {
+    /// The position of a node relative to another, as returned by
+    /// [`Element::compare_document_position`], see
+    /// [compareDocumentPosition](https://developer.mozilla.org/en-US/docs/Web/API/Node/compareDocumentPosition).
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct DocumentPosition: u16 {
+        /// The nodes are not in the same tree
+        const DISCONNECTED = 0x01;
+        /// The other node precedes this node
+        const PRECEDING = 0x02;
+        /// The other node follows this node
+        const FOLLOWING = 0x04;
+        /// The other node is an ancestor of this node
+        const CONTAINS = 0x08;
+        /// The other node is a descendant of this node
+        const CONTAINED_BY = 0x10;
+        /// The order of disconnected nodes is implementation specific
+        const IMPLEMENTATION_SPECIFIC = 0x20;
+    }
+}
+
 /// Represents a [DOM Element](https://developer.mozilla.org/en-US/docs/Web/API/Element).
-#[derive(Debug)]
+///
+/// `Debug` and `Display` print the class and the selector-like description
+/// the element had when it was resolved, like
+/// `Element(HTMLDivElement div#main.container)`.
 pub struct Element {
     /// The Unique object identifier
     pub remote_object_id: RemoteObjectId,
@@ -32,7 +249,32 @@ pub struct Element {
     pub backend_node_id: BackendNodeId,
     /// The identifier of the node this element represents.
     pub node_id: NodeId,
+    /// The class name of the element, like `HTMLDivElement`
+    class_name: Option<String>,
+    /// The description of the element, like `div#main.container`
+    description: Option<String>,
     tab: Arc<PageInner>,
+    _tracked: Option<Arc<ObjectEntry>>,
+}
+
+impl fmt::Debug for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let class = self.class_name.as_deref().unwrap_or("Element");
+        match &self.description {
+            Some(description) => write!(f, "Element({class} {})", Preview(description)),
+            None => write!(f, "Element({class} {:?})", self.backend_node_id),
+        }
+    }
+}
+
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.description, &self.class_name) {
+            (Some(description), _) => Preview(description).fmt(f),
+            (None, Some(class)) => f.write_str(class),
+            (None, None) => write!(f, "{:?}", self.backend_node_id),
+        }
+    }
 }
 
 impl Element {
@@ -56,19 +298,67 @@ impl Element {
             )
             .await?;
 
-        let remote_object_id = resp
-            .result
-            .object
+        let object = resp.result.object;
+        let remote_object_id = object
             .object_id
             .ok_or_else(|| CdpError::msg(format!("No object Id found for {node_id:?}")))?;
+        let _tracked = tab.objects().track(
+            &remote_object_id,
+            object.class_name.as_deref().unwrap_or("Element"),
+        );
         Ok(Self {
             remote_object_id,
             backend_node_id,
             node_id,
+            class_name: object.class_name,
+            description: object.description,
             tab,
+            _tracked,
         })
     }
 
+    pub(crate) fn tab(&self) -> &Arc<PageInner> {
+        &self.tab
+    }
+
+    /// Whether the element is an instance of the global constructor named
+    /// `ctor` of its own window, like `HTMLInputElement` or the class of a
+    /// custom element.
+    ///
+    /// Returns `false` if there is no such global or it is not a constructor.
+    pub async fn instance_of_named(&self, ctor: &str) -> Result<bool> {
+        let is_instance = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            INSTANCE_OF_NAMED_FN,
+            vec![ctor.into()],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(is_instance)?)
+    }
+
+    /// Fails unless the element is an instance of the DOM interface `class`
+    /// of its own window, e.g. `HTMLMediaElement`.
+    pub(crate) async fn ensure_instance_of(&self, class: &str) -> Result<()> {
+        if self.instance_of_named(class).await? {
+            Ok(())
+        } else {
+            Err(CdpError::msg(format!("Element is not an {class}")))
+        }
+    }
+
+    /// Resolves the DOM node referenced by the remote object, which is
+    /// released afterwards.
+    pub(crate) async fn from_object_id(
+        tab: Arc<PageInner>,
+        object_id: RemoteObjectId,
+    ) -> Result<Self> {
+        let node_id = tab.execute(RequestNodeParams::new(object_id.clone())).await;
+        tab.execute(ReleaseObjectParams::new(object_id)).await?;
+        Element::new(tab, node_id?.result.node_id).await
+    }
+
     /// Convert a slice of `NodeId`s into a `Vec` of `Element`s
     pub(crate) async fn from_nodes(tab: &Arc<PageInner>, node_ids: &[NodeId]) -> Result<Vec<Self>> {
         future::join_all(
@@ -198,6 +488,163 @@ impl Element {
             .await
     }
 
+    /// Whether the `other` node is this element or one of its descendants,
+    /// see [contains](https://developer.mozilla.org/en-US/docs/Web/API/Node/contains).
+    ///
+    /// Fails with [`CdpError::CrossDocumentComparison`] if the nodes belong
+    /// to different documents.
+    pub async fn contains(&self, other: &Element) -> Result<bool> {
+        let result = self
+            .compare_with(
+                other,
+                "function(other) { return this.contains(other); }",
+                true,
+            )
+            .await?;
+        Ok(value_or_null(result)?)
+    }
+
+    /// Returns the position of the `other` node relative to this element.
+    ///
+    /// Fails with [`CdpError::CrossDocumentComparison`] if the nodes belong
+    /// to different documents.
+    ///
+    /// # Example assert that an error message follows its input
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::element::DocumentPosition;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let input = page.find_element("input#email").await?;
+    ///     let error = page.find_element(".error").await?;
+    ///     let position = input.compare_document_position(&error).await?;
+    ///     assert!(position.contains(DocumentPosition::FOLLOWING));
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn compare_document_position(&self, other: &Element) -> Result<DocumentPosition> {
+        let result = self
+            .compare_with(
+                other,
+                "function(other) { return this.compareDocumentPosition(other); }",
+                true,
+            )
+            .await?;
+        Ok(DocumentPosition::from_bits_retain(value_or_null(result)?))
+    }
+
+    /// Whether the `other` handle references the very same node, see
+    /// [isSameNode](https://developer.mozilla.org/en-US/docs/Web/API/Node/isSameNode).
+    pub async fn is_same_node(&self, other: &Element) -> Result<bool> {
+        let result = self
+            .compare_with(other, "function(other) { return this === other; }", true)
+            .await?;
+        Ok(value_or_null(result)?)
+    }
+
+    /// Whether the `other` node is structurally equal to this element, i.e.
+    /// has the same type, attributes and children, see
+    /// [isEqualNode](https://developer.mozilla.org/en-US/docs/Web/API/Node/isEqualNode).
+    pub async fn is_equal_node(&self, other: &Element) -> Result<bool> {
+        let result = self
+            .compare_with(
+                other,
+                "function(other) { return this.isEqualNode(other); }",
+                true,
+            )
+            .await?;
+        Ok(value_or_null(result)?)
+    }
+
+    /// Returns the closest element that contains both this element and the
+    /// `other` node, which may be one of them.
+    ///
+    /// Returns `None` if the nodes are in different trees or only share the
+    /// document itself.
+    pub async fn common_ancestor(&self, other: &Element) -> Result<Option<Element>> {
+        let ancestor = self
+            .compare_with(
+                other,
+                "function(other) {
+                    if (this.getRootNode() !== other.getRootNode()) {
+                        return null;
+                    }
+                    const range = this.ownerDocument.createRange();
+                    range.setStart(this, 0);
+                    if (range.comparePoint(other, 0) < 0) {
+                        range.setStart(other, 0);
+                    } else {
+                        range.setEnd(other, 0);
+                    }
+                    const container = range.commonAncestorContainer;
+                    return container.nodeType === Node.ELEMENT_NODE
+                        ? container
+                        : container.parentElement;
+                }",
+                false,
+            )
+            .await?;
+        let Some(object_id) = ancestor.object_id else {
+            return Ok(None);
+        };
+        Ok(Some(
+            Element::from_object_id(Arc::clone(&self.tab), object_id).await?,
+        ))
+    }
+
+    /// Calls the `function_declaration` on this element with the `other`
+    /// element as argument, unless they belong to different documents.
+    ///
+    /// The function must not return `undefined`.
+    async fn compare_with(
+        &self,
+        other: &Element,
+        function_declaration: &str,
+        return_by_value: bool,
+    ) -> Result<RemoteObject> {
+        if self.tab.objects().is_released(&self.remote_object_id)
+            || self.tab.objects().is_released(&other.remote_object_id)
+        {
+            return Err(CdpError::StaleHandle);
+        }
+        self.tab.check_object_origin(&self.remote_object_id).await?;
+        let params = CallFunctionOnParams::builder()
+            .function_declaration(format!(
+                "function(other) {{
+                    const document = node => node.ownerDocument || node;
+                    if (document(this) !== document(other)) {{
+                        return undefined;
+                    }}
+                    return ({function_declaration}).call(this, other);
+                }}"
+            ))
+            .object_id(self.remote_object_id.clone())
+            .argument(
+                CallArgument::builder()
+                    .object_id(other.remote_object_id.clone())
+                    .build(),
+            )
+            .return_by_value(return_by_value)
+            .build()
+            .unwrap();
+        let resp = match self.tab.execute(params).await {
+            Ok(resp) => resp.result,
+            // handles of different frames live in different execution contexts
+            Err(CdpError::Chrome(err)) if err.message.contains("same JavaScript world") => {
+                return Err(CdpError::CrossDocumentComparison)
+            }
+            Err(err) => return Err(err),
+        };
+        if let Some(exception) = resp.exception_details {
+            return Err(CdpError::JavascriptException(Box::new(exception)));
+        }
+        if resp.result.r#type == RemoteObjectType::Undefined {
+            return Err(CdpError::CrossDocumentComparison);
+        }
+        Ok(resp.result)
+    }
+
     /// Returns a JSON representation of this element.
     pub async fn json_value(&self) -> Result<serde_json::Value> {
         let element_json = self
@@ -213,6 +660,90 @@ impl Element {
         Ok(self)
     }
 
+    /// Calls [blur](https://developer.mozilla.org/en-US/docs/Web/API/HTMLElement/blur) on the element.
+    pub async fn blur(&self) -> Result<&Self> {
+        self.call_js_fn("function() { this.blur(); }", false)
+            .await?;
+        Ok(self)
+    }
+
+    /// Whether the element is the focused element of its document or shadow
+    /// root (`activeElement`).
+    pub async fn is_focused(&self) -> Result<bool> {
+        let resp = self
+            .call_js_fn(
+                "function() { return this.getRootNode().activeElement === this; }",
+                false,
+            )
+            .await?;
+        if let Some(exception) = resp.exception_details {
+            return Err(CdpError::JavascriptException(Box::new(exception)));
+        }
+        Ok(resp
+            .result
+            .value
+            .and_then(|value| value.as_bool())
+            .unwrap_or_default())
+    }
+
+    /// Dispatches the `event` on the element and returns `false` if the event
+    /// is cancelable and a handler called `preventDefault()`.
+    ///
+    /// A `detail_object` of the event, see
+    /// [`JsCustomEvent::detail_object`](crate::js::event::JsCustomEvent::detail_object),
+    /// is passed by reference, so the handlers receive the live object.
+    ///
+    /// # Example trigger a click handler
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::element::Element;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::js::event::JsMouseEvent;
+    /// # async fn demo(element: Element) -> Result<()> {
+    ///     element.dispatch_event(JsMouseEvent::new("click")).await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn dispatch_event(&self, event: impl Into<JsEvent>) -> Result<bool> {
+        let event = event.into();
+        let objects = self.tab.objects();
+        if objects.is_released(&self.remote_object_id)
+            || event
+                .detail_object
+                .as_ref()
+                .is_some_and(|id| objects.is_released(id))
+        {
+            return Err(CdpError::StaleHandle);
+        }
+        let mut detail = CallArgument::builder();
+        if let Some(object_id) = event.detail_object {
+            detail = detail.object_id(object_id);
+        }
+        self.tab.check_object_origin(&self.remote_object_id).await?;
+        let params = CallFunctionOnParams::builder()
+            .function_declaration(
+                "function(constructor, type, init, detail) {
+                    if (detail !== undefined) {
+                        init = { ...init, detail };
+                    }
+                    return this.dispatchEvent(new window[constructor](type, init));
+                }",
+            )
+            .object_id(self.remote_object_id.clone())
+            .argument(CallArgument::builder().value(event.constructor).build())
+            .argument(CallArgument::builder().value(event.event_type).build())
+            .argument(CallArgument::builder().value(event.init).build())
+            .argument(detail.build())
+            .return_by_value(true)
+            .build()
+            .unwrap();
+        let resp = self.tab.execute(params).await?.result;
+        if let Some(exception) = resp.exception_details {
+            return Err(CdpError::JavascriptException(Box::new(exception)));
+        }
+        Ok(value_or_null(resp.result)?)
+    }
+
     /// Scrolls the element into view and uses a mouse event to move the mouse
     /// over the center of this element.
     pub async fn hover(&self) -> Result<&Self> {
@@ -261,122 +792,1144 @@ impl Element {
         Ok(self)
     }
 
-    /// This focuses the element by click on it
-    ///
-    /// Bear in mind that if `click()` triggers a navigation this element may be
-    /// not exist anymore.
-    pub async fn click(&self) -> Result<&Self> {
-        let center = self.scroll_into_view().await?.clickable_point().await?;
-        self.tab.click(center).await?;
-        Ok(self)
-    }
-
-    /// Type the input
+    /// Scrolls the element into view with the `options` and waits until the
+    /// scrolling came to a halt, which matters for `smooth` scrolling that
+    /// would otherwise still be animating.
     ///
-    /// # Example type text into an input element
-    ///
-    /// ```no_run
-    /// # use chromiumoxide::page::Page;
-    /// # use chromiumoxide::error::Result;
-    /// # async fn demo(page: Page) -> Result<()> {
-    ///     let element = page.find_element("input#searchInput").await?;
-    ///     element.click().await?.type_str("this goes into the input field").await?;
-    ///     # Ok(())
-    /// # }
-    /// ```
-    pub async fn type_str(&self, input: impl AsRef<str>) -> Result<&Self> {
-        self.tab.type_str(input).await?;
-        Ok(self)
-    }
-
-    /// Presses the key.
+    /// Fails with [`CdpError::Timeout`] if the scroll position did not settle
+    /// within `timeout`.
     ///
-    /// # Example type text into an input element and hit enter
+    /// # Example
     ///
     /// ```no_run
     /// # use chromiumoxide::page::Page;
     /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::element::{ScrollBehavior, ScrollIntoViewOptions, ScrollLogicalPosition};
+    /// # use std::time::Duration;
     /// # async fn demo(page: Page) -> Result<()> {
-    ///     let element = page.find_element("input#searchInput").await?;
-    ///     element.click().await?.type_str("this goes into the input field").await?
-    ///          .press_key("Enter").await?;
+    ///     let options = ScrollIntoViewOptions::builder()
+    ///         .behavior(ScrollBehavior::Smooth)
+    ///         .block(ScrollLogicalPosition::Center)
+    ///         .build();
+    ///     page.find_element("footer")
+    ///         .await?
+    ///         .scroll_into_view_and_wait(options, Duration::from_secs(5))
+    ///         .await?;
     ///     # Ok(())
     /// # }
     /// ```
-    pub async fn press_key(&self, key: impl AsRef<str>) -> Result<&Self> {
-        self.tab.press_key(key).await?;
-        Ok(self)
+    pub async fn scroll_into_view_and_wait(
+        &self,
+        options: ScrollIntoViewOptions,
+        timeout: Duration,
+    ) -> Result<&Self> {
+        let resp = self
+            .call_js_fn(
+                format!(
+                    "function() {{
+                        this.scrollIntoView({});
+                        return {};
+                    }}",
+                    serde_json::to_string(&options)?,
+                    utils::wait_for_scroll_end(
+                        "(r => [r.left, r.top])(this.getBoundingClientRect())",
+                        timeout
+                    )
+                ),
+                true,
+            )
+            .await?;
+        if let Some(exception) = resp.exception_details {
+            return Err(CdpError::JavascriptException(Box::new(exception)));
+        }
+        match resp.result.value.and_then(|v| v.as_bool()) {
+            Some(true) => Ok(self),
+            _ => Err(CdpError::Timeout),
+        }
     }
 
-    /// The description of the element's node
-    pub async fn description(&self) -> Result<Node> {
-        Ok(self
+    /// The accessibility node of this element, `None` if there is none.
+    async fn ax_node(&self) -> Result<Option<AxNode>> {
+        let nodes = self
             .tab
             .execute(
-                DescribeNodeParams::builder()
+                GetPartialAxTreeParams::builder()
                     .backend_node_id(self.backend_node_id)
-                    .depth(100)
+                    .fetch_relatives(false)
                     .build(),
             )
             .await?
             .result
-            .node)
-    }
-
-    /// Attributes of the `Element` node in the form of flat array `[name1,
-    /// value1, name2, value2]
-    pub async fn attributes(&self) -> Result<Vec<String>> {
-        let node = self.description().await?;
-        Ok(node.attributes.unwrap_or_default())
-    }
-
-    /// Returns the value of the element's attribute
-    pub async fn attribute(&self, attribute: impl AsRef<str>) -> Result<Option<String>> {
-        let js_fn = format!(
-            "function() {{ return this.getAttribute('{}'); }}",
-            attribute.as_ref()
-        );
-        let resp = self.call_js_fn(js_fn, false).await?;
-        if let Some(value) = resp.result.value {
-            Ok(serde_json::from_value(value)?)
-        } else {
-            Ok(None)
-        }
+            .nodes;
+        Ok(nodes
+            .into_iter()
+            .find(|node| node.backend_dom_node_id == Some(self.backend_node_id)))
     }
 
-    /// A `Stream` over all attributes and their values
-    pub async fn iter_attributes(
-        &self,
-    ) -> Result<impl Stream<Item = (String, Result<Option<String>>)> + '_> {
-        let attributes = self.attributes().await?;
-        Ok(AttributeStream {
-            attributes,
-            fut: None,
-            element: self,
-        })
+    /// The role of the element as computed for the accessibility tree, like
+    /// `button` or `heading`, `None` if the element is not part of the tree.
+    pub async fn computed_role(&self) -> Result<Option<String>> {
+        Ok(self
+            .ax_node()
+            .await?
+            .and_then(|node| node.role?.value?.as_str().map(str::to_string)))
     }
 
-    /// The inner text of this element.
-    pub async fn inner_text(&self) -> Result<Option<String>> {
-        self.string_property("innerText").await
+    /// The accessible name of the element as computed for the accessibility
+    /// tree, e.g. from its `aria-label`, its `<label>` or its text, `None` if
+    /// the element is not part of the tree or has no name.
+    pub async fn accessible_name(&self) -> Result<Option<String>> {
+        Ok(self
+            .ax_node()
+            .await?
+            .and_then(|node| node.name?.value?.as_str().map(str::to_string))
+            .filter(|name| !name.is_empty()))
     }
 
-    /// The inner HTML of this element.
-    pub async fn inner_html(&self) -> Result<Option<String>> {
-        self.string_property("innerHTML").await
+    /// Whether this is a custom element, either autonomous or a customized
+    /// built-in one with an `is` attribute, whose definition is registered
+    /// and which has been upgraded to it.
+    pub async fn is_defined_custom_element(&self) -> Result<bool> {
+        let defined = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() {
+                const name = this.getAttribute('is') || this.localName;
+                const registry = (this.ownerDocument.defaultView || window).customElements;
+                const ctor = name.includes('-') ? registry.get(name) : undefined;
+                return !!ctor && this instanceof ctor;
+            }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(defined)?)
     }
 
-    /// The outer HTML of this element.
-    pub async fn outer_html(&self) -> Result<Option<String>> {
-        self.string_property("outerHTML").await
+    /// Waits until this custom element is defined and upgraded, see
+    /// [`Element::is_defined_custom_element`].
+    ///
+    /// Elements that are not connected to the document are upgraded
+    /// explicitly once the definition is available. Fails if this is not a
+    /// custom element, if the upgrade failed, e.g. because the constructor
+    /// threw, and with [`CdpError::Timeout`] if the element is not defined
+    /// within `timeout`.
+    pub async fn wait_for_upgrade(&self, timeout: Duration) -> Result<&Self> {
+        let state = await_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function(timeout) {
+                const name = this.getAttribute('is') || this.localName;
+                if (!name.includes('-')) {
+                    return 'invalid';
+                }
+                const registry = (this.ownerDocument.defaultView || window).customElements;
+                const upgraded = () => {
+                    const ctor = registry.get(name);
+                    return !!ctor && this instanceof ctor;
+                };
+                return Promise.race([
+                    registry.whenDefined(name).then(() => {
+                        if (!upgraded()) {
+                            registry.upgrade(this);
+                        }
+                        return upgraded() ? 'upgraded' : 'failed';
+                    }),
+                    new Promise(resolve => setTimeout(() => resolve('timeout'), timeout)),
+                ]);
+            }",
+            vec![(timeout.as_millis() as u64).into()],
+        )
+        .await?;
+        match value_or_null::<String>(state)?.as_str() {
+            "upgraded" => Ok(self),
+            "timeout" => Err(CdpError::Timeout),
+            "invalid" => Err(CdpError::msg("Element is not a custom element")),
+            _ => Err(CdpError::msg("Failed to upgrade the custom element")),
+        }
     }
 
-    /// Returns the string property of the element.
+    /// Waits until this element is removed from its document, i.e. until
+    /// `isConnected` is `false`, like a modal that closes.
     ///
-    /// If the property is an empty String, `None` is returned.
-    pub async fn string_property(&self, property: impl AsRef<str>) -> Result<Option<String>> {
-        let property = property.as_ref();
-        let value = self.property(property).await?.ok_or(CdpError::NotFound)?;
+    /// The document and the shadow roots the element is nested in are
+    /// watched with a `MutationObserver`, so removing any ancestor counts.
+    /// Fails with [`CdpError::Timeout`] if the element is still connected
+    /// after `timeout`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use std::time::Duration;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let modal = page.find_element(".modal").await?;
+    ///     modal.find_element("button.close").await?.click().await?;
+    ///     modal.wait_until_removed(Duration::from_secs(5)).await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_until_removed(&self, timeout: Duration) -> Result<()> {
+        let removed = await_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function(timeout) {
+                if (!this.isConnected) {
+                    return true;
+                }
+                return new Promise(resolve => {
+                    const observers = [];
+                    let timer;
+                    const done = removed => {
+                        observers.forEach(observer => observer.disconnect());
+                        clearTimeout(timer);
+                        resolve(removed);
+                    };
+                    const check = () => {
+                        if (!this.isConnected) {
+                            done(true);
+                        }
+                    };
+                    for (let root = this.getRootNode(); root; root = root.host && root.host.getRootNode()) {
+                        const observer = new MutationObserver(check);
+                        observer.observe(root, { childList: true, subtree: true });
+                        observers.push(observer);
+                    }
+                    timer = setTimeout(() => done(false), timeout);
+                });
+            }",
+            vec![(timeout.as_millis() as u64).into()],
+        )
+        .await?;
+        if value_or_null(removed)? {
+            Ok(())
+        } else {
+            Err(CdpError::Timeout)
+        }
+    }
+
+    /// Whether the element is rendered visibly, which is the case if all of
+    /// these hold:
+    ///
+    /// - the element is connected to the document,
+    /// - `checkVisibility({ visibilityProperty: true, contentVisibilityAuto: true })`
+    ///   is `true`, i.e. neither it nor an ancestor has `display: none` or is
+    ///   skipped by `content-visibility`, and its `visibility` is `visible`,
+    /// - its computed `display` is not `none` and its computed `visibility`
+    ///   is neither `hidden` nor `collapse`,
+    /// - its bounding client rect has a non-zero width and height.
+    ///
+    /// The opacity, the position relative to the viewport and whether other
+    /// elements cover it are not taken into account, see
+    /// [`Element::wait_for_intersection`] for the latter.
+    pub async fn is_visible(&self) -> Result<bool> {
+        let visible = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() {
+                if (!this.isConnected) {
+                    return false;
+                }
+                if (typeof this.checkVisibility === 'function'
+                    && !this.checkVisibility({
+                        visibilityProperty: true,
+                        contentVisibilityAuto: true,
+                    })) {
+                    return false;
+                }
+                const style = this.ownerDocument.defaultView.getComputedStyle(this);
+                if (style.display === 'none'
+                    || style.visibility === 'hidden'
+                    || style.visibility === 'collapse') {
+                    return false;
+                }
+                const rect = this.getBoundingClientRect();
+                return rect.width > 0 && rect.height > 0;
+            }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(visible)?)
+    }
+
+    /// Waits until the visible ratio of the element crosses the threshold of
+    /// the `options` and returns the intersection at that moment.
+    ///
+    /// This installs an `IntersectionObserver` in the page, which reports the
+    /// current intersection right away, so an element that already satisfies
+    /// the threshold resolves immediately. The observer is disconnected once
+    /// the threshold was crossed or the timeout elapsed, even if the returned
+    /// future is dropped before.
+    ///
+    /// Fails with [`CdpError::Timeout`] if the threshold was not crossed
+    /// within the timeout of the `options`.
+    ///
+    /// # Example wait until most of an image is on screen
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::element::{Element, IntersectionOptions};
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(element: Element) -> Result<()> {
+    ///     let info = element
+    ///         .wait_for_intersection(IntersectionOptions::new(0.6))
+    ///         .await?;
+    ///     assert!(info.intersection_ratio >= 0.6);
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_for_intersection(
+        &self,
+        options: IntersectionOptions,
+    ) -> Result<IntersectionInfo> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Entry {
+            intersection_ratio: f64,
+            is_intersecting: bool,
+            bounding_client_rect: [f64; 4],
+            intersection_rect: [f64; 4],
+            root_bounds: Option<[f64; 4]>,
+        }
+        fn bounding_box([x, y, width, height]: [f64; 4]) -> BoundingBox {
+            BoundingBox {
+                x,
+                y,
+                width,
+                height,
+            }
+        }
+
+        if !(0. ..=1.).contains(&options.threshold) {
+            return Err(CdpError::msg(format!(
+                "Intersection threshold must be between 0 and 1, got {}",
+                options.threshold
+            )));
+        }
+        // the reported ratio may be slightly below the threshold it crossed,
+        // hence the tolerance
+        let js_fn = format!(
+            "function() {{
+                const threshold = {};
+                const enter = {};
+                return new Promise(resolve => {{
+                    const rect = r => r && [r.x, r.y, r.width, r.height];
+                    const done = entry => {{
+                        clearTimeout(timer);
+                        observer.disconnect();
+                        resolve(entry);
+                    }};
+                    const observer = new IntersectionObserver(entries => {{
+                        const entry = entries[entries.length - 1];
+                        const visible = entry.isIntersecting
+                            && entry.intersectionRatio >= threshold - 1e-3;
+                        if (visible === enter) {{
+                            done({{
+                                intersectionRatio: entry.intersectionRatio,
+                                isIntersecting: entry.isIntersecting,
+                                boundingClientRect: rect(entry.boundingClientRect),
+                                intersectionRect: rect(entry.intersectionRect),
+                                rootBounds: rect(entry.rootBounds),
+                            }});
+                        }}
+                    }}, {{ threshold: [threshold], rootMargin: {} }});
+                    const timer = setTimeout(() => done(null), {});
+                    observer.observe(this);
+                }});
+            }}",
+            options.threshold,
+            options.direction == IntersectionDirection::Enter,
+            serde_json::to_string(&options.root_margin)?,
+            options.timeout.as_millis()
+        );
+        let resp = self.call_js_fn(js_fn, true).await?;
+        if let Some(exception) = resp.exception_details {
+            return Err(CdpError::JavascriptException(Box::new(exception)));
+        }
+        let entry: Option<Entry> = resp.result.value.map(serde_json::from_value).transpose()?;
+        let entry = entry.ok_or(CdpError::Timeout)?;
+        Ok(IntersectionInfo {
+            intersection_ratio: entry.intersection_ratio,
+            is_intersecting: entry.is_intersecting,
+            bounding_client_rect: bounding_box(entry.bounding_client_rect),
+            intersection_rect: bounding_box(entry.intersection_rect),
+            root_bounds: entry.root_bounds.map(bounding_box),
+        })
+    }
+
+    /// Returns the `scrollLeft` and `scrollTop` of the element's own content.
+    pub async fn scroll_position(&self) -> Result<(f64, f64)> {
+        let position = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() { return [this.scrollLeft, this.scrollTop]; }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(position)?)
+    }
+
+    /// This focuses the element by click on it
+    ///
+    /// Bear in mind that if `click()` triggers a navigation this element may be
+    /// not exist anymore.
+    pub async fn click(&self) -> Result<&Self> {
+        let center = self.scroll_into_view().await?.clickable_point().await?;
+        self.tab.click(center).await?;
+        Ok(self)
+    }
+
+    /// Dispatches a `paste` event on the element whose `clipboardData`
+    /// holds the `data`, like pasting from the clipboard would.
+    ///
+    /// This only dispatches the event, the browser does not insert the
+    /// content by itself. Returns whether a listener canceled the event,
+    /// which is what listeners that handle the paste usually do.
+    ///
+    /// # Example paste an image
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::js::data_transfer::DataTransferInit;
+    /// # async fn demo(page: Page, png: Vec<u8>) -> Result<()> {
+    ///     let editor = page.find_element("#editor").await?;
+    ///     editor
+    ///         .dispatch_paste(DataTransferInit::new().file("image.png", "image/png", png))
+    ///         .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn dispatch_paste(&self, data: DataTransferInit) -> Result<bool> {
+        let canceled = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            &format!(
+                "function(init) {{
+                    const clipboardData = ({NEW_DATA_TRANSFER_FN})(init);
+                    return !this.dispatchEvent(new ClipboardEvent('paste', {{
+                        clipboardData,
+                        bubbles: true,
+                        cancelable: true,
+                        composed: true,
+                    }}));
+                }}"
+            ),
+            vec![serde_json::to_value(data)?],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(canceled)?)
+    }
+
+    /// Drops the `data` onto the element at the `position`, which defaults
+    /// to the center of the element, by dispatching `dragenter`, `dragover`
+    /// and `drop` events whose `dataTransfer` holds the `data`.
+    ///
+    /// The position is in viewport coordinates, like the ones of
+    /// [`Element::clickable_point`]. Returns whether a listener canceled the
+    /// `drop` event, which is what drop zones usually do.
+    pub async fn dispatch_drop(
+        &self,
+        data: DataTransferInit,
+        position: Option<Point>,
+    ) -> Result<bool> {
+        let position = position.map(|p| [p.x, p.y]);
+        let canceled = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            &format!(
+                "function(init, position) {{
+                    const dataTransfer = ({NEW_DATA_TRANSFER_FN})(init);
+                    const rect = this.getBoundingClientRect();
+                    const [clientX, clientY] = position
+                        || [rect.left + rect.width / 2, rect.top + rect.height / 2];
+                    const dispatch = type => this.dispatchEvent(new DragEvent(type, {{
+                        dataTransfer,
+                        clientX,
+                        clientY,
+                        bubbles: true,
+                        cancelable: true,
+                        composed: true,
+                    }}));
+                    dispatch('dragenter');
+                    dispatch('dragover');
+                    return !dispatch('drop');
+                }}"
+            ),
+            vec![serde_json::to_value(data)?, serde_json::to_value(position)?],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(canceled)?)
+    }
+
+    /// Type the input
+    ///
+    /// # Example type text into an input element
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let element = page.find_element("input#searchInput").await?;
+    ///     element.click().await?.type_str("this goes into the input field").await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn type_str(&self, input: impl AsRef<str>) -> Result<&Self> {
+        self.tab.type_str(input).await?;
+        Ok(self)
+    }
+
+    /// Focuses the element and types the text with real key events, so
+    /// `keydown`, `keypress`, `input` and `keyup` listeners of the element
+    /// fire for every character.
+    ///
+    /// Unlike [`Element::type_str`] this accepts any text: line breaks press
+    /// `Enter` and characters without a key, like `é`, are inserted with
+    /// an `input` event only.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.find_element("textarea")
+    ///         .await?
+    ///         .type_text("Dear café owner,\nthanks!")
+    ///         .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn type_text(&self, text: impl AsRef<str>) -> Result<&Self> {
+        self.focus().await?;
+        self.tab.type_text(text.as_ref()).await?;
+        Ok(self)
+    }
+
+    /// Presses the key.
+    ///
+    /// # Example type text into an input element and hit enter
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let element = page.find_element("input#searchInput").await?;
+    ///     element.click().await?.type_str("this goes into the input field").await?
+    ///          .press_key("Enter").await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn press_key(&self, key: impl AsRef<str>) -> Result<&Self> {
+        self.tab.press_key(key).await?;
+        Ok(self)
+    }
+
+    /// The description of the element's node
+    pub async fn description(&self) -> Result<Node> {
+        Ok(self
+            .tab
+            .execute(
+                DescribeNodeParams::builder()
+                    .backend_node_id(self.backend_node_id)
+                    .depth(100)
+                    .build(),
+            )
+            .await?
+            .result
+            .node)
+    }
+
+    /// Attributes of the `Element` node in the form of flat array `[name1,
+    /// value1, name2, value2]
+    pub async fn attributes(&self) -> Result<Vec<String>> {
+        let node = self.description().await?;
+        Ok(node.attributes.unwrap_or_default())
+    }
+
+    /// Returns the value of the element's attribute
+    pub async fn attribute(&self, attribute: impl AsRef<str>) -> Result<Option<String>> {
+        let js_fn = format!(
+            "function() {{ return this.getAttribute('{}'); }}",
+            attribute.as_ref()
+        );
+        let resp = self.call_js_fn(js_fn, false).await?;
+        if let Some(value) = resp.result.value {
+            Ok(serde_json::from_value(value)?)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Sets all the given attributes of the element in a single call.
+    ///
+    /// Each pair is applied with `Element.setAttribute(name, value)` in the
+    /// given order. An empty value sets the attribute to the empty string (this
+    /// is how boolean attributes like `disabled` are enabled), it does not
+    /// remove the attribute.
+    pub async fn set_attributes(&self, attrs: &[(&str, &str)]) -> Result<()> {
+        let js_fn = format!(
+            "function() {{
+                for (const [name, value] of {}) {{
+                    this.setAttribute(name, value);
+                }}
+            }}",
+            serde_json::to_string(attrs)?
+        );
+        let resp = self.call_js_fn(js_fn, false).await?;
+        if let Some(exception) = resp.exception_details {
+            return Err(CdpError::JavascriptException(Box::new(exception)));
+        }
+        Ok(())
+    }
+
+    /// Returns the `data-*` attributes of the element as read from its
+    /// `dataset`, keyed by their `camelCase` property names.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::element::Element;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(element: Element) -> Result<()> {
+    ///     // <li data-product-id="42" data-price="9.99">
+    ///     let data = element.dataset().await?;
+    ///     assert_eq!(data["productId"], "42");
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn dataset(&self) -> Result<HashMap<String, String>> {
+        let dataset = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() { return Object.assign({}, this.dataset); }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(value_or_null::<Option<_>>(dataset)?.unwrap_or_default())
+    }
+
+    /// Returns the value of the `data-*` attribute `key`, if set.
+    ///
+    /// The key may be given in `snake_case` or `kebab-case` and is converted
+    /// to the `camelCase` of `dataset`, so `product_id` reads
+    /// `data-product-id`.
+    pub async fn data(&self, key: impl AsRef<str>) -> Result<Option<String>> {
+        let value = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function(key) { return this.dataset[key] ?? null; }",
+            vec![utils::dataset_key(key.as_ref()).into()],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(value)?)
+    }
+
+    /// Sets the `data-*` attribute `key` to `value`, converting the key like
+    /// [`Element::data`].
+    pub async fn set_data(&self, key: impl AsRef<str>, value: impl AsRef<str>) -> Result<&Self> {
+        call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function(key, value) { this.dataset[key] = value; }",
+            vec![
+                utils::dataset_key(key.as_ref()).into(),
+                value.as_ref().into(),
+            ],
+            false,
+        )
+        .await?;
+        Ok(self)
+    }
+
+    /// Returns the text of the `<caption>` of this table, if any.
+    pub async fn table_caption(&self) -> Result<Option<String>> {
+        let caption = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() { return this.caption ? this.caption.textContent.trim() : null; }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(caption)?)
+    }
+
+    /// Returns the `<thead>` of this table, if any.
+    pub async fn table_head(&self) -> Result<Option<Element>> {
+        Ok(self
+            .find_elements(":scope > thead")
+            .await?
+            .into_iter()
+            .next())
+    }
+
+    /// Returns the `<tbody>` elements of this table.
+    pub async fn table_bodies(&self) -> Result<Vec<Element>> {
+        self.find_elements(":scope > tbody").await
+    }
+
+    /// Returns the rows of this table, including those of its `<thead>`,
+    /// `<tbody>` and `<tfoot>` elements, but not of nested tables.
+    pub async fn table_rows(&self) -> Result<Vec<Element>> {
+        self.find_elements(
+            ":scope > tr, :scope > thead > tr, :scope > tbody > tr, :scope > tfoot > tr",
+        )
+        .await
+    }
+
+    /// Converts the content of the element into Markdown, e.g. to feed a
+    /// page to a language model as compact text.
+    ///
+    /// Headings, paragraphs, lists, block quotes, emphasis, inline code and
+    /// code blocks, tables and horizontal rules are converted. Links and
+    /// images keep their absolute URLs, images without a URL their alt
+    /// text. Hidden elements, scripts and form controls are left out. Text
+    /// is not escaped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::js::markdown::MarkdownOptions;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let markdown = page
+    ///         .find_element("body")
+    ///         .await?
+    ///         .to_markdown(MarkdownOptions::new().skip_landmarks(true).max_length(20_000))
+    ///         .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn to_markdown(&self, options: MarkdownOptions) -> Result<String> {
+        let output = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            TO_MARKDOWN_FN,
+            vec![serde_json::to_value(options)?],
+            true,
+        )
+        .await?;
+        let output: MarkdownOutput = value_or_null(output)?;
+        Ok(output.finish(&options))
+    }
+
+    async fn table(&self, options: TableOptions) -> Result<TableMatrix> {
+        let matrix = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            TABLE_MATRIX_FN,
+            vec![serde_json::to_value(options)?],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(matrix)?)
+    }
+
+    /// Returns the trimmed text of all cells of this table, row by row, in a
+    /// single round trip.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::js::table::TableOptions;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let table = page.find_element("table#prices").await?;
+    ///     for row in table.table_matrix(TableOptions::new().expand_spans(true)).await? {
+    ///         println!("{}", row.join("\t"));
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn table_matrix(&self, options: TableOptions) -> Result<Vec<Vec<String>>> {
+        Ok(self.table(options).await?.rows)
+    }
+
+    /// Returns the column names of this table, the last row of its `<thead>`
+    /// or its first row if there is none.
+    pub async fn table_headers(&self, options: TableOptions) -> Result<Vec<String>> {
+        Ok(self.table(options).await?.headers())
+    }
+
+    /// Returns the rows after the [headers](Element::table_headers) of this
+    /// table as maps from column name to cell text.
+    ///
+    /// Rows with fewer cells than headers are padded with empty strings,
+    /// cells without a header are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::js::table::TableOptions;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let table = page.find_element("table#prices").await?;
+    ///     for record in table.table_records(TableOptions::new().expand_spans(true)).await? {
+    ///         println!("{} costs {}", record["Product"], record["Price"]);
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn table_records(
+        &self,
+        options: TableOptions,
+    ) -> Result<Vec<HashMap<String, String>>> {
+        Ok(self.table(options).await?.records())
+    }
+
+    /// Waits until the value of the element's attribute `name` satisfies the
+    /// `predicate` and returns that value.
+    ///
+    /// The predicate is called with the current value (`None` if the
+    /// attribute is not set) and again after every change of the attribute,
+    /// which is observed with a `MutationObserver` in the page. The observer
+    /// is disconnected once the value changed, the timeout elapsed or the
+    /// returned future is dropped.
+    ///
+    /// Returns [`CdpError::Timeout`] if the predicate was not satisfied within
+    /// the `timeout`.
+    ///
+    /// # Example wait for a menu to expand
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::element::Element;
+    /// # use chromiumoxide::error::Result;
+    /// # use std::time::Duration;
+    /// # async fn demo(element: Element) -> Result<()> {
+    ///     element
+    ///         .wait_for_attribute(
+    ///             "aria-expanded",
+    ///             |value| value == Some("true"),
+    ///             Duration::from_secs(5),
+    ///         )
+    ///         .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_for_attribute(
+        &self,
+        name: impl AsRef<str>,
+        predicate: impl Fn(Option<&str>) -> bool,
+        timeout: Duration,
+    ) -> Result<Option<String>> {
+        #[derive(Deserialize)]
+        struct Change {
+            value: Option<String>,
+        }
+
+        let name = serde_json::to_string(name.as_ref())?;
+        let deadline = Instant::now() + timeout;
+        let mut value = self
+            .call_js_fn(
+                format!("function() {{ return this.getAttribute({name}); }}"),
+                false,
+            )
+            .await?
+            .result
+            .value
+            .map(serde_json::from_value)
+            .transpose()?;
+
+        while !predicate(value.as_deref()) {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(CdpError::Timeout);
+            }
+            // resolves with the new value as soon as it differs from the last
+            // one, or with `null` if the time is up or the wait was canceled
+            let wait = PendingWait::new(self);
+            let js_fn = format!(
+                "function() {{
+                    return new Promise(resolve => {{
+                        const name = {name};
+                        const last = {};
+                        const token = {};
+                        const waits = globalThis[Symbol.for('chromiumoxide.waits')] ??= new Map();
+                        const done = change => {{
+                            clearTimeout(timer);
+                            observer.disconnect();
+                            waits.delete(token);
+                            resolve(change);
+                        }};
+                        const check = () => {{
+                            const value = this.getAttribute(name);
+                            if (value !== last) {{
+                                done({{ value }});
+                            }}
+                        }};
+                        const observer = new MutationObserver(check);
+                        const timer = setTimeout(() => done(null), {});
+                        waits.set(token, () => done(null));
+                        observer.observe(this, {{ attributes: true, attributeFilter: [name] }});
+                        // the attribute may have changed before the observer
+                        // was installed
+                        check();
+                    }});
+                }}",
+                serde_json::to_string(&value)?,
+                serde_json::to_string(&wait.token)?,
+                remaining.as_millis()
+            );
+            let resp = self.call_js_fn(js_fn, true).await?;
+            wait.settled();
+            if let Some(exception) = resp.exception_details {
+                return Err(CdpError::JavascriptException(Box::new(exception)));
+            }
+            let change: Option<Change> =
+                resp.result.value.map(serde_json::from_value).transpose()?;
+            value = change.ok_or(CdpError::Timeout)?.value;
+        }
+        Ok(value)
+    }
+
+    /// Waits until the attribute `name` has the `value`, `None` waiting for
+    /// the attribute to be removed, see [`Element::wait_for_attribute`].
+    pub async fn wait_for_attribute_value(
+        &self,
+        name: impl AsRef<str>,
+        value: Option<&str>,
+        timeout: Duration,
+    ) -> Result<&Self> {
+        self.wait_for_attribute(name, |current| current == value, timeout)
+            .await?;
+        Ok(self)
+    }
+
+    /// Waits until the element has the class `class_name` if `present`, or
+    /// doesn't have it otherwise, and returns the `class` attribute at that
+    /// point.
+    ///
+    /// See [`Element::wait_for_attribute`] for how changes are observed.
+    ///
+    /// # Example wait for an image to be loaded
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::element::Element;
+    /// # use chromiumoxide::error::Result;
+    /// # use std::time::Duration;
+    /// # async fn demo(element: Element) -> Result<()> {
+    ///     element
+    ///         .wait_for_class("loaded", true, Duration::from_secs(5))
+    ///         .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_for_class(
+        &self,
+        class_name: impl AsRef<str>,
+        present: bool,
+        timeout: Duration,
+    ) -> Result<Option<String>> {
+        let class_name = class_name.as_ref();
+        self.wait_for_attribute(
+            "class",
+            |classes| {
+                classes
+                    .unwrap_or_default()
+                    .split_ascii_whitespace()
+                    .any(|class| class == class_name)
+                    == present
+            },
+            timeout,
+        )
+        .await
+    }
+
+    /// A `Stream` over all attributes and their values
+    pub async fn iter_attributes(
+        &self,
+    ) -> Result<impl Stream<Item = (String, Result<Option<String>>)> + '_> {
+        let attributes = self.attributes().await?;
+        Ok(AttributeStream {
+            attributes,
+            fut: None,
+            element: self,
+        })
+    }
+
+    /// The inner text of this element.
+    pub async fn inner_text(&self) -> Result<Option<String>> {
+        self.string_property("innerText").await
+    }
+
+    /// The inner HTML of this element.
+    pub async fn inner_html(&self) -> Result<Option<String>> {
+        self.string_property("innerHTML").await
+    }
+
+    /// The outer HTML of this element.
+    pub async fn outer_html(&self) -> Result<Option<String>> {
+        self.string_property("outerHTML").await
+    }
+
+    /// Replaces the content of this element with the parsed `html` and
+    /// returns once the new nodes are in place.
+    ///
+    /// After the assignment a microtask is awaited, so that mutation
+    /// observers and custom element reactions of the page ran before
+    /// subsequent queries see the new nodes. Returns the new
+    /// `childElementCount`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let list = page.find_element("ul").await?;
+    ///     list.set_inner_html_and_wait("<li>one</li><li>two</li>").await?;
+    ///     let items = list.find_elements("li").await?;
+    ///     assert_eq!(items.len(), 2);
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_inner_html_and_wait(&self, html: impl AsRef<str>) -> Result<u32> {
+        let count = await_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "async function(html) {
+                this.innerHTML = html;
+                await Promise.resolve();
+                return this.childElementCount;
+            }",
+            vec![html.as_ref().into()],
+        )
+        .await?;
+        Ok(value_or_null(count)?)
+    }
+
+    /// A live handle to the classes of this element, its `classList`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let classes = page.find_element("#menu").await?.class_list().await?;
+    ///     if !classes.toggle("open", None).await? {
+    ///         classes.add(["closed"]).await?;
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn class_list(&self) -> Result<JsDomTokenList> {
+        let list = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() { return this.classList; }",
+            vec![],
+            false,
+        )
+        .await?;
+        let remote_object_id = list
+            .object_id
+            .ok_or_else(|| CdpError::msg("No object Id found for DOMTokenList"))?;
+        Ok(JsDomTokenList::new(Arc::clone(&self.tab), remote_object_id)
+            .with_description(list.description))
+    }
+
+    /// The content of this `<template>` element, `None` for other elements.
+    pub async fn template_content(&self) -> Result<Option<JsDocumentFragment>> {
+        let content = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() {
+                return this instanceof this.ownerDocument.defaultView.HTMLTemplateElement
+                    ? this.content
+                    : null;
+            }",
+            vec![],
+            false,
+        )
+        .await?;
+        Ok(content.object_id.map(|id| {
+            JsDocumentFragment::new(Arc::clone(&self.tab), id).with_description(content.description)
+        }))
+    }
+
+    /// Moves the children of the `fragment` to the end of this element in a
+    /// single operation, which leaves the fragment empty.
+    ///
+    /// To insert the content of a `<template>` more than once, append a
+    /// [clone](JsDocumentFragment::clone_fragment) instead.
+    pub async fn append_fragment(&self, fragment: &JsDocumentFragment) -> Result<&Self> {
+        if self.tab.objects().is_released(&self.remote_object_id)
+            || self.tab.objects().is_released(&fragment.remote_object_id)
+        {
+            return Err(CdpError::StaleHandle);
+        }
+        self.tab.check_object_origin(&self.remote_object_id).await?;
+        let params = CallFunctionOnParams::builder()
+            .function_declaration("function(fragment) { this.append(fragment); }")
+            .object_id(self.remote_object_id.clone())
+            .argument(
+                CallArgument::builder()
+                    .object_id(fragment.remote_object_id.clone())
+                    .build(),
+            )
+            .build()
+            .unwrap();
+        let resp = self.tab.execute(params).await?.result;
+        if let Some(exception) = resp.exception_details {
+            return Err(CdpError::JavascriptException(Box::new(exception)));
+        }
+        Ok(self)
+    }
+
+    /// Replaces this element with the parsed `html` and returns the first
+    /// element that took its place, if any, once the new nodes are in place.
+    ///
+    /// This element is detached afterwards, so its handle no longer reflects
+    /// the document. See also [`Element::set_inner_html_and_wait`].
+    pub async fn set_outer_html_and_wait(&self, html: impl AsRef<str>) -> Result<Option<Element>> {
+        let replacement = call_function_on_with(
+            &self.tab,
+            &self.remote_object_id,
+            "async function(html) {
+                const parent = this.parentNode;
+                if (!parent) {
+                    throw new Error('Element has no parent');
+                }
+                const previous = this.previousSibling;
+                this.outerHTML = html;
+                await Promise.resolve();
+                let node = previous ? previous.nextSibling : parent.firstChild;
+                while (node && node.nodeType !== Node.ELEMENT_NODE) {
+                    node = node.nextSibling;
+                }
+                return node;
+            }",
+            vec![html.as_ref().into()],
+            false,
+            true,
+        )
+        .await?;
+        match replacement.object_id {
+            Some(object_id) => Ok(Some(
+                Element::from_object_id(Arc::clone(&self.tab), object_id).await?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the string property of the element.
+    ///
+    /// If the property is an empty String, `None` is returned.
+    pub async fn string_property(&self, property: impl AsRef<str>) -> Result<Option<String>> {
+        let property = property.as_ref();
+        let value = self.property(property).await?.ok_or(CdpError::NotFound)?;
         let txt: String = serde_json::from_value(value)?;
         if !txt.is_empty() {
             Ok(Some(txt))
@@ -395,6 +1948,164 @@ impl Element {
         Ok(resp.result.value)
     }
 
+    /// Assigns the `value` to the javascript `property` of this element.
+    ///
+    /// This does not notify event listeners, so frameworks that track form
+    /// controls through `input`/`change` events won't notice the change, see
+    /// [`Element::set_property_and_notify`].
+    pub async fn set_property(
+        &self,
+        property: impl AsRef<str>,
+        value: impl Serialize,
+    ) -> Result<&Self> {
+        call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function(name, value) { this[name] = value; }",
+            vec![property.as_ref().into(), serde_json::to_value(value)?],
+            true,
+        )
+        .await?;
+        Ok(self)
+    }
+
+    /// Assigns the `value` to the javascript `property` of this element, then
+    /// dispatches bubbling `input` and `change` events like a user edit
+    /// would.
+    ///
+    /// The value is assigned through the native setter of the element's
+    /// prototype, so that frameworks that intercept the setter on the
+    /// instance, like React does for `value`, register the change. For
+    /// `<option>` elements the events are dispatched on the enclosing
+    /// `<select>`.
+    pub async fn set_property_and_notify(
+        &self,
+        property: impl AsRef<str>,
+        value: impl Serialize,
+    ) -> Result<&Self> {
+        call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function(name, value) {
+                let proto = Object.getPrototypeOf(this);
+                let descriptor;
+                while (proto && !(descriptor = Object.getOwnPropertyDescriptor(proto, name))) {
+                    proto = Object.getPrototypeOf(proto);
+                }
+                if (descriptor && descriptor.set) {
+                    descriptor.set.call(this, value);
+                } else {
+                    this[name] = value;
+                }
+                const target = this instanceof HTMLOptionElement
+                    ? (this.closest('select') || this)
+                    : this;
+                target.dispatchEvent(new Event('input', { bubbles: true }));
+                target.dispatchEvent(new Event('change', { bubbles: true }));
+            }",
+            vec![property.as_ref().into(), serde_json::to_value(value)?],
+            true,
+        )
+        .await?;
+        Ok(self)
+    }
+
+    /// Sets the `value` of a form control and notifies listeners, see
+    /// [`Element::set_property_and_notify`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.find_element("input[name=email]")
+    ///         .await?
+    ///         .set_value_and_notify("user@example.com")
+    ///         .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_value_and_notify(&self, value: impl AsRef<str>) -> Result<&Self> {
+        self.set_property_and_notify("value", value.as_ref()).await
+    }
+
+    /// Sets the `checked` state of a checkbox or radio button and notifies
+    /// listeners, see [`Element::set_property_and_notify`].
+    pub async fn set_checked_and_notify(&self, checked: bool) -> Result<&Self> {
+        self.set_property_and_notify("checked", checked).await
+    }
+
+    /// Sets the `selected` state of an `<option>` and notifies listeners of
+    /// its `<select>`, see [`Element::set_property_and_notify`].
+    pub async fn set_selected_and_notify(&self, selected: bool) -> Result<&Self> {
+        self.set_property_and_notify("selected", selected).await
+    }
+
+    /// Focuses this `<input>` or `<textarea>` and selects its text from
+    /// `start` up to `end`, returning the selected text.
+    ///
+    /// The offsets count UTF-16 code units like JavaScript strings and are
+    /// clamped to the length of the value. Typing afterwards replaces the
+    /// selection, e.g. with [`Element::type_text`].
+    ///
+    /// Fails if the element does not support text selection, like inputs of
+    /// type `number` or `email`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     // replace "world" in "hello world"
+    ///     let input = page.find_element("input").await?;
+    ///     assert_eq!(input.set_selection_range(6, 11).await?, "world");
+    ///     input.type_text("there").await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_selection_range(&self, start: u32, end: u32) -> Result<String> {
+        self.select_range(
+            "function(start, end) { this.setSelectionRange(start, end); }",
+            start,
+            end,
+        )
+        .await
+    }
+
+    /// Focuses this `<input>` or `<textarea>` and selects all of its text,
+    /// returning it, see [`Element::set_selection_range`].
+    pub async fn select_text(&self) -> Result<String> {
+        self.select_range("function() { this.select(); }", 0, 0)
+            .await
+    }
+
+    async fn select_range(&self, select: &str, start: u32, end: u32) -> Result<String> {
+        let selected = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            &format!(
+                "function(start, end) {{
+                    const isField = this instanceof this.ownerDocument.defaultView.HTMLInputElement
+                        || this instanceof this.ownerDocument.defaultView.HTMLTextAreaElement;
+                    if (!isField || this.selectionStart === null) {{
+                        return null;
+                    }}
+                    this.focus();
+                    ({select}).call(this, start, end);
+                    return this.value.substring(this.selectionStart, this.selectionEnd);
+                }}"
+            ),
+            vec![start.into(), end.into()],
+            true,
+        )
+        .await?;
+        let selected: Option<String> = value_or_null(selected)?;
+        selected
+            .ok_or_else(|| CdpError::msg(format!("Element {self} does not support text selection")))
+    }
+
     /// Returns a map with all `PropertyDescriptor`s of this element keyed by
     /// their names
     pub async fn properties(&self) -> Result<HashMap<String, PropertyDescriptor>> {
@@ -454,6 +2165,49 @@ pub type AttributeValueFuture<'a> = Option<(
     Pin<Box<dyn Future<Output = Result<Option<String>>> + 'a>>,
 )>;
 
+/// A wait of [`Element::wait_for_attribute`] in the page, canceled when
+/// dropped before it settled, like when the future awaiting it is dropped.
+struct PendingWait<'a> {
+    element: &'a Element,
+    token: String,
+    settled: bool,
+}
+
+impl<'a> PendingWait<'a> {
+    fn new(element: &'a Element) -> Self {
+        static WAITS: AtomicUsize = AtomicUsize::new(0);
+        Self {
+            element,
+            token: WAITS.fetch_add(1, Ordering::Relaxed).to_string(),
+            settled: false,
+        }
+    }
+
+    fn settled(mut self) {
+        self.settled = true;
+    }
+}
+
+impl Drop for PendingWait<'_> {
+    fn drop(&mut self) {
+        if self.settled {
+            return;
+        }
+        self.element.tab.execute_detached(
+            CallFunctionOnParams::builder()
+                .function_declaration(
+                    "function(token) {
+                        globalThis[Symbol.for('chromiumoxide.waits')]?.get(token)?.();
+                    }",
+                )
+                .object_id(self.element.remote_object_id.clone())
+                .argument(CallArgument::builder().value(self.token.clone()).build())
+                .build()
+                .unwrap(),
+        );
+    }
+}
+
 /// Stream over all element's attributes
 #[must_use = "streams do nothing unless polled"]
 #[allow(missing_debug_implementations)]
@@ -13,6 +13,7 @@ use thiserror::Error;
 use chromiumoxide_cdp::cdp::browser_protocol::page::FrameId;
 
 use crate::handler::frame::NavigationError;
+use crate::js::DomException;
 use chromiumoxide_cdp::cdp::js_protocol::runtime::ExceptionDetails;
 
 pub type Result<T, E = CdpError> = std::result::Result<T, E>;
@@ -45,6 +46,21 @@ pub enum CdpError {
     Timeout,
     #[error("FrameId {0:?} not found.")]
     FrameNotFound(FrameId),
+    /// The frame has no execution context yet, e.g. because it did not
+    /// start loading its document
+    #[error("Execution context of frame {0:?} is not available yet.")]
+    FrameContextUnavailable(FrameId),
+    /// A segment of a deep selector matched a frame that is not reachable
+    /// through the page's session, like a cross-origin frame in another
+    /// process
+    #[error("Segment {segment} `{selector}` of a deep selector matched the unreachable frame {frame_id:?}.")]
+    CrossOriginFrame {
+        /// The index of the segment that matched the frame
+        segment: usize,
+        /// The CSS selector of the segment
+        selector: String,
+        frame_id: FrameId,
+    },
     /// Error message related to a cdp response that is not a
     /// `chromiumoxide_types::Error`
     #[error("{0}")]
@@ -61,11 +77,85 @@ pub enum CdpError {
     JavascriptException(Box<ExceptionDetails>),
     #[error("{0}")]
     Url(#[from] url::ParseError),
+    /// The origin of the execution context is not in the page's evaluation
+    /// allowlist
+    #[error("Script evaluation is not allowed in origin {0:?}.")]
+    OriginNotAllowed(String),
+    /// The remote object of a handle was released, e.g. by
+    /// `Page::release_all_objects`
+    #[error("The remote object was released and can no longer be used.")]
+    StaleHandle,
+    /// Nodes of different documents or frames were compared with each other
+    #[error("Nodes of different documents can not be compared.")]
+    CrossDocumentComparison,
+    /// A promise was to be awaited while script execution is paused by
+    /// `Page::pause_script_execution`, it would never settle
+    #[error("Can not await a promise while script execution is paused.")]
+    ScriptPaused,
+    /// An operation failed with a retryable error on every attempt of its
+    /// `RetryPolicy`
+    #[error("Gave up after {} attempts: {source}", attempts.len())]
+    RetriesExhausted {
+        /// The error of every attempt, oldest first
+        attempts: Vec<String>,
+        /// The error of the last attempt
+        source: Box<CdpError>,
+    },
+    /// The value of an evaluation is larger than the limit it may be
+    /// returned with, see `Page::evaluate_limited`
+    #[error("The value of {size} bytes exceeds the limit of {limit} bytes.")]
+    ValueTooLarge {
+        /// The size of the JSON encoded value in bytes
+        size: usize,
+        limit: usize,
+    },
+    /// The browser refused to start a media playback that was not triggered
+    /// by a user gesture, see `MediaElement::play`
+    #[error("Playback was blocked by the autoplay policy, retry with `user_gesture` enabled.")]
+    AutoplayBlocked,
+    /// The operation is not supported by the browser, e.g. in headless mode
+    #[error("Unsupported: {0}")]
+    Unsupported(String),
+    /// The browser is too old for a feature, reported before the command is
+    /// sent, see `Browser::version_info`
+    #[error("{feature} needs {needs}, but the browser is {have}.")]
+    UnsupportedByBrowser {
+        /// What was attempted, like a CDP method
+        feature: String,
+        /// The version the feature needs
+        needs: String,
+        /// The version of the browser
+        have: String,
+    },
 }
 impl CdpError {
     pub fn msg(msg: impl Into<String>) -> Self {
         CdpError::ChromeMessage(msg.into())
     }
+
+    /// The `DOMException` if this is a [`CdpError::JavascriptException`]
+    /// caused by one, which tells apart e.g. a `NotFoundError` from a
+    /// `SecurityError`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let result = page.evaluate("document.querySelector('<')").await;
+    ///     if let Some(exception) = result.as_ref().err().and_then(|e| e.dom_exception()) {
+    ///         assert_eq!(exception.name, "SyntaxError");
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub fn dom_exception(&self) -> Option<DomException> {
+        match self {
+            CdpError::JavascriptException(details) => DomException::from_exception_details(details),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Error)]
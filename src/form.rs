@@ -0,0 +1,408 @@
+//! Filling forms in one go, see [`Page::fill_form`](crate::Page::fill_form).
+
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use chromiumoxide_cdp::cdp::js_protocol::runtime::RemoteObjectId;
+
+use crate::element::Element;
+
+/// The control a [`FormField`] fills.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldTarget {
+    /// The first element of the document matching the CSS selector
+    Selector(String),
+    /// The first element matching the CSS selector in the document or, if
+    /// there is none, in its open shadow roots, searched in document order
+    Deep(String),
+    /// A resolved element of the page's main world
+    Element(RemoteObjectId),
+}
+
+impl fmt::Display for FieldTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldTarget::Selector(selector) => f.write_str(selector),
+            FieldTarget::Deep(selector) => write!(f, "deep({selector})"),
+            FieldTarget::Element(id) => write!(f, "element({})", id.inner()),
+        }
+    }
+}
+
+impl From<&str> for FieldTarget {
+    fn from(selector: &str) -> Self {
+        FieldTarget::Selector(selector.to_string())
+    }
+}
+
+impl From<String> for FieldTarget {
+    fn from(selector: String) -> Self {
+        FieldTarget::Selector(selector)
+    }
+}
+
+impl From<&Element> for FieldTarget {
+    fn from(element: &Element) -> Self {
+        FieldTarget::Element(element.remote_object_id.clone())
+    }
+}
+
+/// The value a [`FormField`] fills in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldValue {
+    /// The text of an `<input>` or `<textarea>`
+    Text(String),
+    /// The checked state of a checkbox or radio button
+    Check(bool),
+    /// Selects the `<option>` with this `value`
+    SelectValue(String),
+    /// Selects the `<option>` with this label or text
+    SelectLabel(String),
+    /// The files of an `<input type="file">`
+    File(Vec<PathBuf>),
+}
+
+/// A control and the value to fill into it, see
+/// [`Page::fill_form`](crate::Page::fill_form).
+///
+/// # Example
+///
+/// ```
+/// use chromiumoxide::form::{FieldTarget, FormField};
+///
+/// let fields = vec![
+///     FormField::text("input[name=email]", "user@example.com"),
+///     FormField::check("#terms", true),
+///     FormField::select_label("select[name=country]", "Germany"),
+///     FormField::text(FieldTarget::Deep("my-widget input".into()), "42").clear_first(false),
+/// ];
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormField {
+    pub target: FieldTarget,
+    pub value: FieldValue,
+    clear_first: bool,
+    dispatch_events: bool,
+}
+
+impl FormField {
+    /// Fills the `value` into the `target`, replacing its current value and
+    /// dispatching `input` and `change` events.
+    pub fn new(target: impl Into<FieldTarget>, value: FieldValue) -> Self {
+        Self {
+            target: target.into(),
+            value,
+            clear_first: true,
+            dispatch_events: true,
+        }
+    }
+
+    pub fn text(target: impl Into<FieldTarget>, text: impl Into<String>) -> Self {
+        Self::new(target, FieldValue::Text(text.into()))
+    }
+
+    pub fn check(target: impl Into<FieldTarget>, checked: bool) -> Self {
+        Self::new(target, FieldValue::Check(checked))
+    }
+
+    pub fn select_value(target: impl Into<FieldTarget>, value: impl Into<String>) -> Self {
+        Self::new(target, FieldValue::SelectValue(value.into()))
+    }
+
+    pub fn select_label(target: impl Into<FieldTarget>, label: impl Into<String>) -> Self {
+        Self::new(target, FieldValue::SelectLabel(label.into()))
+    }
+
+    pub fn file<I, P>(target: impl Into<FieldTarget>, paths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        Self::new(
+            target,
+            FieldValue::File(paths.into_iter().map(Into::into).collect()),
+        )
+    }
+
+    /// Whether the current value is replaced, the default. Otherwise text is
+    /// appended and the options of a `<select multiple>` stay selected.
+    pub fn clear_first(mut self, clear_first: bool) -> Self {
+        self.clear_first = clear_first;
+        self
+    }
+
+    /// Whether bubbling `input` and `change` events are dispatched like a
+    /// user edit would, the default.
+    ///
+    /// File inputs always notify their listeners.
+    pub fn dispatch_events(mut self, dispatch_events: bool) -> Self {
+        self.dispatch_events = dispatch_events;
+        self
+    }
+
+    /// The field as passed to [`fill_form_fn`], `element` is the index of
+    /// the element argument of an [`FieldTarget::Element`] target.
+    pub(crate) fn spec(&self, element: Option<usize>) -> serde_json::Value {
+        let target = match &self.target {
+            FieldTarget::Selector(selector) => serde_json::json!({ "selector": selector }),
+            FieldTarget::Deep(selector) => {
+                serde_json::json!({ "selector": selector, "deep": true })
+            }
+            FieldTarget::Element(_) => serde_json::json!({ "element": element }),
+        };
+        let (kind, value) = match &self.value {
+            FieldValue::Text(text) => ("text", text.as_str()),
+            FieldValue::Check(true) => ("check", "true"),
+            FieldValue::Check(false) => ("check", ""),
+            FieldValue::SelectValue(value) => ("selectValue", value.as_str()),
+            FieldValue::SelectLabel(label) => ("selectLabel", label.as_str()),
+            // set through the protocol beforehand, only validated here
+            FieldValue::File(_) => ("none", ""),
+        };
+        serde_json::json!({
+            "target": target,
+            "kind": kind,
+            "value": value,
+            "clearFirst": self.clear_first,
+            "dispatchEvents": self.dispatch_events,
+        })
+    }
+}
+
+/// The outcome of [`Page::fill_form`](crate::Page::fill_form), one
+/// [`FieldReport`] per field in the order of the fields.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FillReport {
+    pub fields: Vec<FieldReport>,
+}
+
+impl FillReport {
+    /// Whether every field was filled and no control reports a validation
+    /// message.
+    pub fn is_success(&self) -> bool {
+        self.fields
+            .iter()
+            .all(|field| field.status == FieldStatus::Filled && field.validation_message.is_none())
+    }
+
+    /// The fields whose control was not found.
+    pub fn not_found(&self) -> impl Iterator<Item = &FieldReport> {
+        self.fields
+            .iter()
+            .filter(|field| field.status == FieldStatus::NotFound)
+    }
+
+    /// The fields whose control reports a validation message.
+    pub fn invalid(&self) -> impl Iterator<Item = &FieldReport> {
+        self.fields
+            .iter()
+            .filter(|field| field.validation_message.is_some())
+    }
+}
+
+/// How a single field of a [`FillReport`] went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldReport {
+    pub target: FieldTarget,
+    pub status: FieldStatus,
+    /// The `validationMessage` of the control after all fields were filled,
+    /// `None` if it is valid or not subject to constraint validation
+    pub validation_message: Option<String>,
+}
+
+/// Whether a field could be filled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldStatus {
+    Filled,
+    /// No element matched the target
+    NotFound,
+    /// The element can't take the value, like text for a checkbox or a
+    /// label no option has
+    Failed(String),
+}
+
+/// The result of a field as returned by [`fill_form_fn`].
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RawFieldResult {
+    pub found: bool,
+    pub error: Option<String>,
+    pub validation_message: Option<String>,
+}
+
+impl RawFieldResult {
+    pub fn status(&self) -> FieldStatus {
+        match (&self.error, self.found) {
+            (Some(error), _) => FieldStatus::Failed(error.clone()),
+            (None, true) => FieldStatus::Filled,
+            (None, false) => FieldStatus::NotFound,
+        }
+    }
+}
+
+/// Resolves a target of [`FormField::spec`] to its element or `null`, with
+/// the element arguments in `elements`.
+const RESOLVE_TARGET_FN: &str = "(target, elements) => {
+    if (target.element !== undefined) {
+        return elements[target.element] || null;
+    }
+    if (!target.deep) {
+        return document.querySelector(target.selector);
+    }
+    const visit = (root) => {
+        const found = root.querySelector(target.selector);
+        if (found) {
+            return found;
+        }
+        for (const el of root.querySelectorAll('*')) {
+            const inner = el.shadowRoot && visit(el.shadowRoot);
+            if (inner) {
+                return inner;
+            }
+        }
+        return null;
+    };
+    return visit(document);
+}";
+
+/// Resolves the target of a single [`FormField::spec`], called with the
+/// spec.
+pub(crate) fn find_target_fn() -> String {
+    format!("function(field) {{ return ({RESOLVE_TARGET_FN})(field.target, []); }}")
+}
+
+/// Fills the [`FormField::spec`]s, called with the specs followed by the
+/// elements of their element targets. Returns a [`RawFieldResult`] per spec,
+/// validation messages are gathered after all fields are filled.
+pub(crate) fn fill_form_fn() -> String {
+    format!(
+        r#"function(fields, ...elements) {{
+    const resolveTarget = {RESOLVE_TARGET_FN};
+    const setNative = (el, name, value) => {{
+        let proto = Object.getPrototypeOf(el);
+        let descriptor;
+        while (proto && !(descriptor = Object.getOwnPropertyDescriptor(proto, name))) {{
+            proto = Object.getPrototypeOf(proto);
+        }}
+        if (descriptor && descriptor.set) {{
+            descriptor.set.call(el, value);
+        }} else {{
+            el[name] = value;
+        }}
+    }};
+    const isText = (el) => el.localName === 'textarea'
+        || (el.localName === 'input'
+            && !['checkbox', 'radio', 'file', 'button', 'submit', 'reset', 'image'].includes(el.type));
+    const select = (el, field, matches) => {{
+        if (el.localName !== 'select') {{
+            return 'not a select element';
+        }}
+        const option = Array.from(el.options).find(matches);
+        if (!option) {{
+            return `no option matches ${{JSON.stringify(field.value)}}`;
+        }}
+        if (field.clearFirst || !el.multiple) {{
+            for (const other of el.options) {{
+                setNative(other, 'selected', false);
+            }}
+        }}
+        setNative(option, 'selected', true);
+        return null;
+    }};
+    const fill = (el, field) => {{
+        switch (field.kind) {{
+            case 'text':
+                if (!isText(el)) {{
+                    return `${{el.localName}} does not take text`;
+                }}
+                setNative(el, 'value', field.clearFirst ? field.value : el.value + field.value);
+                return null;
+            case 'check':
+                if (el.localName !== 'input' || !['checkbox', 'radio'].includes(el.type)) {{
+                    return 'not a checkbox or radio button';
+                }}
+                setNative(el, 'checked', field.value === 'true');
+                return null;
+            case 'selectValue':
+                return select(el, field, (option) => option.value === field.value);
+            case 'selectLabel':
+                return select(el, field, (option) => option.label === field.value
+                    || option.text.trim() === field.value.trim());
+        }}
+        return null;
+    }};
+    const targets = fields.map((field) => resolveTarget(field.target, elements));
+    const results = fields.map((field, i) => {{
+        const el = targets[i];
+        if (!el) {{
+            return {{ found: false, error: null }};
+        }}
+        const error = fill(el, field);
+        if (!error && field.kind !== 'none' && field.dispatchEvents) {{
+            el.dispatchEvent(new Event('input', {{ bubbles: true, composed: true }}));
+            el.dispatchEvent(new Event('change', {{ bubbles: true }}));
+        }}
+        return {{ found: true, error }};
+    }});
+    return results.map((result, i) => {{
+        const el = targets[i];
+        const message = el && el.willValidate ? el.validationMessage : '';
+        return {{ ...result, validationMessage: message || null }};
+    }});
+}}"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(status: FieldStatus, validation_message: Option<&str>) -> FieldReport {
+        FieldReport {
+            target: "input".into(),
+            status,
+            validation_message: validation_message.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn spec_indexes_element_targets() {
+        let field = FormField::check(FieldTarget::Element(RemoteObjectId::new("1.2.3")), false)
+            .dispatch_events(false);
+        assert_eq!(
+            field.spec(Some(1)),
+            serde_json::json!({
+                "target": { "element": 1 },
+                "kind": "check",
+                "value": "",
+                "clearFirst": true,
+                "dispatchEvents": false,
+            })
+        );
+        let field = FormField::file(FieldTarget::Deep("input".into()), ["a.txt"]);
+        assert_eq!(
+            field.spec(None)["target"],
+            serde_json::json!({ "selector": "input", "deep": true })
+        );
+        assert_eq!(field.spec(None)["kind"], "none");
+    }
+
+    #[test]
+    fn report_summarizes_fields() {
+        let mut fill = FillReport {
+            fields: vec![report(FieldStatus::Filled, None)],
+        };
+        assert!(fill.is_success());
+
+        fill.fields.push(report(FieldStatus::NotFound, None));
+        fill.fields.push(report(
+            FieldStatus::Filled,
+            Some("Please fill out this field."),
+        ));
+        assert!(!fill.is_success());
+        assert_eq!(fill.not_found().count(), 1);
+        assert_eq!(fill.invalid().count(), 1);
+    }
+}
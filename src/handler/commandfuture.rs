@@ -6,10 +6,12 @@ use pin_project_lite::pin_project;
 use std::future::Future;
 use std::marker::PhantomData;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use crate::cmd::{to_command_response, CommandMessage};
 use crate::error::Result;
+use crate::handler::commandqueue::CommandQueue;
 use crate::handler::target::TargetMessage;
 use chromiumoxide_cdp::cdp::browser_protocol::target::SessionId;
 use chromiumoxide_types::{Command, CommandResponse, MethodId, Response};
@@ -28,6 +30,9 @@ pin_project! {
 
         message: Option<TargetMessage>,
 
+        // bounds the commands waiting for the handler, if any
+        queue: Option<Arc<CommandQueue>>,
+
         method: MethodId,
 
         _marker: PhantomData<T>
@@ -55,11 +60,18 @@ impl<T: Command> CommandFuture<T> {
             target_sender,
             rx_command,
             message,
+            queue: None,
             delay,
             method,
             _marker: PhantomData,
         })
     }
+
+    /// Waits for a free slot in the queue before the command is sent.
+    pub(crate) fn with_queue(mut self, queue: Arc<CommandQueue>) -> Self {
+        self.queue = Some(queue);
+        self
+    }
 }
 
 impl<T> Future for CommandFuture<T>
@@ -72,16 +84,36 @@ where
         let mut this = self.project();
 
         if this.message.is_some() {
-            match this.target_sender.poll_ready(cx) {
-                Poll::Ready(Err(e)) => Poll::Ready(Err(e.into())),
+            if let Some(queue) = this.queue.as_ref() {
+                if queue.poll_reserve(cx).is_pending() {
+                    return Poll::Pending;
+                }
+            }
+            let sent = match this.target_sender.poll_ready(cx) {
                 Poll::Ready(Ok(_)) => {
                     let message = this.message.take().expect("existence checked above");
-                    this.target_sender.start_send(message)?;
-
+                    this.target_sender.start_send(message)
+                }
+                Poll::Ready(Err(e)) => Err(e),
+                Poll::Pending => {
+                    // hold no slot while waiting for the channel
+                    if let Some(queue) = this.queue.as_ref() {
+                        queue.release();
+                    }
+                    return Poll::Pending;
+                }
+            };
+            match sent {
+                Ok(_) => {
                     cx.waker().wake_by_ref();
                     Poll::Pending
                 }
-                Poll::Pending => Poll::Pending,
+                Err(e) => {
+                    if let Some(queue) = this.queue.as_ref() {
+                        queue.release();
+                    }
+                    Poll::Ready(Err(e.into()))
+                }
             }
         } else if this.delay.poll(cx).is_ready() {
             Poll::Ready(Err(crate::error::CdpError::Timeout))
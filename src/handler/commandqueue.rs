@@ -0,0 +1,161 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::task::{Context, Poll, Waker};
+
+/// A snapshot of the commands of a page, see
+/// [`Page::command_stats`](crate::Page::command_stats).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CommandStats {
+    /// Commands waiting for the handler to submit them to the browser
+    pub queued: usize,
+    /// Commands submitted to the browser that did not receive a response yet
+    pub in_flight: u64,
+    /// Total number of commands submitted to the browser
+    pub sent: u64,
+    /// Total number of responses received, including timed out commands
+    pub received: u64,
+}
+
+/// Bounds the commands of a page that are waiting for the handler and keeps
+/// count of the commands that passed through.
+///
+/// A slot is reserved before a command is sent to the handler and released
+/// once the handler submits the command to the browser, so senders wait for
+/// capacity instead of queueing up without bound.
+#[derive(Debug)]
+pub(crate) struct CommandQueue {
+    /// The maximum number of queued commands, unbounded if `None`
+    depth: Option<usize>,
+    queued: AtomicUsize,
+    sent: AtomicU64,
+    received: AtomicU64,
+    /// Tasks waiting for a free slot
+    waiters: Mutex<Vec<Waker>>,
+}
+
+impl CommandQueue {
+    pub fn new(depth: Option<usize>) -> Self {
+        Self {
+            depth: depth.map(|depth| depth.max(1)),
+            queued: AtomicUsize::new(0),
+            sent: AtomicU64::new(0),
+            received: AtomicU64::new(0),
+            waiters: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Reserves a slot for a command, pending while the queue is full.
+    pub fn poll_reserve(&self, cx: &mut Context<'_>) -> Poll<()> {
+        let Some(depth) = self.depth else {
+            self.reserve_unchecked();
+            return Poll::Ready(());
+        };
+        // `release` wakes the waiters under the lock, so a slot that frees up
+        // after the check below can't be missed
+        let mut waiters = self.waiters.lock().unwrap();
+        let reserved = self
+            .queued
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |queued| {
+                (queued < depth).then_some(queued + 1)
+            });
+        if reserved.is_ok() {
+            Poll::Ready(())
+        } else {
+            waiters.push(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+
+    /// Reserves a slot for a command, waiting while the queue is full.
+    pub async fn reserve(&self) {
+        futures::future::poll_fn(|cx| self.poll_reserve(cx)).await
+    }
+
+    /// Reserves a slot for a command that can't wait, even if the queue is
+    /// full.
+    pub fn reserve_unchecked(&self) {
+        self.queued.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Frees a slot without the command being submitted to the browser.
+    pub fn release(&self) {
+        self.queued.fetch_sub(1, Ordering::SeqCst);
+        for waker in self.waiters.lock().unwrap().drain(..) {
+            waker.wake();
+        }
+    }
+
+    /// The handler submitted a queued command to the browser.
+    pub fn on_sent(&self) {
+        self.sent.fetch_add(1, Ordering::SeqCst);
+        self.release();
+    }
+
+    /// The response to a submitted command arrived or it timed out.
+    pub fn on_received(&self) {
+        self.received.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// The number of queued commands.
+    pub fn depth(&self) -> usize {
+        self.queued.load(Ordering::SeqCst)
+    }
+
+    pub fn stats(&self) -> CommandStats {
+        let received = self.received.load(Ordering::SeqCst);
+        let sent = self.sent.load(Ordering::SeqCst);
+        CommandStats {
+            queued: self.depth(),
+            in_flight: sent.saturating_sub(received),
+            sent,
+            received,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::Context;
+
+    use futures::task::noop_waker;
+
+    use super::*;
+
+    #[test]
+    fn waits_for_capacity() {
+        let queue = CommandQueue::new(Some(2));
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert!(queue.poll_reserve(&mut cx).is_ready());
+        assert!(queue.poll_reserve(&mut cx).is_ready());
+        assert!(queue.poll_reserve(&mut cx).is_pending());
+
+        queue.on_sent();
+        assert!(queue.poll_reserve(&mut cx).is_ready());
+        queue.release();
+
+        queue.on_received();
+        assert_eq!(
+            queue.stats(),
+            CommandStats {
+                queued: 1,
+                in_flight: 0,
+                sent: 1,
+                received: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn unbounded_never_waits() {
+        let queue = CommandQueue::new(None);
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        for _ in 0..1000 {
+            assert!(queue.poll_reserve(&mut cx).is_ready());
+        }
+        assert_eq!(queue.depth(), 1000);
+    }
+}
@@ -1,4 +1,9 @@
-use chromiumoxide_cdp::cdp::js_protocol::runtime::ExecutionContextId;
+use chromiumoxide_cdp::cdp::browser_protocol::page::FrameId;
+use chromiumoxide_cdp::cdp::js_protocol::runtime::{
+    ExecutionContextDescription, ExecutionContextId,
+};
+
+use crate::handler::frame::UTILITY_WORLD_NAME;
 
 #[derive(Debug, Clone, Default)]
 pub struct DOMWorld {
@@ -68,3 +73,63 @@ pub enum DOMWorldKind {
     /// Each frame gets its own isolated world with universal access
     Secondary,
 }
+
+/// Describes an execution context known to a page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionContextInfo {
+    /// The id used to address the context in `Runtime` commands
+    pub id: ExecutionContextId,
+    /// Globally unique id of the context
+    pub unique_id: String,
+    /// The origin of the document the context belongs to
+    pub origin: String,
+    /// The human readable name, the world name for isolated worlds
+    pub name: String,
+    /// The frame the context belongs to, if any
+    pub frame_id: Option<FrameId>,
+    /// Whether this is the default (main world) context of the frame
+    pub is_default: bool,
+}
+
+impl ExecutionContextInfo {
+    /// The world of the frame this context represents, `None` for contexts
+    /// not managed by chromiumoxide, like isolated worlds of extensions.
+    pub fn world(&self) -> Option<DOMWorldKind> {
+        if self.is_default {
+            Some(DOMWorldKind::Main)
+        } else if self.name == UTILITY_WORLD_NAME {
+            Some(DOMWorldKind::Secondary)
+        } else {
+            None
+        }
+    }
+}
+
+impl From<&ExecutionContextDescription> for ExecutionContextInfo {
+    fn from(context: &ExecutionContextDescription) -> Self {
+        let aux_data = context.aux_data.as_ref();
+        Self {
+            id: context.id,
+            unique_id: context.unique_id.clone(),
+            origin: context.origin.clone(),
+            name: context.name.clone(),
+            frame_id: aux_data
+                .and_then(|v| v["frameId"].as_str())
+                .map(|id| FrameId::new(id.to_string())),
+            is_default: aux_data
+                .and_then(|v| v["isDefault"].as_bool())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// A change to the execution contexts of a page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecutionContextChange {
+    /// A new context was created
+    Created(ExecutionContextInfo),
+    /// The context with the unique id was destroyed
+    Destroyed(String),
+    /// All contexts were cleared, e.g. because of a navigation
+    Cleared,
+}
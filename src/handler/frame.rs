@@ -20,7 +20,7 @@ use chromiumoxide_cdp::cdp::{
 use chromiumoxide_types::{Method, MethodId, Request};
 
 use crate::error::DeadlineExceeded;
-use crate::handler::domworld::DOMWorld;
+use crate::handler::domworld::{DOMWorld, ExecutionContextInfo};
 use crate::handler::http::HttpRequest;
 use crate::handler::REQUEST_TIMEOUT;
 use crate::{cmd::CommandChain, ArcHttpRequest};
@@ -188,6 +188,8 @@ pub struct FrameManager {
     frames: HashMap<FrameId, Frame>,
     /// The contexts mapped with their frames
     context_ids: HashMap<String, FrameId>,
+    /// All known contexts
+    contexts: HashMap<ExecutionContextId, ExecutionContextInfo>,
     isolated_worlds: HashSet<String>,
     /// Timeout after which an anticipated event (related to navigation) doesn't
     /// arrive results in an error
@@ -204,6 +206,7 @@ impl FrameManager {
             main_frame: None,
             frames: Default::default(),
             context_ids: Default::default(),
+            contexts: Default::default(),
             isolated_worlds: Default::default(),
             request_timeout,
             pending_navigations: Default::default(),
@@ -444,6 +447,8 @@ impl FrameManager {
 
     /// Issued when new execution context is created
     pub fn on_frame_execution_context_created(&mut self, event: &EventExecutionContextCreated) {
+        self.contexts
+            .insert(event.context.id, (&event.context).into());
         if let Some(frame_id) = event
             .context
             .aux_data
@@ -485,6 +490,8 @@ impl FrameManager {
 
     /// Issued when execution context is destroyed
     pub fn on_frame_execution_context_destroyed(&mut self, event: &EventExecutionContextDestroyed) {
+        self.contexts
+            .retain(|_, ctx| ctx.unique_id != event.execution_context_unique_id);
         if let Some(id) = self.context_ids.remove(&event.execution_context_unique_id) {
             if let Some(frame) = self.frames.get_mut(&id) {
                 frame.destroy_context(&event.execution_context_unique_id);
@@ -499,7 +506,20 @@ impl FrameManager {
                 frame.clear_contexts();
             }
         }
-        self.context_ids.clear()
+        self.context_ids.clear();
+        self.contexts.clear()
+    }
+
+    /// The origin of the execution context, as reported when it was created
+    pub fn execution_context_origin(&self, id: ExecutionContextId) -> Option<&str> {
+        self.contexts.get(&id).map(|ctx| ctx.origin.as_str())
+    }
+
+    /// All known execution contexts, ordered by their id
+    pub fn execution_contexts(&self) -> Vec<ExecutionContextInfo> {
+        let mut contexts: Vec<_> = self.contexts.values().cloned().collect();
+        contexts.sort_by_key(|ctx| *ctx.id.inner());
+        contexts
     }
 
     /// Fired for top level page lifecycle events (nav, load, paint, etc.)
@@ -17,6 +17,7 @@ use chromiumoxide_types::{CallId, Message, Method, Response};
 use chromiumoxide_types::{MethodId, Request as CdpRequest};
 pub(crate) use page::PageInner;
 
+use crate::browser::BrowserVersion;
 use crate::cmd::{to_command_response, CommandMessage};
 use crate::conn::Connection;
 use crate::error::{CdpError, Result};
@@ -33,8 +34,12 @@ use crate::page::Page;
 /// Standard timeout in MS
 pub const REQUEST_TIMEOUT: u64 = 30_000;
 
+/// Standard number of commands a page queues before senders have to wait
+pub const COMMAND_QUEUE_DEPTH: usize = 64;
+
 pub mod browser;
 pub mod commandfuture;
+pub mod commandqueue;
 pub mod domworld;
 pub mod emulation;
 pub mod frame;
@@ -83,6 +88,18 @@ pub struct Handler {
     event_listeners: EventListeners,
     /// Keeps track is the browser is closing
     closing: bool,
+    /// The version of the browser, requested when the handler is created
+    version: Option<BrowserVersion>,
+    /// Whether a request for the version is in flight
+    version_pending: bool,
+    /// Waiting for the version to arrive
+    version_waiters: Vec<OneshotSender<Result<BrowserVersion>>>,
+    /// The maximum number of commands in flight per session, if limited
+    max_inflight_per_session: Option<usize>,
+    /// The maximum number of commands in flight over all sessions, if limited
+    max_inflight_global: Option<usize>,
+    /// The number of commands of pages awaiting a response
+    commands_in_flight: usize,
 }
 
 impl Handler {
@@ -100,6 +117,27 @@ impl Handler {
             serde_json::to_value(discover).unwrap(),
         );
 
+        let mut pending_commands = FnvHashMap::default();
+        let get_version = GetVersionParams::default();
+        let version_pending = match conn.submit_command(
+            get_version.identifier(),
+            None,
+            serde_json::to_value(&get_version).unwrap(),
+        ) {
+            Ok(call_id) => {
+                pending_commands.insert(
+                    call_id,
+                    (
+                        PendingRequest::GetVersion,
+                        get_version.identifier(),
+                        Instant::now(),
+                    ),
+                );
+                true
+            }
+            Err(_) => false,
+        };
+
         let browser_contexts = config
             .context_ids
             .iter()
@@ -107,7 +145,7 @@ impl Handler {
             .collect();
 
         Self {
-            pending_commands: Default::default(),
+            pending_commands,
             from_browser: rx.fuse(),
             default_browser_context: Default::default(),
             browser_contexts,
@@ -121,9 +159,21 @@ impl Handler {
             config,
             event_listeners: Default::default(),
             closing: false,
+            version: None,
+            version_pending,
+            version_waiters: Vec::new(),
+            max_inflight_per_session: None,
+            max_inflight_global: None,
+            commands_in_flight: 0,
         }
     }
 
+    /// The version of the browser, once the response to the request sent
+    /// when connecting arrived
+    pub fn version_info(&self) -> Option<&BrowserVersion> {
+        self.version.as_ref()
+    }
+
     /// Return the target with the matching `target_id`
     pub fn get_target(&self, target_id: &TargetId) -> Option<&Target> {
         self.targets.get(target_id)
@@ -240,7 +290,8 @@ impl Handler {
                 PendingRequest::Navigate(id) => {
                     self.on_navigation_response(id, resp);
                 }
-                PendingRequest::ExternalCommand(tx) => {
+                PendingRequest::ExternalCommand(tx, target_id) => {
+                    self.on_external_command_done(target_id);
                     let _ = tx.send(Ok(resp)).ok();
                 }
                 PendingRequest::InternalCommand(target_id) => {
@@ -252,26 +303,113 @@ impl Handler {
                     self.closing = true;
                     let _ = tx.send(Ok(CloseReturns {})).ok();
                 }
+                PendingRequest::GetVersion => {
+                    let version = to_command_response::<GetVersionParams>(resp, method)
+                        .map(|resp| BrowserVersion::from(resp.result));
+                    self.on_version(version);
+                }
+            }
+        }
+    }
+
+    /// Received the version of the browser or failed to.
+    fn on_version(&mut self, version: Result<BrowserVersion>) {
+        self.version_pending = false;
+        match version {
+            Ok(version) => {
+                for tx in self.version_waiters.drain(..) {
+                    let _ = tx.send(Ok(version.clone()));
+                }
+                for target in self.targets.values_mut() {
+                    target.set_browser_version(&version);
+                }
+                self.version = Some(version);
+            }
+            Err(err) => {
+                let msg = err.to_string();
+                for tx in self.version_waiters.drain(..) {
+                    let _ = tx.send(Err(CdpError::msg(msg.clone())));
+                }
+            }
+        }
+    }
+
+    /// Answers with the known version or requests it (again, if the last
+    /// request failed).
+    fn submit_get_version(&mut self, tx: OneshotSender<Result<BrowserVersion>>, now: Instant) {
+        if let Some(version) = &self.version {
+            let _ = tx.send(Ok(version.clone()));
+            return;
+        }
+        self.version_waiters.push(tx);
+        if self.version_pending {
+            return;
+        }
+        let msg = GetVersionParams::default();
+        let method = msg.identifier();
+        match self
+            .conn
+            .submit_command(method.clone(), None, serde_json::to_value(msg).unwrap())
+        {
+            Ok(call_id) => {
+                self.version_pending = true;
+                self.pending_commands
+                    .insert(call_id, (PendingRequest::GetVersion, method, now));
             }
+            Err(err) => self.on_version(Err(err.into())),
         }
     }
 
-    /// Submit a command initiated via channel
+    /// Submit a command initiated via channel, by the page of the target if
+    /// any
     pub(crate) fn submit_external_command(
         &mut self,
         msg: CommandMessage,
+        target_id: Option<TargetId>,
         now: Instant,
     ) -> Result<()> {
         let call_id = self
             .conn
             .submit_command(msg.method.clone(), msg.session_id, msg.params)?;
+        if target_id.is_some() {
+            self.commands_in_flight += 1;
+        }
         self.pending_commands.insert(
             call_id,
-            (PendingRequest::ExternalCommand(msg.sender), msg.method, now),
+            (
+                PendingRequest::ExternalCommand(msg.sender, target_id),
+                msg.method,
+                now,
+            ),
         );
         Ok(())
     }
 
+    /// A command of the target's page received its response or timed out
+    fn on_external_command_done(&mut self, target_id: Option<TargetId>) {
+        let Some(target_id) = target_id else {
+            return;
+        };
+        self.commands_in_flight = self.commands_in_flight.saturating_sub(1);
+        if let Some(target) = self.targets.get_mut(&target_id) {
+            target.on_command_done();
+        }
+    }
+
+    /// How many more commands the target's page may submit right now
+    fn command_budget(&self, target: &Target) -> Option<usize> {
+        let per_session = self
+            .max_inflight_per_session
+            .map(|max| max.saturating_sub(target.commands_in_flight()));
+        let global = self
+            .max_inflight_global
+            .map(|max| max.saturating_sub(self.commands_in_flight));
+        match (per_session, global) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
     pub(crate) fn submit_internal_command(
         &mut self,
         target_id: TargetId,
@@ -346,8 +484,11 @@ impl Handler {
                 id,
                 NavigationRequest::Navigate(NavigationInProgress::new(tx)),
             );
-        } else {
-            let _ = self.submit_external_command(msg, now);
+        } else if self
+            .submit_external_command(msg, Some(target.target_id().clone()), now)
+            .is_err()
+        {
+            target.on_command_done();
         }
     }
 
@@ -434,9 +575,11 @@ impl Handler {
             TargetConfig {
                 ignore_https_errors: self.config.ignore_https_errors,
                 request_timeout: self.config.request_timeout,
+                command_queue_depth: self.config.command_queue_depth,
                 viewport: self.config.viewport.clone(),
                 request_intercept: self.config.request_intercept,
                 cache_enabled: self.config.cache_enabled,
+                browser_version: self.version.clone(),
             },
             browser_ctx,
         );
@@ -504,13 +647,20 @@ impl Handler {
                             }
                         }
                     }
-                    PendingRequest::ExternalCommand(tx) => {
+                    PendingRequest::ExternalCommand(tx, target_id) => {
+                        self.on_external_command_done(target_id);
                         let _ = tx.send(Err(CdpError::Timeout));
                     }
                     PendingRequest::InternalCommand(_) => {}
                     PendingRequest::CloseBrowser(tx) => {
                         let _ = tx.send(Err(CdpError::Timeout));
                     }
+                    PendingRequest::GetVersion => {
+                        self.version_pending = false;
+                        for tx in self.version_waiters.drain(..) {
+                            let _ = tx.send(Err(CdpError::Timeout));
+                        }
+                    }
                 }
             }
         }
@@ -535,7 +685,7 @@ impl Stream for Handler {
             while let Poll::Ready(Some(msg)) = Pin::new(&mut pin.from_browser).poll_next(cx) {
                 match msg {
                     HandlerMessage::Command(cmd) => {
-                        pin.submit_external_command(cmd, now)?;
+                        pin.submit_external_command(cmd, None, now)?;
                     }
                     HandlerMessage::FetchTargets(tx) => {
                         pin.submit_fetch_targets(tx, now);
@@ -543,6 +693,9 @@ impl Stream for Handler {
                     HandlerMessage::CloseBrowser(tx) => {
                         pin.submit_close(tx, now);
                     }
+                    HandlerMessage::GetVersionInfo(tx) => {
+                        pin.submit_get_version(tx, now);
+                    }
                     HandlerMessage::CreatePage(params, tx) => {
                         pin.create_page(params, tx);
                     }
@@ -573,12 +726,20 @@ impl Stream for Handler {
                     HandlerMessage::AddEventListener(req) => {
                         pin.event_listeners.add_listener(req);
                     }
+                    HandlerMessage::SetMaxInflightCommands {
+                        per_session,
+                        global,
+                    } => {
+                        pin.max_inflight_per_session = per_session;
+                        pin.max_inflight_global = global;
+                    }
                 }
             }
 
             for n in (0..pin.target_ids.len()).rev() {
                 let target_id = pin.target_ids.swap_remove(n);
                 if let Some((id, mut target)) = pin.targets.remove_entry(&target_id) {
+                    target.set_command_budget(pin.command_budget(&target));
                     while let Some(event) = target.poll(cx, now) {
                         match event {
                             TargetEvent::Request(req) => {
@@ -660,6 +821,9 @@ pub struct HandlerConfig {
     pub request_intercept: bool,
     /// Whether to enable cache
     pub cache_enabled: bool,
+    /// How many commands a page queues before senders have to wait, unbounded
+    /// if `None`
+    pub command_queue_depth: Option<usize>,
 }
 
 impl Default for HandlerConfig {
@@ -671,6 +835,7 @@ impl Default for HandlerConfig {
             request_timeout: Duration::from_millis(REQUEST_TIMEOUT),
             request_intercept: false,
             cache_enabled: true,
+            command_queue_depth: Some(COMMAND_QUEUE_DEPTH),
         }
     }
 }
@@ -730,13 +895,16 @@ enum PendingRequest {
     /// after the `Target` notifies the `Handler` that the `Page` has finished
     /// loading, which comes after the response.
     Navigate(NavigationId),
-    /// A common request received via a channel (`Page`).
-    ExternalCommand(OneshotSender<Result<Response>>),
+    /// A common request received via a channel (`Page`), together with the
+    /// `Target` of the page that sent it.
+    ExternalCommand(OneshotSender<Result<Response>>, Option<TargetId>),
     /// Requests that are initiated directly from a `Target` (all the
     /// initialization commands).
     InternalCommand(TargetId),
     // A Request to close the browser.
     CloseBrowser(OneshotSender<Result<CloseReturns>>),
+    /// A Request for the version of the browser, see `Handler::version_info`.
+    GetVersion,
 }
 
 /// Events used internally to communicate with the handler, which are executed
@@ -753,4 +921,9 @@ pub(crate) enum HandlerMessage {
     GetPage(TargetId, OneshotSender<Option<Page>>),
     AddEventListener(EventListenerRequest),
     CloseBrowser(OneshotSender<Result<CloseReturns>>),
+    GetVersionInfo(OneshotSender<Result<BrowserVersion>>),
+    SetMaxInflightCommands {
+        per_session: Option<usize>,
+        global: Option<usize>,
+    },
 }
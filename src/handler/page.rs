@@ -1,42 +1,67 @@
-use std::sync::Arc;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::Duration;
 
-use futures::channel::mpsc::{channel, Receiver, Sender};
+use futures::channel::mpsc::{channel, unbounded, Receiver, Sender};
 use futures::channel::oneshot::channel as oneshot_channel;
 use futures::stream::Fuse;
 use futures::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
 
-use chromiumoxide_cdp::cdp::browser_protocol::browser::{GetVersionParams, GetVersionReturns};
+use chromiumoxide_cdp::cdp::browser_protocol::browser::{
+    GetVersionParams, GetVersionReturns, GrantPermissionsParams, PermissionType,
+};
 use chromiumoxide_cdp::cdp::browser_protocol::dom::{
-    DiscardSearchResultsParams, GetSearchResultsParams, NodeId, PerformSearchParams,
-    QuerySelectorAllParams, QuerySelectorParams, Rgba,
+    DescribeNodeParams, DiscardSearchResultsParams, GetSearchResultsParams, NodeId,
+    PerformSearchParams, QuerySelectorAllParams, QuerySelectorParams, RequestNodeParams, Rgba,
 };
 use chromiumoxide_cdp::cdp::browser_protocol::emulation::{
     ClearDeviceMetricsOverrideParams, SetDefaultBackgroundColorOverrideParams,
-    SetDeviceMetricsOverrideParams,
+    SetDeviceMetricsOverrideParams, SetPageScaleFactorParams,
 };
 use chromiumoxide_cdp::cdp::browser_protocol::input::{
     DispatchKeyEventParams, DispatchKeyEventType, DispatchMouseEventParams, DispatchMouseEventType,
-    MouseButton,
+    InsertTextParams, MouseButton,
 };
 use chromiumoxide_cdp::cdp::browser_protocol::page::{
-    FrameId, GetLayoutMetricsParams, GetLayoutMetricsReturns, Viewport,
+    AddScriptToEvaluateOnNewDocumentParams, BringToFrontParams, CaptureScreenshotFormat,
+    CaptureScreenshotParams, FrameId, GetFrameTreeParams, GetLayoutMetricsParams,
+    GetLayoutMetricsReturns, RemoveScriptToEvaluateOnNewDocumentParams, ScriptIdentifier, Viewport,
+};
+use chromiumoxide_cdp::cdp::browser_protocol::target::{
+    ActivateTargetParams, GetTargetInfoParams, SessionId, TargetId,
+};
+use chromiumoxide_cdp::cdp::js_protocol::debugger::{
+    self, CallFrameId, EvaluateOnCallFrameParams, EventPaused, PauseParams, ScopeType,
 };
-use chromiumoxide_cdp::cdp::browser_protocol::target::{ActivateTargetParams, SessionId, TargetId};
 use chromiumoxide_cdp::cdp::js_protocol::runtime::{
-    CallFunctionOnParams, CallFunctionOnReturns, EvaluateParams, ExecutionContextId, RemoteObjectId,
+    CallArgument, CallFunctionOnParams, CallFunctionOnReturns, EvaluateParams, ExecutionContextId,
+    ReleaseObjectParams, RemoteObject, RemoteObjectId,
 };
+use chromiumoxide_cdp::cdp::IntoEventKind;
 use chromiumoxide_types::{Command, CommandResponse};
 
+use crate::browser::BrowserVersion;
 use crate::cmd::{to_command_response, CommandMessage};
 use crate::error::{CdpError, Result};
 use crate::handler::commandfuture::CommandFuture;
-use crate::handler::domworld::DOMWorldKind;
+use crate::handler::commandqueue::CommandQueue;
+use crate::handler::domworld::{DOMWorldKind, ExecutionContextInfo};
 use crate::handler::httpfuture::HttpFuture;
-use crate::handler::target::{GetExecutionContext, TargetMessage};
+use crate::handler::target::{GetExecutionContext, GetUrl, TargetMessage};
 use crate::handler::target_message_future::TargetMessageFuture;
-use crate::js::EvaluationResult;
+use crate::handler::REQUEST_TIMEOUT;
+use crate::js::cache::EvalCache;
+use crate::js::registry::ObjectRegistry;
+use crate::js::{
+    call_function_on, call_function_on_with, value_or_null, Evaluation, EvaluationResult,
+};
 use crate::layout::Point;
-use crate::page::ScreenshotParams;
+use crate::listeners::{EventListenerRequest, EventStream};
+use crate::origin::{object_context_id, EvalOriginAllowlist};
+use crate::page::{InitScriptHandle, ScreenshotParams};
+use crate::retry::{RetryPolicy, RetryState};
 use crate::{keys, utils, ArcHttpRequest};
 
 #[derive(Debug)]
@@ -46,13 +71,30 @@ pub struct PageHandle {
 }
 
 impl PageHandle {
-    pub fn new(target_id: TargetId, session_id: SessionId, opener_id: Option<TargetId>) -> Self {
+    pub fn new(
+        target_id: TargetId,
+        session_id: SessionId,
+        opener_id: Option<TargetId>,
+        command_queue_depth: Option<usize>,
+        browser_version: Option<BrowserVersion>,
+    ) -> Self {
         let (commands, rx) = channel(1);
         let page = PageInner {
             target_id,
             session_id,
             opener_id,
             sender: commands,
+            command_queue: Arc::new(CommandQueue::new(command_queue_depth)),
+            eval_origin_allowlist: Default::default(),
+            objects: Default::default(),
+            eval_cache: Default::default(),
+            page_scale_factor: AtomicU64::new(1f64.to_bits()),
+            browser_version: browser_version.map(OnceLock::from).unwrap_or_default(),
+            user_gesture_scopes: AtomicUsize::new(0),
+            script_pause: Default::default(),
+            pausing: Default::default(),
+            debugger_enabled: AtomicBool::new(false),
+            retry: Default::default(),
         };
         Self {
             rx: rx.fuse(),
@@ -71,17 +113,131 @@ pub(crate) struct PageInner {
     session_id: SessionId,
     opener_id: Option<TargetId>,
     sender: Sender<TargetMessage>,
+    /// Bounds the commands waiting for the handler
+    command_queue: Arc<CommandQueue>,
+    /// Origins evaluation is restricted to, if any
+    eval_origin_allowlist: RwLock<Option<Arc<EvalOriginAllowlist>>>,
+    /// The remote object handles tracked for diagnostics
+    objects: ObjectRegistry,
+    /// The opt-in cache of by-value evaluation results
+    eval_cache: EvalCache,
+    /// The page scale factor set via `set_page_scale_factor`, as `f64` bits
+    page_scale_factor: AtomicU64,
+    /// The version of the browser, passed down by the handler that requests it
+    /// when connecting
+    browser_version: OnceLock<BrowserVersion>,
+    /// The number of active `with_user_gesture` scopes
+    user_gesture_scopes: AtomicUsize,
+    /// The state of `Page::pause_script_execution`
+    script_pause: Mutex<ScriptPause>,
+    /// Held while a pause is established, later callers wait for it
+    pausing: futures::lock::Mutex<()>,
+    /// Whether the Debugger domain was enabled with `Page::enable_debugger`
+    debugger_enabled: AtomicBool,
+    /// The retry policy and the counters of retried operations
+    retry: RetryState,
+}
+
+/// Script execution paused by `Page::pause_script_execution`.
+#[derive(Debug, Default)]
+struct ScriptPause {
+    /// The number of active `PauseGuard`s
+    guards: usize,
+    /// The top call frame while paused, evaluations run on it
+    call_frame_id: Option<CallFrameId>,
+    /// The context of the global scope of the top call frame, which may
+    /// belong to another frame than the main one
+    context_id: Option<ExecutionContextId>,
 }
 
 impl PageInner {
     /// Execute a PDL command and return its response
     pub(crate) async fn execute<T: Command>(&self, cmd: T) -> Result<CommandResponse<T::Response>> {
-        execute(cmd, self.sender.clone(), Some(self.session_id.clone())).await
+        execute(
+            cmd,
+            self.sender.clone(),
+            Some(self.session_id.clone()),
+            &self.command_queue,
+        )
+        .await
+    }
+
+    /// Execute a PDL command on the browser instead of the page's session
+    pub(crate) async fn execute_browser<T: Command>(
+        &self,
+        cmd: T,
+    ) -> Result<CommandResponse<T::Response>> {
+        execute(cmd, self.sender.clone(), None, &self.command_queue).await
+    }
+
+    /// The remote object handles tracked for diagnostics
+    pub(crate) fn objects(&self) -> &ObjectRegistry {
+        &self.objects
+    }
+
+    /// The opt-in cache of by-value evaluation results
+    pub(crate) fn eval_cache(&self) -> &EvalCache {
+        &self.eval_cache
+    }
+
+    /// The retry policy and the counters of retried operations
+    pub(crate) fn retry_state(&self) -> &RetryState {
+        &self.retry
+    }
+
+    /// Runs the `operation` with the `policy`, or the page's retry policy if
+    /// `None`. An operation that `awaits_promise` is not retried after its
+    /// execution context was destroyed, as it may have run already.
+    pub(crate) async fn retry<T, F, Fut>(
+        &self,
+        policy: Option<&RetryPolicy>,
+        awaits_promise: bool,
+        operation: F,
+    ) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        match policy {
+            Some(policy) => policy.run(&self.retry, awaits_promise, operation).await,
+            None => {
+                let policy = self.retry.policy();
+                policy.run(&self.retry, awaits_promise, operation).await
+            }
+        }
+    }
+
+    /// Runs the `operation` that performs the `evaluation` like
+    /// [`PageInner::retry`], but only once if the evaluation is bound to an
+    /// execution context, which can't come back.
+    pub(crate) async fn retry_evaluation<F, Fut>(
+        &self,
+        policy: Option<&RetryPolicy>,
+        evaluation: &Evaluation,
+        mut operation: F,
+    ) -> Result<EvaluationResult>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<EvaluationResult>>,
+    {
+        if evaluation.is_context_bound() {
+            return operation().await;
+        }
+        self.retry(policy, evaluation.awaits_promise(), operation)
+            .await
+    }
+
+    /// Bounds the commands waiting for the handler and keeps count of them
+    pub(crate) fn command_queue(&self) -> &CommandQueue {
+        &self.command_queue
     }
 
     /// Create a PDL command future
     pub(crate) fn command_future<T: Command>(&self, cmd: T) -> Result<CommandFuture<T>> {
-        CommandFuture::new(cmd, self.sender.clone(), Some(self.session_id.clone()))
+        Ok(
+            CommandFuture::new(cmd, self.sender.clone(), Some(self.session_id.clone()))?
+                .with_queue(Arc::clone(&self.command_queue)),
+        )
     }
 
     /// This creates navigation future with the final http response when the page is loaded
@@ -117,6 +273,58 @@ impl PageInner {
         &self.sender
     }
 
+    /// Adds a script that is evaluated in every frame upon creation, the
+    /// script is removed again when the returned handle is dropped.
+    pub(crate) async fn add_init_script(
+        self: &Arc<Self>,
+        source: impl Into<String>,
+    ) -> Result<InitScriptHandle> {
+        let identifier = self
+            .execute(AddScriptToEvaluateOnNewDocumentParams::new(source))
+            .await?
+            .result
+            .identifier;
+        Ok(InitScriptHandle::new(identifier, Arc::clone(self)))
+    }
+
+    /// Removes the init script without waiting for the response, this is
+    /// used when the handle is dropped.
+    pub(crate) fn remove_init_script_detached(&self, identifier: ScriptIdentifier) {
+        self.execute_detached(RemoveScriptToEvaluateOnNewDocumentParams::new(identifier));
+    }
+
+    /// Sends the command without waiting for the response, for cleanups in
+    /// `Drop` implementations.
+    pub(crate) fn execute_detached<T: Command>(&self, cmd: T) {
+        let (tx, _) = oneshot_channel();
+        if let Ok(msg) = CommandMessage::with_session(cmd, tx, Some(self.session_id.clone())) {
+            // a cloned sender always has a free slot, this only fails if the
+            // page is already gone. Cleanups can't wait for capacity, so they
+            // may exceed the queue depth.
+            self.command_queue.reserve_unchecked();
+            if self
+                .sender
+                .clone()
+                .try_send(TargetMessage::Command(msg))
+                .is_err()
+            {
+                self.command_queue.release();
+            }
+        }
+    }
+
+    /// Returns a stream of the events of type `T` of this page.
+    pub(crate) async fn event_listener<T: IntoEventKind>(&self) -> Result<EventStream<T>> {
+        let (tx, rx) = unbounded();
+        self.sender
+            .clone()
+            .send(TargetMessage::AddEventListener(
+                EventListenerRequest::new::<T>(tx),
+            ))
+            .await?;
+        Ok(EventStream::new(rx))
+    }
+
     /// Returns the first element in the node which matches the given CSS
     /// selector.
     pub async fn find_element(&self, selector: impl Into<String>, node: NodeId) -> Result<NodeId> {
@@ -138,6 +346,98 @@ impl PageInner {
         Ok(self.execute(GetVersionParams::default()).await?.result)
     }
 
+    /// Fails with [`CdpError::UnsupportedByBrowser`] if the browser is known
+    /// to be older than `min_major`, see [`BrowserVersion::require`].
+    ///
+    /// Passes while the handler has not received the version yet.
+    pub(crate) fn require_browser(&self, feature: &str, min_major: u32) -> Result<()> {
+        match self.browser_version.get() {
+            Some(version) => version.require(feature, min_major),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the browser is not known to be older than `min_major`.
+    pub(crate) fn browser_supports(&self, min_major: u32) -> bool {
+        self.require_browser("", min_major).is_ok()
+    }
+
+    /// Sets the version of the browser, once the handler received it.
+    pub(crate) fn set_browser_version(&self, version: BrowserVersion) {
+        let _ = self.browser_version.set(version);
+    }
+
+    /// Grants the permissions to the origin of the current document, or to
+    /// all origins if the document has none, like `about:blank`.
+    pub(crate) async fn grant_permissions(&self, permissions: Vec<PermissionType>) -> Result<()> {
+        let browser_context_id = self
+            .execute_browser(
+                GetTargetInfoParams::builder()
+                    .target_id(self.target_id().clone())
+                    .build(),
+            )
+            .await?
+            .result
+            .target_info
+            .browser_context_id;
+        let (tx, rx) = oneshot_channel();
+        self.sender()
+            .clone()
+            .send(TargetMessage::Url(GetUrl::new(tx)))
+            .await?;
+        let origin = rx
+            .await?
+            .and_then(|url| url::Url::parse(&url).ok())
+            .map(|url| url.origin())
+            .filter(|origin| origin.is_tuple())
+            .map(|origin| origin.ascii_serialization());
+        let mut grant = GrantPermissionsParams::new(permissions);
+        grant.origin = origin;
+        grant.browser_context_id = browser_context_id;
+        self.execute_browser(grant).await?;
+        Ok(())
+    }
+
+    /// Reads the text of the clipboard with `navigator.clipboard.readText()`
+    /// in the main frame.
+    ///
+    /// Grants the clipboard permissions and focuses the page first, as the
+    /// async Clipboard API requires both. It is only available in secure
+    /// contexts, so fails on plain `http` pages other than `localhost`.
+    pub async fn read_clipboard_text(&self) -> Result<String> {
+        self.prepare_clipboard().await?;
+        Ok(self
+            .with_user_gesture(|| {
+                self.evaluate_expression("window.focus(), navigator.clipboard.readText()")
+            })
+            .await?
+            .into_value()?)
+    }
+
+    /// Writes the text to the clipboard with
+    /// `navigator.clipboard.writeText(text)` in the main frame, see
+    /// [`PageInner::read_clipboard_text`].
+    pub async fn write_clipboard_text(&self, text: impl AsRef<str>) -> Result<()> {
+        self.prepare_clipboard().await?;
+        let write = format!(
+            "window.focus(), navigator.clipboard.writeText({})",
+            serde_json::to_string(text.as_ref())?
+        );
+        self.with_user_gesture(|| self.evaluate_expression(write))
+            .await?;
+        Ok(())
+    }
+
+    async fn prepare_clipboard(&self) -> Result<()> {
+        self.grant_permissions(vec![
+            PermissionType::ClipboardReadWrite,
+            PermissionType::ClipboardSanitizedWrite,
+        ])
+        .await?;
+        self.execute(BringToFrontParams::default()).await?;
+        Ok(())
+    }
+
     /// Return all `Element`s inside the node that match the given selector
     pub(crate) async fn find_elements(
         &self,
@@ -151,6 +451,103 @@ impl PageInner {
             .node_ids)
     }
 
+    /// Returns the `document` of the frame, evaluated in the main world of
+    /// the frame.
+    ///
+    /// Fails with [`CdpError::FrameContextUnavailable`] if the frame has no
+    /// execution context yet.
+    pub(crate) async fn frame_document(&self, frame_id: FrameId) -> Result<RemoteObjectId> {
+        self.frame_document_object(frame_id.clone())
+            .await?
+            .object_id
+            .ok_or(CdpError::FrameContextUnavailable(frame_id))
+    }
+
+    /// Same as [`PageInner::frame_document`], but returns the whole remote
+    /// object.
+    pub(crate) async fn frame_document_object(&self, frame_id: FrameId) -> Result<RemoteObject> {
+        let context_id = self
+            .frame_execution_context(frame_id.clone())
+            .await?
+            .ok_or_else(|| CdpError::FrameContextUnavailable(frame_id.clone()))?;
+        let document = self
+            .evaluate_expression(
+                EvaluateParams::builder()
+                    .expression("document")
+                    .context_id(context_id)
+                    .return_by_value(false)
+                    .build()
+                    .unwrap(),
+            )
+            .await?;
+        Ok(document.object().clone())
+    }
+
+    /// Returns the first element in the document of the frame which matches
+    /// the given CSS selector, `None` if there is none.
+    ///
+    /// Fails with [`CdpError::FrameContextUnavailable`] if the frame has no
+    /// execution context yet.
+    pub async fn query_selector_in_frame(
+        &self,
+        frame_id: FrameId,
+        selector: impl AsRef<str>,
+    ) -> Result<Option<NodeId>> {
+        let document = self.frame_document(frame_id).await?;
+        let element = self
+            .execute(
+                CallFunctionOnParams::builder()
+                    .function_declaration(
+                        "function(selector) { return this.querySelector(selector); }",
+                    )
+                    .object_id(document.clone())
+                    .argument(CallArgument::builder().value(selector.as_ref()).build())
+                    .build()
+                    .unwrap(),
+            )
+            .await;
+        self.execute(ReleaseObjectParams::new(document)).await?;
+        let element = element?.result;
+        if let Some(exception) = element.exception_details {
+            return Err(CdpError::JavascriptException(Box::new(exception)));
+        }
+        let Some(object_id) = element.result.object_id else {
+            return Ok(None);
+        };
+        let node_id = self
+            .execute(RequestNodeParams::new(object_id.clone()))
+            .await;
+        self.execute(ReleaseObjectParams::new(object_id)).await?;
+        Ok(Some(node_id?.result.node_id))
+    }
+
+    /// Returns all elements in the document of the frame which match the
+    /// given CSS selector.
+    ///
+    /// Fails with [`CdpError::FrameContextUnavailable`] if the frame has no
+    /// execution context yet.
+    pub(crate) async fn query_selector_all_in_frame(
+        &self,
+        frame_id: FrameId,
+        selector: impl Into<String>,
+    ) -> Result<Vec<NodeId>> {
+        let document = self.frame_document(frame_id).await?;
+        let node_id = self.execute(RequestNodeParams::new(document.clone())).await;
+        self.execute(ReleaseObjectParams::new(document)).await?;
+        self.find_elements(selector, node_id?.result.node_id).await
+    }
+
+    /// The frame whose owner is the node, like an `<iframe>`, `None` for
+    /// other nodes.
+    pub(crate) async fn owned_frame(&self, node_id: NodeId) -> Result<Option<FrameId>> {
+        Ok(self
+            .execute(DescribeNodeParams::builder().node_id(node_id).build())
+            .await?
+            .result
+            .node
+            .frame_id)
+    }
+
     /// Returns all elements which matches the given xpath selector
     pub async fn find_xpaths(&self, query: impl Into<String>) -> Result<Vec<NodeId>> {
         let perform_search_returns = self
@@ -180,6 +577,11 @@ impl PageInner {
 
     /// Moves the mouse to this point (dispatches a mouseMoved event)
     pub async fn move_mouse(&self, point: Point) -> Result<&Self> {
+        let point = self.to_input_point(point).await?;
+        self.dispatch_mouse_move(point).await
+    }
+
+    async fn dispatch_mouse_move(&self, point: Point) -> Result<&Self> {
         self.execute(DispatchMouseEventParams::new(
             DispatchMouseEventType::MouseMoved,
             point.x,
@@ -189,15 +591,38 @@ impl PageInner {
         Ok(self)
     }
 
+    /// Sets the page scale factor, `1.0` is the default.
+    pub async fn set_page_scale_factor(&self, factor: f64) -> Result<&Self> {
+        self.execute(SetPageScaleFactorParams::new(factor)).await?;
+        self.page_scale_factor
+            .store(factor.to_bits(), Ordering::Relaxed);
+        Ok(self)
+    }
+
+    /// Converts a point in layout viewport coordinates into the visual
+    /// viewport coordinates input events use, which differ once the page is
+    /// scaled.
+    async fn to_input_point(&self, point: Point) -> Result<Point> {
+        if f64::from_bits(self.page_scale_factor.load(Ordering::Relaxed)) == 1. {
+            return Ok(point);
+        }
+        let viewport = self.layout_metrics().await?.css_visual_viewport;
+        Ok(point.to_visual_viewport(
+            Point::new(viewport.offset_x, viewport.offset_y),
+            viewport.scale,
+        ))
+    }
+
     /// Performs a mouse click event at the point's location
     pub async fn click(&self, point: Point) -> Result<&Self> {
+        let point = self.to_input_point(point).await?;
         let cmd = DispatchMouseEventParams::builder()
             .x(point.x)
             .y(point.y)
             .button(MouseButton::Left)
             .click_count(1);
 
-        self.move_mouse(point)
+        self.dispatch_mouse_move(point)
             .await?
             .execute(
                 cmd.clone()
@@ -231,6 +656,28 @@ impl PageInner {
         Ok(self)
     }
 
+    /// Types the text with a key press per character, like a user typing on
+    /// a US keyboard. Line breaks press `Enter`.
+    ///
+    /// Characters without a key on the keyboard, like `é` or emoji, are
+    /// inserted with `Input.insertText`, which fires `input` but no key
+    /// events.
+    pub async fn type_text(&self, text: &str) -> Result<&Self> {
+        for c in text.chars() {
+            let key = match c {
+                '\r' => continue,
+                '\n' => "Enter".to_string(),
+                c => c.to_string(),
+            };
+            if keys::get_key_definition(&key).is_some() {
+                self.press_key(&key).await?;
+            } else {
+                self.execute(InsertTextParams::new(key)).await?;
+            }
+        }
+        Ok(self)
+    }
+
     /// Uses the `DispatchKeyEvent` mechanism to simulate pressing keyboard
     /// keys.
     pub async fn press_key(&self, key: impl AsRef<str>) -> Result<&Self> {
@@ -273,31 +720,222 @@ impl PageInner {
         await_promise: bool,
         remote_object_id: RemoteObjectId,
     ) -> Result<CallFunctionOnReturns> {
-        let resp = self
-            .execute(
-                CallFunctionOnParams::builder()
-                    .object_id(remote_object_id)
-                    .function_declaration(function_declaration)
-                    .generate_preview(true)
-                    .await_promise(await_promise)
-                    .build()
-                    .unwrap(),
-            )
-            .await?;
+        if self.objects.is_released(&remote_object_id) {
+            return Err(CdpError::StaleHandle);
+        }
+        self.check_object_origin(&remote_object_id).await?;
+        let params = CallFunctionOnParams::builder()
+            .object_id(remote_object_id)
+            .function_declaration(function_declaration)
+            .generate_preview(true)
+            .await_promise(await_promise)
+            .build()
+            .unwrap();
+        let resp = self.execute(params).await?;
         Ok(resp.result)
     }
 
+    /// Releases a temporary remote object, a failure must not hide the
+    /// result it was used for.
+    pub(crate) async fn release_best_effort(&self, object_id: RemoteObjectId) {
+        if let Err(err) = self.execute(ReleaseObjectParams::new(object_id)).await {
+            tracing::debug!("Failed to release a temporary object: {err:?}");
+        }
+    }
+
+    /// Runs the future with all evaluations on this page treated as
+    /// initiated by a user gesture, unless they set `user_gesture`
+    /// themselves.
+    pub async fn with_user_gesture<F, Fut, T>(&self, f: F) -> T
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        struct Scope<'a>(&'a AtomicUsize);
+
+        impl Drop for Scope<'_> {
+            fn drop(&mut self) {
+                self.0.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+
+        self.user_gesture_scopes.fetch_add(1, Ordering::SeqCst);
+        let _scope = Scope(&self.user_gesture_scopes);
+        f().await
+    }
+
+    /// The `user_gesture` flag to use for an evaluation that set `flag`
+    fn user_gesture(&self, flag: Option<bool>) -> Option<bool> {
+        if flag.is_none() && self.user_gesture_scopes.load(Ordering::SeqCst) > 0 {
+            Some(true)
+        } else {
+            flag
+        }
+    }
+
+    /// Pauses script execution with `Debugger.pause`, or adds a guard to
+    /// the current pause. Every call must be balanced by
+    /// [`PageInner::release_script_pause`].
+    ///
+    /// Concurrent callers wait until the first one established the pause,
+    /// so the page is paused once this returns. Fails with
+    /// [`CdpError::Timeout`] if the pause did not take effect within the
+    /// request timeout.
+    pub(crate) async fn pause_script_execution(&self) -> Result<()> {
+        let _pausing = self.pausing.lock().await;
+        {
+            let mut pause = self.script_pause.lock().unwrap();
+            if pause.guards > 0 {
+                pause.guards += 1;
+                return Ok(());
+            }
+        }
+        let timeout = Duration::from_millis(REQUEST_TIMEOUT);
+        let paused = utils::with_timeout(self.pause_on_call_frame(), timeout)
+            .await
+            .unwrap_or(Err(CdpError::Timeout));
+        match paused {
+            Ok((call_frame_id, context_id)) => {
+                let mut pause = self.script_pause.lock().unwrap();
+                pause.guards = 1;
+                pause.call_frame_id = Some(call_frame_id);
+                pause.context_id = context_id;
+                Ok(())
+            }
+            Err(err) => {
+                if self.owns_debugger() {
+                    self.execute_detached(debugger::DisableParams::default());
+                }
+                Err(err)
+            }
+        }
+    }
+
+    /// Whether the Debugger domain is enabled only for a pause and must be
+    /// disabled once it ends, so `debugger;` statements of the page don't
+    /// pause it for good.
+    pub(crate) fn owns_debugger(&self) -> bool {
+        !self.debugger_enabled.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn set_debugger_enabled(&self, enabled: bool) {
+        self.debugger_enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Pauses and returns the top call frame with the context of its global
+    /// scope.
+    async fn pause_on_call_frame(&self) -> Result<(CallFrameId, Option<ExecutionContextId>)> {
+        let mut paused = self.event_listener::<EventPaused>().await?;
+        self.execute(debugger::EnableParams::default()).await?;
+        self.execute(PauseParams::default()).await?;
+        // the pause takes effect on the next statement, this evaluation runs
+        // one if the page is idle and completes once resumed
+        self.execute_detached(EvaluateParams::new("void 0"));
+        let event = paused.next().await.ok_or(CdpError::NoResponse)?;
+        let frame = event
+            .call_frames
+            .first()
+            .ok_or_else(|| CdpError::msg("Paused without a call frame"))?;
+        let context_id = frame
+            .scope_chain
+            .iter()
+            .find(|scope| scope.r#type == ScopeType::Global)
+            .and_then(|scope| scope.object.object_id.as_ref())
+            .and_then(object_context_id);
+        Ok((frame.call_frame_id.clone(), context_id))
+    }
+
+    /// Removes a guard of the pause, returns whether it was the last one and
+    /// script execution needs to be resumed.
+    pub(crate) fn release_script_pause(&self) -> bool {
+        let mut pause = self.script_pause.lock().unwrap();
+        pause.guards = pause.guards.saturating_sub(1);
+        if pause.guards == 0 {
+            pause.call_frame_id = None;
+            pause.context_id = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whether script execution is paused by `Page::pause_script_execution`
+    pub(crate) fn is_script_paused(&self) -> bool {
+        self.script_pause.lock().unwrap().guards > 0
+    }
+
+    /// The `await_promise` flag to use for an evaluation that set `flag`,
+    /// promises of a paused page never settle.
+    fn await_promise(&self, flag: Option<bool>) -> Result<Option<bool>> {
+        if !self.is_script_paused() {
+            return Ok(flag.or(Some(true)));
+        }
+        match flag {
+            Some(true) => Err(CdpError::ScriptPaused),
+            _ => Ok(Some(false)),
+        }
+    }
+
+    /// Evaluates the expression on the top call frame of the pause, in the
+    /// context of its global scope.
+    async fn evaluate_on_call_frame(
+        &self,
+        call_frame_id: CallFrameId,
+        context_id: Option<ExecutionContextId>,
+        evaluate: EvaluateParams,
+    ) -> Result<EvaluationResult> {
+        self.check_eval_origin(context_id).await?;
+        let mut params = EvaluateOnCallFrameParams::new(call_frame_id, evaluate.expression);
+        params.object_group = evaluate.object_group;
+        params.include_command_line_api = evaluate.include_command_line_api;
+        params.silent = evaluate.silent;
+        params.return_by_value = evaluate.return_by_value.or(Some(true));
+        params.generate_preview = evaluate.generate_preview;
+        params.throw_on_side_effect = evaluate.throw_on_side_effect;
+        params.timeout = evaluate.timeout;
+        let resp = self.execute(params).await?.result;
+        if let Some(exception) = resp.exception_details {
+            return Err(CdpError::JavascriptException(Box::new(exception)));
+        }
+        Ok(EvaluationResult::new(resp.result))
+    }
+
     pub async fn evaluate_expression(
         &self,
         evaluate: impl Into<EvaluateParams>,
     ) -> Result<EvaluationResult> {
-        let mut evaluate = evaluate.into();
+        let evaluate = evaluate.into();
+        let evaluation = Evaluation::Expression(evaluate.clone());
+        self.retry_evaluation(None, &evaluation, || {
+            self.evaluate_expression_once(evaluate.clone())
+        })
+        .await
+    }
+
+    /// Same as [`PageInner::evaluate_expression`] but attempted only once.
+    pub(crate) async fn evaluate_expression_once(
+        &self,
+        mut evaluate: EvaluateParams,
+    ) -> Result<EvaluationResult> {
+        evaluate.user_gesture = self.user_gesture(evaluate.user_gesture);
+        evaluate.await_promise = self.await_promise(evaluate.await_promise)?;
+        // evaluations in the page's context run on the paused call frame, as
+        // the call frame determines what is in scope
         if evaluate.context_id.is_none() {
-            evaluate.context_id = self.execution_context().await?;
+            let (call_frame_id, context_id) = {
+                let pause = self.script_pause.lock().unwrap();
+                (pause.call_frame_id.clone(), pause.context_id)
+            };
+            if let Some(call_frame_id) = call_frame_id {
+                return self
+                    .evaluate_on_call_frame(call_frame_id, context_id, evaluate)
+                    .await;
+            }
         }
-        if evaluate.await_promise.is_none() {
-            evaluate.await_promise = Some(true);
+        if evaluate.context_id.is_none() {
+            evaluate.context_id = self.execution_context().await?;
         }
+        self.check_eval_origin(evaluate.context_id).await?;
         if evaluate.return_by_value.is_none() {
             evaluate.return_by_value = Some(true);
         }
@@ -310,17 +948,88 @@ impl PageInner {
         Ok(EvaluationResult::new(resp.result))
     }
 
+    /// Same as [`PageInner::evaluate_expression`] but answers from the
+    /// evaluation cache if enabled.
+    ///
+    /// Only by-value results are cached, evaluations that return remote
+    /// objects, carry a user gesture or run while script execution is paused
+    /// are always sent to the browser. The
+    /// key includes the execution context and the generation of the cache,
+    /// which is bumped whenever the main frame gets a new document, so a
+    /// navigation never serves results of the previous document.
+    pub async fn evaluate_cached(
+        &self,
+        evaluate: impl Into<EvaluateParams>,
+    ) -> Result<EvaluationResult> {
+        let mut evaluate = evaluate.into();
+        evaluate.user_gesture = self.user_gesture(evaluate.user_gesture);
+        if !self.eval_cache.is_enabled()
+            || evaluate.return_by_value == Some(false)
+            || evaluate.user_gesture == Some(true)
+            || self.is_script_paused()
+        {
+            return self.evaluate_expression(evaluate).await;
+        }
+        if evaluate.context_id.is_none() {
+            evaluate.context_id = self.execution_context().await?;
+        }
+        if evaluate.context_id.is_none() {
+            return self.evaluate_expression(evaluate).await;
+        }
+        evaluate.return_by_value = Some(true);
+
+        // the generation is taken before the evaluation, a result that
+        // arrives after a navigation is stored under the previous one
+        let key = format!(
+            "{}:{}",
+            self.eval_cache.generation(),
+            serde_json::to_string(&evaluate)?
+        );
+        if let Some(value) = self.eval_cache.get(&key) {
+            return Ok(EvaluationResult::new(value));
+        }
+        let result = self.evaluate_expression(evaluate).await?;
+        self.eval_cache.insert(key, result.object().clone());
+        Ok(result)
+    }
+
     pub async fn evaluate_function(
         &self,
         evaluate: impl Into<CallFunctionOnParams>,
     ) -> Result<EvaluationResult> {
-        let mut evaluate = evaluate.into();
-        if evaluate.execution_context_id.is_none() {
-            evaluate.execution_context_id = self.execution_context().await?;
+        let evaluate = evaluate.into();
+        let evaluation = Evaluation::Function(evaluate.clone());
+        self.retry_evaluation(None, &evaluation, || {
+            self.evaluate_function_once(evaluate.clone())
+        })
+        .await
+    }
+
+    /// Same as [`PageInner::evaluate_function`] but attempted only once.
+    pub(crate) async fn evaluate_function_once(
+        &self,
+        mut evaluate: CallFunctionOnParams,
+    ) -> Result<EvaluationResult> {
+        evaluate.user_gesture = self.user_gesture(evaluate.user_gesture);
+        if let Some(object_id) = &evaluate.object_id {
+            if self.objects.is_released(object_id) {
+                return Err(CdpError::StaleHandle);
+            }
         }
-        if evaluate.await_promise.is_none() {
-            evaluate.await_promise = Some(true);
+        let context_id = match evaluate.execution_context_id {
+            Some(context_id) => Some(context_id),
+            None => self.execution_context().await?,
+        };
+        // a call on an object runs in the context of the object, the protocol
+        // rejects a context id next to the object id
+        if evaluate.object_id.is_none() {
+            evaluate.execution_context_id = context_id;
+        }
+        match &evaluate.object_id {
+            Some(object_id) => self.check_object_origin(object_id).await?,
+            None => self.check_eval_origin(context_id).await?,
         }
+        evaluate.await_promise = self.await_promise(evaluate.await_promise)?;
         if evaluate.return_by_value.is_none() {
             evaluate.return_by_value = Some(true);
         }
@@ -332,6 +1041,111 @@ impl PageInner {
         Ok(EvaluationResult::new(resp.result))
     }
 
+    /// Evaluates the `expression` in the main world of every frame of the
+    /// page, in the order of the frame tree, and returns the values with the
+    /// id of their frame.
+    ///
+    /// Frames without an execution context, like frames that did not start
+    /// loading, and frames whose origin is not in the evaluation allowlist
+    /// are skipped.
+    pub async fn eval_all_frames<T: DeserializeOwned>(
+        &self,
+        expression: impl Into<String>,
+    ) -> Result<Vec<(FrameId, T)>> {
+        let expression = expression.into();
+        let tree = self
+            .execute(GetFrameTreeParams::default())
+            .await?
+            .result
+            .frame_tree;
+        let mut frame_ids = Vec::new();
+        let mut pending = vec![tree];
+        while let Some(tree) = pending.pop() {
+            frame_ids.push(tree.frame.id);
+            pending.extend(tree.child_frames.unwrap_or_default().into_iter().rev());
+        }
+
+        let mut values = Vec::with_capacity(frame_ids.len());
+        for frame_id in frame_ids {
+            let Some(context_id) = self.frame_execution_context(frame_id.clone()).await? else {
+                continue;
+            };
+            let evaluate = EvaluateParams::builder()
+                .expression(expression.clone())
+                .context_id(context_id)
+                .build()
+                .unwrap();
+            match self.evaluate_expression(evaluate).await {
+                Ok(result) => values.push((frame_id, result.into_value()?)),
+                Err(CdpError::OriginNotAllowed(_)) => continue,
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(values)
+    }
+
+    pub(crate) fn set_eval_origin_allowlist(&self, allowlist: Option<EvalOriginAllowlist>) {
+        *self.eval_origin_allowlist.write().unwrap() = allowlist.map(Arc::new);
+    }
+
+    /// Fails with [`CdpError::OriginNotAllowed`] if an allowlist is set and
+    /// the origin of the context is not part of it.
+    pub(crate) async fn check_eval_origin(
+        &self,
+        context_id: Option<ExecutionContextId>,
+    ) -> Result<()> {
+        let Some(allowlist) = self.eval_origin_allowlist.read().unwrap().clone() else {
+            return Ok(());
+        };
+        let origin = match context_id {
+            Some(context_id) => self.execution_context_origin(context_id).await?,
+            None => None,
+        };
+        match origin {
+            Some(origin) if allowlist.allows(&origin) => Ok(()),
+            origin => Err(CdpError::OriginNotAllowed(origin.unwrap_or_default())),
+        }
+    }
+
+    /// Fails with [`CdpError::OriginNotAllowed`] if an allowlist is set and
+    /// the origin of the context the remote object was created in is not
+    /// part of it.
+    pub(crate) async fn check_object_origin(&self, object_id: &RemoteObjectId) -> Result<()> {
+        self.check_eval_origin(object_context_id(object_id)).await
+    }
+
+    /// The origin of the execution context, as cached by the target
+    pub async fn execution_context_origin(
+        &self,
+        context_id: ExecutionContextId,
+    ) -> Result<Option<String>> {
+        let (tx, rx) = oneshot_channel();
+        self.sender
+            .clone()
+            .send(TargetMessage::GetExecutionContextOrigin(context_id, tx))
+            .await?;
+        Ok(rx.await?)
+    }
+
+    pub async fn execution_contexts(&self) -> Result<Vec<ExecutionContextInfo>> {
+        let (tx, rx) = oneshot_channel();
+        self.sender
+            .clone()
+            .send(TargetMessage::GetExecutionContexts(tx))
+            .await?;
+        Ok(rx.await?)
+    }
+
+    /// The lifecycle events the main frame reached since it started loading
+    pub async fn main_frame_lifecycle_events(&self) -> Result<Vec<String>> {
+        let (tx, rx) = oneshot_channel();
+        self.sender
+            .clone()
+            .send(TargetMessage::MainFrameLifecycleEvents(tx))
+            .await?;
+        Ok(rx.await?)
+    }
+
     pub async fn execution_context(&self) -> Result<Option<ExecutionContextId>> {
         self.execution_context_for_world(None, DOMWorldKind::Main)
             .await
@@ -385,13 +1199,65 @@ impl PageInner {
 
     pub async fn screenshot(&self, params: impl Into<ScreenshotParams>) -> Result<Vec<u8>> {
         self.activate().await?;
-        let params = params.into();
+        self.capture_screenshot(params.into()).await
+    }
+
+    /// Same as [`PageInner::screenshot`] but without activating the target
+    /// first, so pages can be captured in the background.
+    ///
+    /// Pages in the background of a headful browser may not render and
+    /// never produce the screenshot.
+    pub(crate) async fn capture_screenshot(&self, params: ScreenshotParams) -> Result<Vec<u8>> {
         let full_page = params.full_page();
         let omit_background = params.omit_background();
 
+        let stitched = params.stitched();
+        if stitched && !full_page {
+            return Err(CdpError::msg(
+                "Stitched screenshots capture the full page, enable `full_page` too",
+            ));
+        }
+        let hide_fixed_elements = params.hide_fixed_elements();
+        let clip_element = params.clip_element;
         let mut cdp_params = params.cdp_params;
 
-        if full_page {
+        if let Some(quality) = cdp_params.quality {
+            if cdp_params
+                .format
+                .as_ref()
+                .map_or(true, |f| f == &CaptureScreenshotFormat::Png)
+            {
+                return Err(CdpError::msg(
+                    "Screenshot quality is only supported for jpeg and webp",
+                ));
+            }
+            if !(0..=100).contains(&quality) {
+                return Err(CdpError::msg(format!(
+                    "Screenshot quality must be within 0..=100, got {quality}"
+                )));
+            }
+        }
+
+        // capturing beyond the viewport makes resizing the viewport for full
+        // page and element screenshots unnecessary
+        let beyond_viewport = !stitched
+            && (full_page || clip_element.is_some())
+            && cdp_params.capture_beyond_viewport != Some(false)
+            && self.browser_supports(CAPTURE_BEYOND_VIEWPORT_SINCE);
+        if beyond_viewport {
+            cdp_params.capture_beyond_viewport = Some(true);
+        }
+
+        let resize = full_page && !stitched && !beyond_viewport;
+        if stitched {
+            if cdp_params
+                .format
+                .as_ref()
+                .is_some_and(|f| f != &CaptureScreenshotFormat::Png)
+            {
+                return Err(CdpError::msg("Stitched screenshots only support png"));
+            }
+        } else if full_page {
             let metrics = self.layout_metrics().await?;
             let width = metrics.css_content_size.width;
             let height = metrics.css_content_size.height;
@@ -404,13 +1270,20 @@ impl PageInner {
                 scale: 1.,
             });
 
-            self.execute(SetDeviceMetricsOverrideParams::new(
-                width as i64,
-                height as i64,
-                1.,
-                false,
-            ))
-            .await?;
+            if resize {
+                self.execute(SetDeviceMetricsOverrideParams::new(
+                    width as i64,
+                    height as i64,
+                    1.,
+                    false,
+                ))
+                .await?;
+            }
+        }
+
+        // the element takes precedence over the full page
+        if let Some(element) = clip_element {
+            cdp_params.clip = Some(self.element_clip(&element, !beyond_viewport).await?);
         }
 
         if omit_background {
@@ -425,31 +1298,253 @@ impl PageInner {
             .await?;
         }
 
-        let res = self.execute(cdp_params).await?.result;
+        let res = if stitched {
+            self.stitched_screenshot(cdp_params, hide_fixed_elements)
+                .await
+        } else {
+            self.execute(cdp_params)
+                .await
+                .and_then(|res| Ok(utils::base64::decode(&res.result.data)?))
+        };
 
         if omit_background {
             self.execute(SetDefaultBackgroundColorOverrideParams { color: None })
                 .await?;
         }
 
-        if full_page {
+        if resize {
             self.execute(ClearDeviceMetricsOverrideParams {}).await?;
         }
 
-        Ok(utils::base64::decode(&res.data)?)
+        res
+    }
+
+    /// The bounds of the element in document coordinates, optionally after
+    /// scrolling it into view.
+    async fn element_clip(&self, element: &RemoteObjectId, scroll: bool) -> Result<Viewport> {
+        let bounds = call_function_on(
+            self,
+            element,
+            "function(scroll) {
+                if (scroll) {
+                    this.scrollIntoView({ block: 'nearest', inline: 'nearest' });
+                }
+                const rect = this.getBoundingClientRect();
+                return [rect.x + window.scrollX, rect.y + window.scrollY, rect.width, rect.height];
+            }",
+            vec![scroll.into()],
+            true,
+        )
+        .await?;
+        let [x, y, width, height]: [f64; 4] = value_or_null(bounds)?;
+        if width == 0. || height == 0. {
+            return Err(CdpError::msg("Element has no size to capture"));
+        }
+        Ok(Viewport {
+            x,
+            y,
+            width,
+            height,
+            scale: 1.,
+        })
+    }
+
+    /// Scrolls the tile at `y` into view and captures it, returns the base64
+    /// encoded image.
+    async fn capture_tile(
+        &self,
+        cdp_params: &mut CaptureScreenshotParams,
+        y: f64,
+        width: f64,
+        height: f64,
+    ) -> Result<String> {
+        self.evaluate_expression(format!("window.scrollTo(0, {y})").as_str())
+            .await?;
+        // the clip is in document coordinates
+        cdp_params.clip = Some(Viewport {
+            x: 0.,
+            y,
+            width,
+            height,
+            scale: 1.,
+        });
+        Ok(self.execute(cdp_params.clone()).await?.result.data.into())
+    }
+
+    /// Captures the whole page in tiles of the viewport's height, scrolling
+    /// to every tile, and stitches them together.
+    ///
+    /// Tiles are laid out in device pixels, so that they neither overlap nor
+    /// leave gaps at fractional device pixel ratios.
+    async fn stitched_screenshot(
+        &self,
+        mut cdp_params: CaptureScreenshotParams,
+        hide_fixed_elements: bool,
+    ) -> Result<Vec<u8>> {
+        let metrics = self.layout_metrics().await?;
+        let (scroll, ratio): ((f64, f64), f64) = self
+            .evaluate_expression("[[window.scrollX, window.scrollY], window.devicePixelRatio]")
+            .await?
+            .into_value()?;
+        let width = (metrics.css_content_size.width * ratio).ceil();
+        let height = (metrics.css_content_size.height * ratio).ceil();
+        let tile_height = (metrics.css_layout_viewport.client_height as f64 * ratio)
+            .floor()
+            .max(1.);
+
+        let mut tiles = Vec::new();
+        let mut hidden = false;
+        let mut res = Ok(());
+        let mut y = 0.;
+        while y < height {
+            if hide_fixed_elements && !hidden && !tiles.is_empty() {
+                hidden = true;
+                if let Err(err) = self.evaluate_expression(HIDE_FIXED_ELEMENTS).await {
+                    res = Err(err);
+                    break;
+                }
+            }
+            let clip_height = tile_height.min(height - y);
+            match self
+                .capture_tile(
+                    &mut cdp_params,
+                    y / ratio,
+                    width / ratio,
+                    clip_height / ratio,
+                )
+                .await
+            {
+                Ok(tile) => tiles.push((y, tile)),
+                Err(err) => {
+                    res = Err(err);
+                    break;
+                }
+            }
+            y += tile_height;
+        }
+
+        if hidden {
+            self.evaluate_expression(RESTORE_FIXED_ELEMENTS).await?;
+        }
+        self.evaluate_expression(format!("window.scrollTo({}, {})", scroll.0, scroll.1).as_str())
+            .await?;
+        res?;
+
+        self.stitch_tiles(width, height, tiles).await
+    }
+
+    /// Draws the base64 encoded PNG `tiles` at their offsets onto a canvas of
+    /// the page and encodes it as PNG, like a canvas screenshot.
+    async fn stitch_tiles(
+        &self,
+        width: f64,
+        height: f64,
+        tiles: Vec<(f64, String)>,
+    ) -> Result<Vec<u8>> {
+        let canvas = self
+            .evaluate_function(
+                CallFunctionOnParams::builder()
+                    .function_declaration(
+                        "(width, height) => {
+                            const canvas = document.createElement('canvas');
+                            canvas.width = width;
+                            canvas.height = height;
+                            return canvas;
+                        }",
+                    )
+                    .argument(CallArgument::builder().value(width).build())
+                    .argument(CallArgument::builder().value(height).build())
+                    .return_by_value(false)
+                    .build()
+                    .unwrap(),
+            )
+            .await?
+            .object()
+            .object_id
+            .clone()
+            .ok_or_else(|| CdpError::msg("No object Id found for canvas"))?;
+
+        let res = self.encode_tiles(&canvas, tiles).await;
+        if let Err(err) = self.execute(ReleaseObjectParams::new(canvas)).await {
+            tracing::debug!("Failed to release the screenshot canvas: {err:?}");
+        }
+        res
+    }
+
+    async fn encode_tiles(
+        &self,
+        canvas: &RemoteObjectId,
+        tiles: Vec<(f64, String)>,
+    ) -> Result<Vec<u8>> {
+        // every tile is sent in a call of its own
+        for (y, tile) in tiles {
+            call_function_on_with(
+                self,
+                canvas,
+                "async function(y, data) {
+                    const bytes = Uint8Array.from(atob(data), c => c.charCodeAt(0));
+                    const tile = await createImageBitmap(new Blob([bytes], { type: 'image/png' }));
+                    this.getContext('2d').drawImage(tile, 0, y);
+                    tile.close();
+                }",
+                vec![y.into(), tile.into()],
+                true,
+                true,
+            )
+            .await?;
+        }
+        let data_url = call_function_on(
+            self,
+            canvas,
+            "function() { return this.toDataURL('image/png'); }",
+            vec![],
+            true,
+        )
+        .await?;
+        let data_url: String = value_or_null(data_url)?;
+        // an empty data url if the canvas exceeds the size limit of the browser
+        let data = data_url
+            .strip_prefix("data:image/png;base64,")
+            .ok_or_else(|| CdpError::msg("The page is too large to stitch into one image"))?;
+        Ok(utils::base64::decode(data)?)
     }
 }
 
+/// The first major version of Chrome known to support `captureBeyondViewport`
+const CAPTURE_BEYOND_VIEWPORT_SINCE: u32 = 91;
+
+/// Hides all fixed and sticky elements, remembering their inline visibility
+const HIDE_FIXED_ELEMENTS: &str = "
+    for (const el of document.querySelectorAll('*')) {
+        const position = getComputedStyle(el).position;
+        if (position === 'fixed' || position === 'sticky') {
+            el.dataset.chromiumoxideVisibility = el.style.visibility;
+            el.style.visibility = 'hidden';
+        }
+    }";
+
+/// Restores the elements hidden by `HIDE_FIXED_ELEMENTS`
+const RESTORE_FIXED_ELEMENTS: &str = "
+    for (const el of document.querySelectorAll('[data-chromiumoxide-visibility]')) {
+        el.style.visibility = el.dataset.chromiumoxideVisibility;
+        delete el.dataset.chromiumoxideVisibility;
+    }";
+
 pub(crate) async fn execute<T: Command>(
     cmd: T,
     mut sender: Sender<TargetMessage>,
     session: Option<SessionId>,
+    queue: &CommandQueue,
 ) -> Result<CommandResponse<T::Response>> {
     let (tx, rx) = oneshot_channel();
     let method = cmd.identifier();
     let msg = CommandMessage::with_session(cmd, tx, session)?;
 
-    sender.send(TargetMessage::Command(msg)).await?;
+    queue.reserve().await;
+    if let Err(err) = sender.send(TargetMessage::Command(msg)).await {
+        queue.release();
+        return Err(err.into());
+    }
     let resp = rx.await??;
     to_command_response::<T>(resp, method)
 }
@@ -19,12 +19,13 @@ use chromiumoxide_cdp::cdp::CdpEventMessage;
 use chromiumoxide_types::{Command, Method, Request, Response};
 
 use crate::auth::Credentials;
+use crate::browser::BrowserVersion;
 use crate::cdp::browser_protocol::target::CloseTargetParams;
 use crate::cmd::CommandChain;
 use crate::cmd::CommandMessage;
 use crate::error::{CdpError, Result};
 use crate::handler::browser::BrowserContext;
-use crate::handler::domworld::DOMWorldKind;
+use crate::handler::domworld::{DOMWorldKind, ExecutionContextInfo};
 use crate::handler::emulation::EmulationManager;
 use crate::handler::frame::{
     FrameEvent, FrameManager, NavigationError, NavigationId, NavigationOk,
@@ -33,7 +34,7 @@ use crate::handler::frame::{FrameNavigationRequest, UTILITY_WORLD_NAME};
 use crate::handler::network::{NetworkEvent, NetworkManager};
 use crate::handler::page::PageHandle;
 use crate::handler::viewport::Viewport;
-use crate::handler::{PageInner, REQUEST_TIMEOUT};
+use crate::handler::{PageInner, COMMAND_QUEUE_DEPTH, REQUEST_TIMEOUT};
 use crate::listeners::{EventListenerRequest, EventListeners};
 use crate::{page::Page, ArcHttpRequest};
 use chromiumoxide_cdp::cdp::js_protocol::runtime::{
@@ -92,6 +93,12 @@ pub struct Target {
     wait_for_frame_navigation: Vec<Sender<ArcHttpRequest>>,
     /// The sender who requested the page.
     initiator: Option<Sender<Result<Page>>>,
+    /// Commands of the page held back by the in-flight limits of the handler
+    held_commands: VecDeque<CommandMessage>,
+    /// How many more commands may be submitted, unlimited if `None`
+    command_budget: Option<usize>,
+    /// The number of submitted commands awaiting a response
+    commands_in_flight: usize,
 }
 
 impl Target {
@@ -119,6 +126,9 @@ impl Target {
             queued_events: Default::default(),
             event_listeners: Default::default(),
             initiator: None,
+            held_commands: Default::default(),
+            command_budget: None,
+            commands_in_flight: 0,
             browser_context,
         }
     }
@@ -162,13 +172,27 @@ impl Target {
     fn create_page(&mut self) {
         if self.page.is_none() {
             if let Some(session) = self.session_id.clone() {
-                let handle =
-                    PageHandle::new(self.target_id().clone(), session, self.opener_id().cloned());
+                let handle = PageHandle::new(
+                    self.target_id().clone(),
+                    session,
+                    self.opener_id().cloned(),
+                    self.config.command_queue_depth,
+                    self.config.browser_version.clone(),
+                );
                 self.page = Some(handle);
             }
         }
     }
 
+    /// Passes the version of the browser, once the handler received it, down
+    /// to the page.
+    pub(crate) fn set_browser_version(&mut self, version: &BrowserVersion) {
+        if let Some(page) = &self.page {
+            page.inner().set_browser_version(version.clone());
+        }
+        self.config.browser_version = Some(version.clone());
+    }
+
     /// Tries to create the `PageInner` if this target is already initialized
     pub(crate) fn get_or_create_page(&mut self) -> Option<&Arc<PageInner>> {
         self.create_page();
@@ -225,6 +249,13 @@ impl Target {
         }
     }
 
+    /// Invalidates the results cached for the previous document.
+    fn on_new_main_document(&self) {
+        if let Some(page) = self.page.as_ref() {
+            page.inner().eval_cache().next_generation();
+        }
+    }
+
     pub fn on_event(&mut self, event: CdpEventMessage) {
         let CdpEventMessage { params, method, .. } = event;
         match &params {
@@ -233,7 +264,12 @@ impl Target {
                 .frame_manager
                 .on_frame_attached(ev.frame_id.clone(), Some(ev.parent_frame_id.clone())),
             CdpEvent::PageFrameDetached(ev) => self.frame_manager.on_frame_detached(ev),
-            CdpEvent::PageFrameNavigated(ev) => self.frame_manager.on_frame_navigated(&ev.frame),
+            CdpEvent::PageFrameNavigated(ev) => {
+                if ev.frame.parent_id.is_none() {
+                    self.on_new_main_document();
+                }
+                self.frame_manager.on_frame_navigated(&ev.frame)
+            }
             CdpEvent::PageNavigatedWithinDocument(ev) => {
                 self.frame_manager.on_frame_navigated_within_document(ev)
             }
@@ -244,6 +280,7 @@ impl Target {
                 self.frame_manager.on_frame_execution_context_destroyed(ev)
             }
             CdpEvent::RuntimeExecutionContextsCleared(_) => {
+                self.on_new_main_document();
                 self.frame_manager.on_execution_contexts_cleared()
             }
             CdpEvent::RuntimeBindingCalled(ev) => {
@@ -434,7 +471,7 @@ impl Target {
                 while let Poll::Ready(Some(msg)) = Pin::new(&mut handle.rx).poll_next(cx) {
                     match msg {
                         TargetMessage::Command(cmd) => {
-                            self.queued_events.push_back(TargetEvent::Command(cmd));
+                            self.held_commands.push_back(cmd);
                         }
                         TargetMessage::MainFrame(tx) => {
                             let _ =
@@ -515,6 +552,29 @@ impl Target {
                                 let _ = tx.send(None);
                             }
                         }
+                        TargetMessage::GetExecutionContextOrigin(context_id, tx) => {
+                            let _ = tx.send(
+                                self.frame_manager
+                                    .execution_context_origin(context_id)
+                                    .map(str::to_string),
+                            );
+                        }
+                        TargetMessage::GetExecutionContexts(tx) => {
+                            let _ = tx.send(self.frame_manager.execution_contexts());
+                        }
+                        TargetMessage::MainFrameLifecycleEvents(tx) => {
+                            let _ = tx.send(
+                                self.frame_manager
+                                    .main_frame()
+                                    .map(|f| {
+                                        f.lifecycle_events()
+                                            .iter()
+                                            .map(|ev| ev.to_string())
+                                            .collect()
+                                    })
+                                    .unwrap_or_default(),
+                            );
+                        }
                         TargetMessage::Authenticate(credentials) => {
                             self.network_manager.authenticate(credentials);
                         }
@@ -522,6 +582,8 @@ impl Target {
                 }
             }
 
+            self.release_commands();
+
             while let Some(event) = self.network_manager.poll() {
                 match event {
                     NetworkEvent::SendCdpRequest((method, params)) => {
@@ -562,6 +624,56 @@ impl Target {
         }
     }
 
+    /// Sets how many more commands of the page may be submitted until the
+    /// next poll, unlimited if `None`
+    pub(crate) fn set_command_budget(&mut self, budget: Option<usize>) {
+        self.command_budget = budget;
+    }
+
+    /// The number of submitted commands of the page awaiting a response
+    pub(crate) fn commands_in_flight(&self) -> usize {
+        self.commands_in_flight
+    }
+
+    /// A submitted command of the page received its response or timed out
+    pub(crate) fn on_command_done(&mut self) {
+        self.commands_in_flight = self.commands_in_flight.saturating_sub(1);
+        if let Some(page) = self.page.as_ref() {
+            page.inner().command_queue().on_received();
+        }
+    }
+
+    /// Hands the held commands over to the handler, as far as the budget
+    /// allows
+    fn release_commands(&mut self) {
+        while let Some(cmd) = self.held_commands.front() {
+            // navigations are tracked by the frame manager and don't count
+            // towards the in-flight limits
+            let is_navigation = cmd.is_navigation();
+            if !is_navigation {
+                match self.command_budget.as_mut() {
+                    Some(0) => break,
+                    Some(budget) => *budget -= 1,
+                    None => {}
+                }
+            }
+            let cmd = self
+                .held_commands
+                .pop_front()
+                .expect("existence checked above");
+            if let Some(page) = self.page.as_ref() {
+                let queue = page.inner().command_queue();
+                if is_navigation {
+                    queue.release();
+                } else {
+                    self.commands_in_flight += 1;
+                    queue.on_sent();
+                }
+            }
+            self.queued_events.push_back(TargetEvent::Command(cmd));
+        }
+    }
+
     /// Set the sender half of the channel who requested the creation of this
     /// target
     pub fn set_initiator(&mut self, tx: Sender<Result<Page>>) {
@@ -599,9 +711,13 @@ pub struct TargetConfig {
     pub ignore_https_errors: bool,
     ///  Request timeout to use
     pub request_timeout: Duration,
+    /// How many commands the page queues before senders have to wait
+    pub command_queue_depth: Option<usize>,
     pub viewport: Option<Viewport>,
     pub request_intercept: bool,
     pub cache_enabled: bool,
+    /// The version of the browser, if the handler already received it
+    pub browser_version: Option<BrowserVersion>,
 }
 
 impl Default for TargetConfig {
@@ -609,9 +725,11 @@ impl Default for TargetConfig {
         Self {
             ignore_https_errors: true,
             request_timeout: Duration::from_secs(REQUEST_TIMEOUT),
+            command_queue_depth: Some(COMMAND_QUEUE_DEPTH),
             viewport: Default::default(),
             request_intercept: false,
             cache_enabled: true,
+            browser_version: None,
         }
     }
 }
@@ -780,5 +898,12 @@ pub enum TargetMessage {
     AddEventListener(EventListenerRequest),
     /// Get the `ExecutionContext` if available
     GetExecutionContext(GetExecutionContext),
+    /// Get the origin of the `ExecutionContext` if known
+    GetExecutionContextOrigin(ExecutionContextId, Sender<Option<String>>),
+    /// Get all known `ExecutionContext`s
+    GetExecutionContexts(Sender<Vec<ExecutionContextInfo>>),
+    /// Get the lifecycle events the main frame reached since it started
+    /// loading
+    MainFrameLifecycleEvents(Sender<Vec<String>>),
     Authenticate(Credentials),
 }
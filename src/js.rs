@@ -1,10 +1,41 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::{Stream, StreamExt};
+
 use serde::de::DeserializeOwned;
+use serde::Serialize;
 
+use chromiumoxide_cdp::cdp::browser_protocol::dom::RequestNodeParams;
+use chromiumoxide_cdp::cdp::browser_protocol::page::FrameId;
 use chromiumoxide_cdp::cdp::js_protocol::runtime::{
-    CallFunctionOnParams, EvaluateParams, RemoteObject,
+    AddBindingParams, AwaitPromiseParams, CallArgument, CallFunctionOnParams, EvaluateParams,
+    EventBindingCalled, ExceptionDetails, GetPropertiesParams, PropertyDescriptor,
+    ReleaseObjectParams, RemoteObject, RemoteObjectId, RemoteObjectSubtype, RemoteObjectType,
+    RemoveBindingParams,
 };
 
-use crate::utils::is_likely_js_function;
+use crate::element::Element;
+use crate::error::{CdpError, Result};
+use crate::handler::PageInner;
+use crate::js::registry::ObjectEntry;
+use crate::page::Page;
+use crate::utils::{self, is_likely_js_function};
+
+pub mod cache;
+pub mod census;
+pub mod data_transfer;
+pub mod event;
+pub mod markdown;
+pub mod registry;
+pub mod script;
+pub mod table;
+pub mod time;
 
 #[derive(Debug, Clone)]
 pub struct EvaluationResult {
@@ -17,21 +48,305 @@ impl EvaluationResult {
         Self { inner }
     }
 
+    /// The raw mirror object as reported by the browser.
+    ///
+    /// This is meant for inspecting details like the `description` or
+    /// `preview`. To read the result prefer [`EvaluationResult::into_value`],
+    /// and [`EvaluationResult::into_js_object`] or
+    /// [`EvaluationResult::into_element`] for results returned by reference.
     pub fn object(&self) -> &RemoteObject {
         &self.inner
     }
 
+    /// Converts a result returned by reference into a live handle to the
+    /// object, `None` for primitive values.
+    ///
+    /// The handle takes over the remote object, it is tracked like objects
+    /// returned by [`Page::js_object`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let result = page.evaluate_handle("performance.timing").await?;
+    ///     if let Some(timing) = result.into_js_object(&page) {
+    ///         println!("{:?}", timing.own_keys(Default::default()).await?);
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub fn into_js_object(self, page: &Page) -> Option<JsObject> {
+        let remote_object_id = self.inner.object_id?;
+        let class = self.inner.class_name.as_deref().unwrap_or("Object");
+        Some(
+            JsObject::new(Arc::clone(page.inner()), remote_object_id, class)
+                .with_description(self.inner.description),
+        )
+    }
+
+    /// Converts a result returned by reference into an [`Element`], `None`
+    /// if the result is not a DOM node.
+    pub async fn into_element(self, page: &Page) -> Result<Option<Element>> {
+        if self.inner.subtype != Some(RemoteObjectSubtype::Node) {
+            return Ok(None);
+        }
+        match self.inner.object_id {
+            Some(object_id) => Ok(Some(
+                Element::from_object_id(Arc::clone(page.inner()), object_id).await?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Converts a result returned by reference into a live handle to the
+    /// array, fails if the result is not an array.
+    pub fn into_js_array(self, page: &Page) -> Result<JsArray> {
+        if self.inner.subtype != Some(RemoteObjectSubtype::Array) {
+            return Err(self.unexpected("an array"));
+        }
+        let remote_object_id = self
+            .inner
+            .object_id
+            .ok_or_else(|| CdpError::msg("No object Id found for array"))?;
+        Ok(JsArray::new(Arc::clone(page.inner()), remote_object_id)
+            .with_description(self.inner.description))
+    }
+
+    /// Converts a result returned by reference into a live handle to the
+    /// function, fails if the result is not a function.
+    pub fn into_js_function(self, page: &Page) -> Result<JsFunction> {
+        if self.inner.r#type != RemoteObjectType::Function {
+            return Err(self.unexpected("a function"));
+        }
+        let remote_object_id = self
+            .inner
+            .object_id
+            .ok_or_else(|| CdpError::msg("No object Id found for function"))?;
+        Ok(JsFunction::new(Arc::clone(page.inner()), remote_object_id)
+            .with_description(self.inner.description))
+    }
+
+    fn unexpected(&self, expected: &str) -> CdpError {
+        CdpError::msg(format!(
+            "Expected {expected}, found {}",
+            self.inner
+                .description
+                .as_deref()
+                .unwrap_or_else(|| self.inner.r#type.as_ref())
+        ))
+    }
+
+    /// The description of the error, including its stack, if the result is
+    /// an `Error` object, like an exception the script caught and returned.
+    ///
+    /// Exceptions thrown by the evaluation itself fail it with
+    /// [`CdpError::JavascriptException`] instead.
+    pub fn exception(&self) -> Option<&str> {
+        if self.inner.subtype != Some(RemoteObjectSubtype::Error) {
+            return None;
+        }
+        self.inner.description.as_deref()
+    }
+
     pub fn value(&self) -> Option<&serde_json::Value> {
         self.object().value.as_ref()
     }
 
     /// Attempts to deserialize the value into the given type
+    ///
+    /// Numbers JSON can't represent, `Infinity`, `-Infinity`, `NaN` and `-0`,
+    /// deserialize into floats and `BigInt`s into integers, if they fit.
     pub fn into_value<T: DeserializeOwned>(self) -> serde_json::Result<T> {
-        let value = self
-            .inner
-            .value
-            .ok_or_else(|| serde::de::Error::custom("No value found"))?;
-        serde_json::from_value(value)
+        if let Some(value) = self.inner.value {
+            return serde_json::from_value(value);
+        }
+        match &self.inner.unserializable_value {
+            Some(value) => from_unserializable(value.as_ref()),
+            None => Err(serde::de::Error::custom("No value found")),
+        }
+    }
+}
+
+/// The longest description of a remote object that `Debug` and `Display`
+/// of handles print, function sources and strings can be huge.
+const MAX_PREVIEW_CHARS: usize = 60;
+
+/// Displays the first line of the description of a remote object, shortened
+/// to [`MAX_PREVIEW_CHARS`].
+pub(crate) struct Preview<'a>(pub &'a str);
+
+impl fmt::Display for Preview<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let line = self.0.lines().next().unwrap_or_default();
+        match line.char_indices().nth(MAX_PREVIEW_CHARS) {
+            Some((end, _)) => write!(f, "{}…", &line[..end]),
+            None if line.len() < self.0.trim_end().len() => write!(f, "{line}…"),
+            None => f.write_str(line),
+        }
+    }
+}
+
+/// Implements a compact `Debug` for handles that prints a preview of the
+/// remote object and its id instead of the internals of the page, like
+/// `JsArray(Array(3) -4129.1.7)`, and a `Display` that prints the preview
+/// only. The preview is the description the object had when the handle was
+/// created, or the class if there is none.
+macro_rules! impl_handle_fmt {
+    ($($handle:ident => $class:expr),* $(,)?) => {$(
+        impl $handle {
+            fn preview(&self) -> Preview<'_> {
+                Preview(self.description.as_deref().unwrap_or($class))
+            }
+        }
+
+        impl fmt::Debug for $handle {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(
+                    f,
+                    "{}({} {})",
+                    stringify!($handle),
+                    self.preview(),
+                    self.remote_object_id.inner()
+                )
+            }
+        }
+
+        impl fmt::Display for $handle {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.preview().fmt(f)
+            }
+        }
+    )*};
+}
+
+impl_handle_fmt! {
+    JsReadableStream => "ReadableStream",
+    JsFunction => "Function",
+    JsArray => "Array",
+    JsUrl => "URL",
+    JsUrlSearchParams => "URLSearchParams",
+    JsHeaders => "Headers",
+    JsSelection => "Selection",
+    JsDocument => "Document",
+    JsPromise => "Promise",
+    JsDocumentFragment => "DocumentFragment",
+    JsDomTokenList => "DOMTokenList",
+}
+
+impl JsObject {
+    fn preview(&self) -> Preview<'_> {
+        Preview(self.description.as_deref().unwrap_or(&self.class))
+    }
+}
+
+/// Implements keeping the description of the remote object a handle is
+/// created from, for handles that are created from an evaluation result.
+macro_rules! impl_with_description {
+    ($($handle:ident),* $(,)?) => {$(
+        impl $handle {
+            pub(crate) fn with_description(mut self, description: Option<String>) -> Self {
+                self.description = description;
+                self
+            }
+        }
+    )*};
+}
+
+impl_with_description! {
+    JsObject,
+    JsReadableStream,
+    JsFunction,
+    JsArray,
+    JsHeaders,
+    JsSelection,
+    JsDocument,
+    JsPromise,
+    JsDocumentFragment,
+    JsDomTokenList,
+}
+
+impl fmt::Debug for JsObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "JsObject({} {})",
+            self.preview(),
+            self.remote_object_id.inner()
+        )
+    }
+}
+
+impl fmt::Display for JsObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.preview().fmt(f)
+    }
+}
+
+/// A thrown
+/// [DOMException](https://developer.mozilla.org/en-US/docs/Web/API/DOMException),
+/// see [`CdpError::dom_exception`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DomException {
+    /// The name of the error, e.g. `NotFoundError` or `SecurityError`
+    pub name: String,
+    /// The message of the error
+    pub message: String,
+    /// The legacy error code of the name, `0` for names without one
+    pub code: u16,
+}
+
+impl DomException {
+    /// Extracts the `DOMException` from the details of a thrown exception,
+    /// returns `None` if something else was thrown.
+    pub fn from_exception_details(details: &ExceptionDetails) -> Option<Self> {
+        let exception = details.exception.as_ref()?;
+        if exception.class_name.as_deref() != Some("DOMException") {
+            return None;
+        }
+        // the description is the stack: `{name}: {message}` followed by one
+        // line per frame
+        let description = exception.description.as_deref()?;
+        let description = description
+            .find("\n    at ")
+            .map_or(description, |frames| &description[..frames]);
+        let (name, message) = description.split_once(": ").unwrap_or((description, ""));
+        Some(Self {
+            code: Self::legacy_code(name),
+            name: name.to_string(),
+            message: message.to_string(),
+        })
+    }
+
+    /// The legacy code of the error `name`, see
+    /// [error names](https://webidl.spec.whatwg.org/#idl-DOMException-error-names).
+    pub fn legacy_code(name: &str) -> u16 {
+        match name {
+            "IndexSizeError" => 1,
+            "HierarchyRequestError" => 3,
+            "WrongDocumentError" => 4,
+            "InvalidCharacterError" => 5,
+            "NoModificationAllowedError" => 7,
+            "NotFoundError" => 8,
+            "NotSupportedError" => 9,
+            "InUseAttributeError" => 10,
+            "InvalidStateError" => 11,
+            "SyntaxError" => 12,
+            "InvalidModificationError" => 13,
+            "NamespaceError" => 14,
+            "InvalidAccessError" => 15,
+            "TypeMismatchError" => 17,
+            "SecurityError" => 18,
+            "NetworkError" => 19,
+            "AbortError" => 20,
+            "URLMismatchError" => 21,
+            "QuotaExceededError" => 22,
+            "TimeoutError" => 23,
+            "InvalidNodeTypeError" => 24,
+            "DataCloneError" => 25,
+            _ => 0,
+        }
     }
 }
 
@@ -41,6 +356,28 @@ pub enum Evaluation {
     Function(CallFunctionOnParams),
 }
 
+impl Evaluation {
+    /// Whether the evaluation names the execution context it runs in, by its
+    /// id or by an object of it.
+    pub(crate) fn is_context_bound(&self) -> bool {
+        match self {
+            Evaluation::Expression(params) => params.context_id.is_some(),
+            Evaluation::Function(params) => {
+                params.execution_context_id.is_some() || params.object_id.is_some()
+            }
+        }
+    }
+
+    /// Whether a returned promise is awaited, which is the default.
+    pub(crate) fn awaits_promise(&self) -> bool {
+        let await_promise = match self {
+            Evaluation::Expression(params) => params.await_promise,
+            Evaluation::Function(params) => params.await_promise,
+        };
+        await_promise != Some(false)
+    }
+}
+
 impl From<&str> for Evaluation {
     fn from(expression: &str) -> Self {
         if is_likely_js_function(expression) {
@@ -68,3 +405,2708 @@ impl From<CallFunctionOnParams> for Evaluation {
         Evaluation::Function(params)
     }
 }
+
+/// An optional argument of a function call that is passed as `undefined`
+/// rather than `null` if it is `None`.
+///
+/// By default serde serializes `None` to `null`, so a
+/// `CallArgument::builder().value(json!(None::<u32>))` passes `null`. That
+/// differs from a missing argument for functions that check for
+/// `undefined`, like default parameters, which only apply to `undefined`.
+/// Wrapping the value in a `JsOptional` passes `undefined` instead, by
+/// leaving every field of the [`CallArgument`] unset. `Some` values are
+/// serialized as usual.
+///
+/// Only the argument itself is affected. Properties of an object that are
+/// `None` are still `null`, `#[serde(skip_serializing_if = "Option::is_none")]`
+/// leaves them out, so they read as `undefined`.
+///
+/// # Example
+///
+/// ```no_run
+/// # use chromiumoxide::page::Page;
+/// # use chromiumoxide::error::Result;
+/// use chromiumoxide::cdp::js_protocol::runtime::CallFunctionOnParams;
+/// use chromiumoxide::js::JsOptional;
+///
+/// # async fn demo(page: Page) -> Result<()> {
+///     let call = CallFunctionOnParams::builder()
+///         .function_declaration("(limit = 10) => limit")
+///         .argument(JsOptional::<u32>(None).into_argument()?)
+///         .build()
+///         .unwrap();
+///     let limit: u32 = page.evaluate_function(call).await?.into_value()?;
+///     assert_eq!(limit, 10);
+///     # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct JsOptional<T>(pub Option<T>);
+
+impl<T: Serialize> JsOptional<T> {
+    /// The argument that passes the value, or `undefined` if `None`.
+    pub fn into_argument(self) -> Result<CallArgument> {
+        Ok(match self.0 {
+            Some(value) => CallArgument::builder()
+                .value(serde_json::to_value(value)?)
+                .build(),
+            None => CallArgument::default(),
+        })
+    }
+}
+
+impl<T> From<Option<T>> for JsOptional<T> {
+    fn from(value: Option<T>) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: Serialize> TryFrom<JsOptional<T>> for CallArgument {
+    type Error = CdpError;
+
+    fn try_from(optional: JsOptional<T>) -> Result<Self> {
+        optional.into_argument()
+    }
+}
+
+/// A single step of a path into a nested JavaScript value, see
+/// [`JsObject::get_path`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(untagged)]
+pub enum JsonPointerSegment {
+    /// A property name
+    Key(String),
+    /// An array index
+    Index(usize),
+}
+
+impl From<&str> for JsonPointerSegment {
+    fn from(key: &str) -> Self {
+        JsonPointerSegment::Key(key.to_string())
+    }
+}
+
+impl From<String> for JsonPointerSegment {
+    fn from(key: String) -> Self {
+        JsonPointerSegment::Key(key)
+    }
+}
+
+impl From<usize> for JsonPointerSegment {
+    fn from(index: usize) -> Self {
+        JsonPointerSegment::Index(index)
+    }
+}
+
+/// Which properties [`JsObject::own_keys`], [`JsObject::entries_with`] and
+/// [`JsObject::property_descriptors`] enumerate.
+///
+/// By default only the own string-keyed properties are included, all of
+/// them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PropertyOptions {
+    /// Whether the properties of the prototype chain are included
+    pub prototype_chain: bool,
+    /// Whether symbol-keyed properties are included, keyed by the
+    /// description of the symbol
+    pub symbols: bool,
+    /// The number of leading properties to skip
+    pub offset: usize,
+    /// The maximum number of properties to include after the `offset`
+    pub limit: Option<usize>,
+}
+
+impl PropertyOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Include the properties of the prototype chain.
+    pub fn prototype_chain(mut self, prototype_chain: bool) -> Self {
+        self.prototype_chain = prototype_chain;
+        self
+    }
+
+    /// Include symbol-keyed properties.
+    pub fn symbols(mut self, symbols: bool) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Skip the first `offset` properties, to read a large object page by
+    /// page.
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Include at most `limit` properties.
+    ///
+    /// The page only resolves the values of the included properties, which
+    /// keeps reads of objects with many properties bounded.
+    pub fn limit(mut self, limit: impl Into<Option<usize>>) -> Self {
+        self.limit = limit.into();
+        self
+    }
+
+    /// The arguments of [`COLLECT_KEYS_FN`] and the paging of its keys.
+    fn args(&self) -> Vec<serde_json::Value> {
+        vec![
+            self.prototype_chain.into(),
+            self.symbols.into(),
+            self.offset.into(),
+            self.limit.into(),
+        ]
+    }
+
+    fn is_truncated(&self, included: usize, total: usize) -> bool {
+        self.offset + included < total
+    }
+}
+
+/// A page of the properties of an object, see [`JsObject::entries_page`].
+#[derive(Debug)]
+pub struct PropertyPage {
+    /// The properties of the page with their values, or the error message
+    /// of a throwing getter
+    pub entries: Vec<(String, std::result::Result<EvaluationResult, String>)>,
+    /// The number of properties selected by the options, ignoring the offset
+    /// and limit
+    pub total: usize,
+    /// Whether properties after this page were left out by the limit
+    pub truncated: bool,
+}
+
+/// The attributes of a property, see [`JsObject::property_descriptors`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyDescriptorInfo {
+    /// The name of the property, the description for symbol keys
+    pub name: String,
+    /// Whether the property is keyed by a symbol
+    pub is_symbol: bool,
+    /// Whether the value can be changed, `None` for accessor properties
+    pub writable: Option<bool>,
+    pub enumerable: bool,
+    pub configurable: bool,
+    /// Whether the property is an accessor with a getter
+    pub has_getter: bool,
+    /// Whether the property is an accessor with a setter
+    pub has_setter: bool,
+    /// Whether the property is defined on the object itself rather than its
+    /// prototype chain
+    pub is_own: bool,
+}
+
+impl From<&PropertyDescriptor> for PropertyDescriptorInfo {
+    fn from(descriptor: &PropertyDescriptor) -> Self {
+        let is_function = |object: &Option<RemoteObject>| {
+            object
+                .as_ref()
+                .is_some_and(|object| object.r#type == RemoteObjectType::Function)
+        };
+        let name = match &descriptor.symbol {
+            // the description of a symbol reads `Symbol(description)`
+            Some(symbol) => symbol
+                .description
+                .as_deref()
+                .and_then(|d| d.strip_prefix("Symbol(")?.strip_suffix(')'))
+                .unwrap_or_default()
+                .to_string(),
+            None => descriptor.name.clone(),
+        };
+        Self {
+            name,
+            is_symbol: descriptor.symbol.is_some(),
+            writable: descriptor.writable,
+            enumerable: descriptor.enumerable,
+            configurable: descriptor.configurable,
+            has_getter: is_function(&descriptor.get),
+            has_setter: is_function(&descriptor.set),
+            is_own: descriptor.is_own.unwrap_or(true),
+        }
+    }
+}
+
+/// A live handle to a JavaScript
+/// [Object](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object)
+/// in the page.
+pub struct JsObject {
+    /// The Unique object identifier
+    pub remote_object_id: RemoteObjectId,
+    /// The class name of the object, like `Object` or `HTMLCollection`
+    class: String,
+    tab: Arc<PageInner>,
+    /// How the object was described when the handle was created, like
+    /// `Object` or `Map(2)`
+    description: Option<String>,
+    _tracked: Option<Arc<ObjectEntry>>,
+}
+
+impl JsObject {
+    pub(crate) fn new(tab: Arc<PageInner>, remote_object_id: RemoteObjectId, class: &str) -> Self {
+        let _tracked = tab.objects().track(&remote_object_id, class);
+        Self {
+            remote_object_id,
+            class: class.to_string(),
+            tab,
+            description: None,
+            _tracked,
+        }
+    }
+
+    /// Returns the own enumerable string-keyed properties of the object, as
+    /// returned by `Object.entries`, with their values resolved by reference.
+    ///
+    /// This takes two round trips, regardless of the number of properties.
+    pub async fn entries(&self) -> Result<Vec<(String, EvaluationResult)>> {
+        // copy the entries into an object without prototype, so that only the
+        // entries are reported as own properties
+        let entries = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() {
+                const entries = Object.create(null);
+                for (const [key, value] of Object.entries(this)) {
+                    entries[key] = value;
+                }
+                return entries;
+            }",
+            vec![],
+            false,
+        )
+        .await?
+        .object_id
+        .ok_or_else(|| CdpError::msg("No object Id found for entries"))?;
+
+        let mut params = GetPropertiesParams::new(entries.clone());
+        params.own_properties = Some(true);
+        let properties = self.tab.execute(params).await;
+        self.tab.release_best_effort(entries).await;
+
+        Ok(properties?
+            .result
+            .result
+            .into_iter()
+            .filter(|p| p.enumerable)
+            .filter_map(|p| Some((p.name, EvaluationResult::new(p.value?))))
+            .collect())
+    }
+
+    /// Returns the entries of a JavaScript `Map`, deserialized by value.
+    ///
+    /// Keys are not limited to strings, so a `Map` with numeric or object keys
+    /// deserializes into `HashMap<u32, V>` or `HashMap<K, V>` with a struct
+    /// key. For any other object the own enumerable string-keyed properties
+    /// are returned instead.
+    pub async fn map_entries<K, V>(&self) -> Result<HashMap<K, V>>
+    where
+        K: DeserializeOwned + Eq + std::hash::Hash,
+        V: DeserializeOwned,
+    {
+        let entries = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() {
+                return this instanceof Map ? Array.from(this) : Object.entries(this);
+            }",
+            vec![],
+            true,
+        )
+        .await?;
+        let entries: Vec<(K, V)> = value_or_null(entries)?;
+        Ok(entries.into_iter().collect())
+    }
+
+    /// Whether the object is an instance of the global constructor named
+    /// `ctor`, like `Map` or `HTMLElement`.
+    ///
+    /// Returns `false` if there is no such global or it is not a constructor.
+    pub async fn instance_of_named(&self, ctor: &str) -> Result<bool> {
+        let is_instance = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            INSTANCE_OF_NAMED_FN,
+            vec![ctor.into()],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(is_instance)?)
+    }
+
+    /// Returns the JavaScript `typeof` of the object, like `"object"` or
+    /// `"function"`, as evaluated in the page rather than as categorized by
+    /// the protocol.
+    pub async fn js_typeof(&self) -> Result<String> {
+        let type_of = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() { return typeof this; }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(type_of)?)
+    }
+
+    /// Freezes the object with `Object.freeze`, so its properties can no
+    /// longer be added, removed or changed.
+    pub async fn freeze(&self) -> Result<()> {
+        call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() { Object.freeze(this); }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Whether the object is frozen, see `Object.isFrozen`.
+    pub async fn is_frozen(&self) -> Result<bool> {
+        let frozen = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() { return Object.isFrozen(this); }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(frozen)?)
+    }
+
+    /// Seals the object with `Object.seal`, so its properties can no longer
+    /// be added or removed, while writable ones can still be changed.
+    pub async fn seal(&self) -> Result<()> {
+        call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() { Object.seal(this); }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Whether the object is sealed, see `Object.isSealed`.
+    ///
+    /// Frozen objects are sealed as well.
+    pub async fn is_sealed(&self) -> Result<bool> {
+        let sealed = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() { return Object.isSealed(this); }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(sealed)?)
+    }
+
+    /// Returns the keys of the properties selected by the `options`,
+    /// symbols as their description.
+    ///
+    /// Keys that are shadowed by an own property are only listed once.
+    pub async fn own_keys(&self, options: PropertyOptions) -> Result<Vec<String>> {
+        let keys = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            &format!(
+                "function(prototypeChain, symbols, offset, limit) {{
+                    return ({COLLECT_KEYS_FN})(this, prototypeChain, symbols)
+                        .slice(offset, limit === null ? undefined : offset + limit)
+                        .map(([, name]) => name);
+                }}"
+            ),
+            options.args(),
+            true,
+        )
+        .await?;
+        Ok(value_or_null(keys)?)
+    }
+
+    /// Returns the properties selected by the `options` with their values
+    /// resolved by reference, so nested objects can be inspected further.
+    ///
+    /// Getters are invoked to read accessor properties. A getter that throws
+    /// does not abort the enumeration, the error message is reported for its
+    /// property instead.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::js::PropertyOptions;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let location = page.js_object("location").await?;
+    ///     for (key, value) in location.entries_with(PropertyOptions::new()).await? {
+    ///         match value {
+    ///             Ok(value) => println!("{key}: {:?}", value.value()),
+    ///             Err(err) => println!("{key} threw {err}"),
+    ///         }
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn entries_with(
+        &self,
+        options: PropertyOptions,
+    ) -> Result<Vec<(String, std::result::Result<EvaluationResult, String>)>> {
+        Ok(self.entries_page(options).await?.entries)
+    }
+
+    /// Same as [`JsObject::entries_with`], but also reports how many
+    /// properties there are in total and whether the `limit` of the options
+    /// left some out.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::js::PropertyOptions;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let cache = page.js_object("window.hugeCache").await?;
+    ///     let mut options = PropertyOptions::new().limit(500);
+    ///     loop {
+    ///         let page = cache.entries_page(options).await?;
+    ///         println!("{} of {}", page.entries.len(), page.total);
+    ///         if !page.truncated {
+    ///             break;
+    ///         }
+    ///         options = options.offset(options.offset + page.entries.len());
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn entries_page(&self, options: PropertyOptions) -> Result<PropertyPage> {
+        // the values of the properties that could be read are collected into
+        // an array, the errors of throwing getters are passed along as JSON
+        // keyed by the index of their property
+        let holder = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            &format!(
+                "function(prototypeChain, symbols, offset, limit) {{
+                    const all = ({COLLECT_KEYS_FN})(this, prototypeChain, symbols);
+                    const keys = all.slice(offset, limit === null ? undefined : offset + limit);
+                    const entries = [];
+                    const errors = {{}};
+                    keys.forEach(([key], index) => {{
+                        try {{
+                            entries.push(this[key]);
+                        }} catch (e) {{
+                            errors[index] = String(e);
+                        }}
+                    }});
+                    return {{
+                        names: JSON.stringify(keys.map(([, name]) => name)),
+                        errors: JSON.stringify(errors),
+                        total: all.length,
+                        values: entries,
+                    }};
+                }}"
+            ),
+            options.args(),
+            false,
+        )
+        .await?
+        .object_id
+        .ok_or_else(|| CdpError::msg("No object Id found for entries"))?;
+
+        let result = self.holder_entries(&holder).await;
+        self.tab.release_best_effort(holder).await;
+        let (entries, total) = result?;
+        Ok(PropertyPage {
+            truncated: options.is_truncated(entries.len(), total),
+            entries,
+            total,
+        })
+    }
+
+    /// Reads the entries collected into the holder by `entries_page`, with
+    /// the total number of properties.
+    async fn holder_entries(
+        &self,
+        holder: &RemoteObjectId,
+    ) -> Result<(
+        Vec<(String, std::result::Result<EvaluationResult, String>)>,
+        usize,
+    )> {
+        let mut params = GetPropertiesParams::new(holder.clone());
+        params.own_properties = Some(true);
+        let mut total = 0;
+        let mut names = Vec::new();
+        let mut errors = HashMap::new();
+        let mut values = None;
+        for property in self.tab.execute(params).await?.result.result {
+            let Some(value) = property.value else {
+                continue;
+            };
+            match (property.name.as_str(), value.value) {
+                ("names", Some(serde_json::Value::String(json))) => {
+                    names = serde_json::from_str::<Vec<String>>(&json)?;
+                }
+                ("errors", Some(serde_json::Value::String(json))) => {
+                    errors = serde_json::from_str::<HashMap<usize, String>>(&json)?;
+                }
+                ("total", Some(count)) => total = serde_json::from_value(count)?,
+                ("values", _) => values = value.object_id,
+                _ => {}
+            }
+        }
+        let values = values.ok_or_else(|| CdpError::msg("No object Id found for values"))?;
+
+        let mut params = GetPropertiesParams::new(values);
+        params.own_properties = Some(true);
+        let mut values = self
+            .tab
+            .execute(params)
+            .await?
+            .result
+            .result
+            .into_iter()
+            .filter_map(|p| Some((p.name.parse::<usize>().ok()?, p.value?)))
+            .collect::<Vec<_>>();
+        values.sort_by_key(|(index, _)| *index);
+        let mut values = values.into_iter().map(|(_, value)| value);
+
+        let entries = names
+            .into_iter()
+            .enumerate()
+            .map(|(index, name)| match errors.remove(&index) {
+                Some(err) => (name, Err(err)),
+                None => {
+                    let value = values.next().map(EvaluationResult::new);
+                    (name, value.ok_or_else(|| "Missing value".to_string()))
+                }
+            })
+            .collect();
+        Ok((entries, total))
+    }
+
+    /// Returns the attributes of the properties selected by the `options`.
+    ///
+    /// This inspects the property descriptors without invoking getters. The
+    /// protocol reports all descriptors at once, so the `offset` and `limit`
+    /// of the options only bound the result, not the transfer.
+    pub async fn property_descriptors(
+        &self,
+        options: PropertyOptions,
+    ) -> Result<Vec<PropertyDescriptorInfo>> {
+        let mut params = GetPropertiesParams::new(self.remote_object_id.clone());
+        params.own_properties = Some(!options.prototype_chain);
+        let properties = self.tab.execute(params).await?;
+        Ok(properties
+            .result
+            .result
+            .iter()
+            .filter(|p| options.symbols || p.symbol.is_none())
+            .skip(options.offset)
+            .take(options.limit.unwrap_or(usize::MAX))
+            .map(PropertyDescriptorInfo::from)
+            .collect())
+    }
+
+    /// Resolves the value at the `pointer` path below this object and
+    /// deserializes it, walking all segments in a single call.
+    ///
+    /// Fails with [`CdpError::NotFound`] if a segment does not exist.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::js::JsonPointerSegment;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let response = page.js_object("({ data: [{ id: 42 }] })").await?;
+    ///     let id: u32 = response
+    ///         .get_path(&["data".into(), 0.into(), "id".into()])
+    ///         .await?;
+    ///     assert_eq!(id, 42);
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_path<T: DeserializeOwned>(&self, pointer: &[JsonPointerSegment]) -> Result<T> {
+        let value = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function(path) {
+                let value = this;
+                for (const segment of path) {
+                    if (value === null || value === undefined || !(segment in Object(value))) {
+                        return [];
+                    }
+                    value = value[segment];
+                }
+                return [value];
+            }",
+            vec![serde_json::to_value(pointer)?],
+            true,
+        )
+        .await?;
+        let mut value: Vec<serde_json::Value> = value_or_null(value)?;
+        let value = value.pop().ok_or(CdpError::NotFound)?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Whether this and the `other` remote object are the same JavaScript
+    /// object, i.e. `this === other`.
+    ///
+    /// Resolving the same object twice yields distinct remote object ids, so
+    /// comparing the ids is not sufficient to tell whether two handles
+    /// reference the same object.
+    pub async fn same_object(&self, other: &RemoteObjectId) -> Result<bool> {
+        self.tab.check_object_origin(&self.remote_object_id).await?;
+        let params = CallFunctionOnParams::builder()
+            .function_declaration("function(other) { return this === other; }")
+            .object_id(self.remote_object_id.clone())
+            .argument(CallArgument::builder().object_id(other.clone()).build())
+            .return_by_value(true)
+            .build()
+            .unwrap();
+        let resp = self.tab.execute(params).await?.result;
+        if let Some(exception) = resp.exception_details {
+            return Err(CdpError::JavascriptException(Box::new(exception)));
+        }
+        Ok(value_or_null(resp.result)?)
+    }
+
+    /// Copies the own enumerable properties of the serialized `source` onto
+    /// the object in a single call, like `Object.assign(this, source)`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::js::JsObject;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(config: JsObject) -> Result<()> {
+    ///     config
+    ///         .assign(serde_json::json!({ "theme": "dark", "fontSize": 14 }))
+    ///         .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn assign<T: Serialize>(&self, source: T) -> Result<&Self> {
+        call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function(source) { Object.assign(this, source); }",
+            vec![serde_json::to_value(source)?],
+            true,
+        )
+        .await?;
+        Ok(self)
+    }
+
+    /// Copies the own enumerable properties of the `source` remote object,
+    /// including symbol-keyed ones, onto the object, like
+    /// `Object.assign(this, source)`.
+    ///
+    /// In contrast to [`JsObject::assign`] the values are copied by
+    /// reference, so they may be functions or DOM nodes.
+    pub async fn assign_object(&self, source: &RemoteObjectId) -> Result<&Self> {
+        if self.tab.objects().is_released(&self.remote_object_id)
+            || self.tab.objects().is_released(source)
+        {
+            return Err(CdpError::StaleHandle);
+        }
+        self.tab.check_object_origin(&self.remote_object_id).await?;
+        let params = CallFunctionOnParams::builder()
+            .function_declaration("function(source) { Object.assign(this, source); }")
+            .object_id(self.remote_object_id.clone())
+            .argument(CallArgument::builder().object_id(source.clone()).build())
+            .build()
+            .unwrap();
+        let resp = self.tab.execute(params).await?.result;
+        if let Some(exception) = resp.exception_details {
+            return Err(CdpError::JavascriptException(Box::new(exception)));
+        }
+        Ok(self)
+    }
+
+    /// Returns a stream that polls the property `name` every `interval` and
+    /// yields its value whenever it differs from the last one, starting with
+    /// the current value.
+    ///
+    /// The stream ends after yielding an error, e.g. if the object was
+    /// released. Polling stops when the stream is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::js::JsObject;
+    /// # use chromiumoxide::error::Result;
+    /// # use futures::StreamExt;
+    /// # use std::time::Duration;
+    /// # async fn demo(video: JsObject) -> Result<()> {
+    ///     let mut times = video.watch_property::<f64>("currentTime", Duration::from_millis(250));
+    ///     while let Some(time) = times.next().await {
+    ///         println!("at {}s", time?);
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub fn watch_property<T>(&self, name: impl Into<String>, interval: Duration) -> PropertyWatch<T>
+    where
+        T: DeserializeOwned + PartialEq + Clone + Send + 'static,
+    {
+        let name = name.into();
+        // the same params are sent on every tick
+        let params = CallFunctionOnParams::builder()
+            .function_declaration("function(name) { return this[name]; }")
+            .object_id(self.remote_object_id.clone())
+            .argument(CallArgument::builder().value(name.clone()).build())
+            .return_by_value(true)
+            .build()
+            .unwrap();
+        let tab = Arc::clone(&self.tab);
+        let object_id = self.remote_object_id.clone();
+        // keeps the object accounted as live while it is polled
+        let tracked = self._tracked.clone();
+        let state = PollState::<T> {
+            last: None,
+            first: true,
+            done: false,
+        };
+        let values = futures::stream::unfold(state, move |mut state| {
+            let tab = Arc::clone(&tab);
+            let object_id = object_id.clone();
+            let params = params.clone();
+            let _tracked = tracked.clone();
+            async move {
+                if state.done {
+                    return None;
+                }
+                loop {
+                    if !state.first {
+                        futures_timer::Delay::new(interval).await;
+                    }
+                    state.first = false;
+                    let value = if tab.objects().is_released(&object_id) {
+                        Err(CdpError::StaleHandle)
+                    } else {
+                        let resp = async {
+                            tab.check_object_origin(&object_id).await?;
+                            tab.execute(params.clone()).await
+                        };
+                        match resp.await {
+                            Ok(resp) => match resp.result.exception_details {
+                                Some(exception) => {
+                                    Err(CdpError::JavascriptException(Box::new(exception)))
+                                }
+                                None => value_or_null::<T>(resp.result.result).map_err(Into::into),
+                            },
+                            Err(err) => Err(err),
+                        }
+                    };
+                    match value {
+                        Ok(value) if state.last.as_ref() == Some(&value) => continue,
+                        Ok(value) => {
+                            state.last = Some(value.clone());
+                            return Some((Ok(value), state));
+                        }
+                        Err(err) => {
+                            state.done = true;
+                            return Some((Err(err), state));
+                        }
+                    }
+                }
+            }
+        });
+        PropertyWatch {
+            property: name,
+            values: Box::pin(values),
+            proxy: None,
+        }
+    }
+
+    /// Returns a stream of the values assigned to the property `name`,
+    /// starting with the current value, without polling.
+    ///
+    /// The property is replaced with an accessor that reports every
+    /// assignment through a binding, so changes that bypass the setter, like
+    /// the `currentTime` of a playing video, are not reported, see
+    /// [`JsObject::watch_property`] for those. Assignments of an equal value
+    /// are skipped.
+    ///
+    /// Fails if the property is an own property that is not configurable.
+    /// The original property is restored when the stream is dropped.
+    pub async fn watch_property_via_proxy<T>(
+        &self,
+        name: impl Into<String>,
+    ) -> Result<PropertyWatch<T>>
+    where
+        T: DeserializeOwned + PartialEq + Clone + Send + 'static,
+    {
+        static BINDINGS: AtomicUsize = AtomicUsize::new(0);
+        let name = name.into();
+        let binding = format!(
+            "__chromiumoxide_watch_{}",
+            BINDINGS.fetch_add(1, Ordering::Relaxed)
+        );
+        let events = self.tab.event_listener::<EventBindingCalled>().await?;
+        self.tab
+            .execute(AddBindingParams::new(binding.clone()))
+            .await?;
+
+        // returns a function that restores the original property, or the
+        // reason why the property can not be watched
+        let installed = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function(name, binding) {
+                const notify = globalThis[binding];
+                const own = Object.getOwnPropertyDescriptor(this, name);
+                if (own && !own.configurable) {
+                    return `Property ${name} is not configurable`;
+                }
+                let inherited;
+                for (let proto = Object.getPrototypeOf(this); !own && proto; proto = Object.getPrototypeOf(proto)) {
+                    inherited = Object.getOwnPropertyDescriptor(proto, name);
+                    if (inherited) {
+                        break;
+                    }
+                }
+                const base = own || inherited
+                    || { value: undefined, writable: true, enumerable: true };
+                const accessor = !!(base.get || base.set);
+                let value = base.value;
+                const report = target => notify(JSON.stringify(target[name]) ?? 'null');
+                Object.defineProperty(this, name, {
+                    configurable: true,
+                    enumerable: base.enumerable,
+                    get() {
+                        return base.get ? base.get.call(this) : value;
+                    },
+                    set(next) {
+                        if (base.set) {
+                            base.set.call(this, next);
+                        } else if (!accessor && base.writable !== false) {
+                            value = next;
+                        } else {
+                            return;
+                        }
+                        report(this);
+                    },
+                });
+                report(this);
+                const target = this;
+                return function() {
+                    const current = accessor ? undefined : target[name];
+                    delete target[name];
+                    if (own) {
+                        Object.defineProperty(target, name, accessor ? own : { ...own, value: current });
+                    } else if (!accessor) {
+                        target[name] = current;
+                    }
+                };
+            }",
+            vec![name.clone().into(), binding.clone().into()],
+            false,
+        )
+        .await;
+        let restore = match installed {
+            Ok(RemoteObject {
+                object_id: Some(restore),
+                ..
+            }) => restore,
+            Ok(reason) => {
+                self.tab.execute_detached(RemoveBindingParams::new(binding));
+                let reason: Option<String> = value_or_null(reason)?;
+                return Err(CdpError::msg(
+                    reason.unwrap_or_else(|| format!("Failed to watch property {name}")),
+                ));
+            }
+            Err(err) => {
+                self.tab.execute_detached(RemoveBindingParams::new(binding));
+                return Err(err);
+            }
+        };
+
+        let state = (events, None::<T>);
+        let watched = binding.clone();
+        let values = futures::stream::unfold(state, move |(mut events, mut last)| {
+            let watched = watched.clone();
+            async move {
+                loop {
+                    let event = events.next().await?;
+                    if event.name != watched {
+                        continue;
+                    }
+                    match serde_json::from_str::<T>(&event.payload) {
+                        Ok(value) if last.as_ref() == Some(&value) => continue,
+                        Ok(value) => {
+                            last = Some(value.clone());
+                            return Some((Ok(value), (events, last)));
+                        }
+                        Err(err) => return Some((Err(err.into()), (events, last))),
+                    }
+                }
+            }
+        });
+        Ok(PropertyWatch {
+            property: name,
+            values: Box::pin(values),
+            proxy: Some(PropertyProxy {
+                tab: Arc::clone(&self.tab),
+                restore,
+                binding,
+            }),
+        })
+    }
+
+    /// Returns the descriptions of the object's own symbol-keyed properties,
+    /// as returned by `Object.getOwnPropertySymbols`.
+    ///
+    /// Symbols created without a description yield `None`.
+    pub async fn own_symbol_descriptions(&self) -> Result<Vec<Option<String>>> {
+        let descriptions = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() {
+                return Object.getOwnPropertySymbols(this).map(s => s.description ?? null);
+            }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(descriptions)?)
+    }
+}
+
+/// A live handle to a JavaScript
+/// [ReadableStream](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStream)
+/// in the page, e.g. the `body` of a fetch response.
+pub struct JsReadableStream {
+    /// The Unique object identifier
+    pub remote_object_id: RemoteObjectId,
+    tab: Arc<PageInner>,
+    /// The description of the stream when the handle was created, usually
+    /// `ReadableStream`
+    description: Option<String>,
+    _tracked: Option<Arc<ObjectEntry>>,
+}
+
+impl JsReadableStream {
+    pub(crate) fn new(tab: Arc<PageInner>, remote_object_id: RemoteObjectId) -> Self {
+        let _tracked = tab.objects().track(&remote_object_id, "ReadableStream");
+        Self {
+            remote_object_id,
+            tab,
+            description: None,
+            _tracked,
+        }
+    }
+
+    /// Locks the stream and returns its default reader.
+    pub async fn get_reader(&self) -> Result<JsObject> {
+        let reader = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() { return this.getReader(); }",
+            vec![],
+            false,
+        )
+        .await?;
+        let object_id = reader
+            .object_id
+            .ok_or_else(|| CdpError::msg("No object Id found for reader"))?;
+        Ok(JsObject::new(
+            Arc::clone(&self.tab),
+            object_id,
+            "ReadableStreamDefaultReader",
+        )
+        .with_description(reader.description))
+    }
+
+    /// Reads the stream to completion and returns the concatenated chunks.
+    ///
+    /// Chunks must be `Uint8Array`s (or other buffers), as produced by
+    /// byte streams like fetch response bodies, or strings, which are UTF-8
+    /// encoded. Every chunk takes a round trip. The stream is locked while
+    /// reading, the lock is released afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let body = page
+    ///         .js_readable_stream("fetch('/data.bin').then(res => res.body)")
+    ///         .await?;
+    ///     let bytes = body.read_all().await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_all(&self) -> Result<Vec<u8>> {
+        let reader = self.get_reader().await?;
+        let read = format!(
+            "async function() {{
+                const {{ done, value }} = await this.read();
+                if (done) return null;
+                const chunk = typeof value === 'string' ? new TextEncoder().encode(value) : value;
+                return ({ENCODE_BASE64_FN})(chunk);
+            }}"
+        );
+        let mut bytes = Vec::new();
+        let result = loop {
+            let chunk = await_function_on(&self.tab, &reader.remote_object_id, &read, vec![]).await;
+            match chunk.and_then(|chunk| Ok(value_or_null::<Option<String>>(chunk)?)) {
+                Ok(Some(chunk)) => match utils::base64::decode(chunk) {
+                    Ok(chunk) => bytes.extend(chunk),
+                    Err(err) => break Err(err.into()),
+                },
+                Ok(None) => break Ok(bytes),
+                Err(err) => break Err(err),
+            }
+        };
+        // a failed cleanup must not hide the result of the read
+        if let Err(err) = call_function_on(
+            &self.tab,
+            &reader.remote_object_id,
+            "function() { this.releaseLock(); }",
+            vec![],
+            true,
+        )
+        .await
+        {
+            tracing::debug!("Failed to release the lock of a stream: {err:?}");
+        }
+        self.tab
+            .release_best_effort(reader.remote_object_id.clone())
+            .await;
+        result
+    }
+}
+
+/// A live handle to a JavaScript
+/// [Function](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Function)
+/// in the page, see [`Page::js_function`].
+pub struct JsFunction {
+    /// The Unique object identifier
+    pub remote_object_id: RemoteObjectId,
+    tab: Arc<PageInner>,
+    /// The source of the function as described when the handle was created
+    description: Option<String>,
+    _tracked: Option<Arc<ObjectEntry>>,
+}
+
+impl JsFunction {
+    pub(crate) fn new(tab: Arc<PageInner>, remote_object_id: RemoteObjectId) -> Self {
+        let _tracked = tab.objects().track(&remote_object_id, "Function");
+        Self {
+            remote_object_id,
+            tab,
+            description: None,
+            _tracked,
+        }
+    }
+
+    /// Returns the source text of the function, as returned by
+    /// `Function.prototype.toString`.
+    ///
+    /// Built-in and bound functions have no source, for these the text is
+    /// like `function push() { [native code] }`, see
+    /// [`JsFunction::is_native`].
+    pub async fn to_source(&self) -> Result<String> {
+        let source = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() { return Function.prototype.toString.call(this); }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(source)?)
+    }
+
+    /// Whether this is a built-in or bound function without source text.
+    pub async fn is_native(&self) -> Result<bool> {
+        Ok(is_native_source(&self.to_source().await?))
+    }
+
+    /// Returns the `name` of the function, empty for anonymous functions.
+    pub async fn name(&self) -> Result<String> {
+        let name = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() { return typeof this.name === 'string' ? this.name : ''; }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(name)?)
+    }
+}
+
+/// Whether the source text, as returned by `Function.prototype.toString`,
+/// is the placeholder of a function without source.
+fn is_native_source(source: &str) -> bool {
+    source
+        .trim_end()
+        .strip_suffix('}')
+        .and_then(|body| body.trim_end().strip_suffix("[native code]"))
+        .map(|body| body.trim_end().ends_with('{'))
+        .unwrap_or_default()
+}
+
+/// A live handle to a JavaScript
+/// [Array](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array)
+/// in the page.
+///
+/// In contrast to deserializing an evaluation result into a `Vec<T>`, this
+/// references the array itself, so that it can be mutated in place.
+pub struct JsArray {
+    /// The Unique object identifier
+    pub remote_object_id: RemoteObjectId,
+    tab: Arc<PageInner>,
+    /// The description of the array when the handle was created, like
+    /// `Array(3)`
+    description: Option<String>,
+    _tracked: Option<Arc<ObjectEntry>>,
+}
+
+impl JsArray {
+    pub(crate) fn new(tab: Arc<PageInner>, remote_object_id: RemoteObjectId) -> Self {
+        let _tracked = tab.objects().track(&remote_object_id, "Array");
+        Self {
+            remote_object_id,
+            tab,
+            description: None,
+            _tracked,
+        }
+    }
+
+    async fn call(
+        &self,
+        function_declaration: &str,
+        args: Vec<serde_json::Value>,
+        return_by_value: bool,
+    ) -> Result<RemoteObject> {
+        call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            function_declaration,
+            args,
+            return_by_value,
+        )
+        .await
+    }
+
+    /// Returns the `length` of the array.
+    pub async fn length(&self) -> Result<usize> {
+        let length = self
+            .call("function() { return this.length; }", vec![], true)
+            .await?;
+        Ok(EvaluationResult::new(length).into_value()?)
+    }
+
+    /// Returns a handle to the element at the `index`, or `None` if the index
+    /// is out of bounds.
+    ///
+    /// Objects are returned by reference, see [`EvaluationResult::object`].
+    pub async fn get(&self, index: usize) -> Result<Option<EvaluationResult>> {
+        if index >= self.length().await? {
+            return Ok(None);
+        }
+        let element = self
+            .call("function(i) { return this[i]; }", vec![index.into()], false)
+            .await?;
+        Ok(Some(EvaluationResult::new(element)))
+    }
+
+    /// Appends the `value` to the end of the array and returns the new length.
+    pub async fn push<T: Serialize>(&self, value: T) -> Result<usize> {
+        let length = self
+            .call(
+                "function(value) { return this.push(value); }",
+                vec![serde_json::to_value(value)?],
+                true,
+            )
+            .await?;
+        Ok(EvaluationResult::new(length).into_value()?)
+    }
+
+    /// Removes the last element of the array and returns it, or `None` if the
+    /// array is empty.
+    pub async fn pop<T: DeserializeOwned>(&self) -> Result<Option<T>> {
+        self.take("function() { return this.length ? [this.pop()] : []; }")
+            .await
+    }
+
+    /// Removes the first element of the array and returns it, or `None` if the
+    /// array is empty.
+    pub async fn shift<T: DeserializeOwned>(&self) -> Result<Option<T>> {
+        self.take("function() { return this.length ? [this.shift()] : []; }")
+            .await
+    }
+
+    async fn take<T: DeserializeOwned>(&self, function_declaration: &str) -> Result<Option<T>> {
+        let removed: Vec<T> =
+            EvaluationResult::new(self.call(function_declaration, vec![], true).await?)
+                .into_value()?;
+        Ok(removed.into_iter().next())
+    }
+
+    /// Returns a copy of the array's current content.
+    pub async fn to_vec<T: DeserializeOwned>(&self) -> Result<Vec<T>> {
+        let values = self
+            .call("function() { return this; }", vec![], true)
+            .await?;
+        Ok(EvaluationResult::new(values).into_value()?)
+    }
+}
+
+/// A live handle to a JavaScript
+/// [URL](https://developer.mozilla.org/en-US/docs/Web/API/URL) object in the
+/// page.
+pub struct JsUrl {
+    /// The Unique object identifier
+    pub remote_object_id: RemoteObjectId,
+    tab: Arc<PageInner>,
+    /// The description of the object, `URL`
+    description: Option<String>,
+    _tracked: Option<Arc<ObjectEntry>>,
+}
+
+impl JsUrl {
+    /// Creates a new `URL` object in the page, the `href` is resolved against
+    /// the `base` if it is relative.
+    ///
+    /// An invalid URL results in a [`CdpError::JavascriptException`] with the
+    /// `TypeError` thrown by the `URL` constructor.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::js::JsUrl;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let url = JsUrl::new(&page, "/search?q=rust", Some("https://example.com")).await?;
+    ///     url.search_params().await?.append("page", "2").await?;
+    ///     assert_eq!(url.href().await?, "https://example.com/search?q=rust&page=2");
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn new(page: &Page, href: &str, base: Option<&str>) -> Result<Self> {
+        let tab = Arc::clone(page.inner());
+        let params = CallFunctionOnParams::builder()
+            .function_declaration("(href, base) => new URL(href, base ?? undefined)")
+            .argument(CallArgument::builder().value(href).build())
+            .argument(CallArgument::builder().value(base).build())
+            .return_by_value(false)
+            .build()
+            .unwrap();
+        let url = tab.evaluate_function(params).await?;
+        let remote_object_id = url
+            .object()
+            .object_id
+            .clone()
+            .ok_or_else(|| CdpError::msg("No object Id found for URL"))?;
+        let _tracked = tab.objects().track(&remote_object_id, "URL");
+        Ok(Self {
+            remote_object_id,
+            tab,
+            description: url.object().description.clone(),
+            _tracked,
+        })
+    }
+
+    async fn call(
+        &self,
+        function_declaration: &str,
+        args: Vec<serde_json::Value>,
+        return_by_value: bool,
+    ) -> Result<RemoteObject> {
+        call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            function_declaration,
+            args,
+            return_by_value,
+        )
+        .await
+    }
+
+    async fn property(&self, name: &str) -> Result<String> {
+        let value = self
+            .call(
+                "function(name) { return this[name]; }",
+                vec![name.into()],
+                true,
+            )
+            .await?;
+        Ok(EvaluationResult::new(value).into_value()?)
+    }
+
+    async fn set_property(&self, name: &str, value: &str) -> Result<&Self> {
+        self.call(
+            "function(name, value) { this[name] = value; }",
+            vec![name.into(), value.into()],
+            true,
+        )
+        .await?;
+        Ok(self)
+    }
+
+    /// Returns the whole URL.
+    pub async fn href(&self) -> Result<String> {
+        self.property("href").await
+    }
+
+    /// Sets the whole URL.
+    pub async fn set_href(&self, href: &str) -> Result<&Self> {
+        self.set_property("href", href).await
+    }
+
+    /// Returns the protocol scheme of the URL, including the final `:`.
+    pub async fn protocol(&self) -> Result<String> {
+        self.property("protocol").await
+    }
+
+    /// Sets the protocol scheme of the URL.
+    pub async fn set_protocol(&self, protocol: &str) -> Result<&Self> {
+        self.set_property("protocol", protocol).await
+    }
+
+    /// Returns the host of the URL, including the port if it is not the
+    /// default one.
+    pub async fn host(&self) -> Result<String> {
+        self.property("host").await
+    }
+
+    /// Sets the host of the URL.
+    pub async fn set_host(&self, host: &str) -> Result<&Self> {
+        self.set_property("host", host).await
+    }
+
+    /// Returns the path of the URL.
+    pub async fn pathname(&self) -> Result<String> {
+        self.property("pathname").await
+    }
+
+    /// Sets the path of the URL.
+    pub async fn set_pathname(&self, pathname: &str) -> Result<&Self> {
+        self.set_property("pathname", pathname).await
+    }
+
+    /// Returns the query string of the URL, including the leading `?`.
+    pub async fn search(&self) -> Result<String> {
+        self.property("search").await
+    }
+
+    /// Sets the query string of the URL.
+    pub async fn set_search(&self, search: &str) -> Result<&Self> {
+        self.set_property("search", search).await
+    }
+
+    /// Returns the fragment of the URL, including the leading `#`.
+    pub async fn hash(&self) -> Result<String> {
+        self.property("hash").await
+    }
+
+    /// Sets the fragment of the URL.
+    pub async fn set_hash(&self, hash: &str) -> Result<&Self> {
+        self.set_property("hash", hash).await
+    }
+
+    /// Returns a handle to the `searchParams` of the URL.
+    ///
+    /// Changes made via the returned handle are reflected in the URL.
+    pub async fn search_params(&self) -> Result<JsUrlSearchParams> {
+        let params = self
+            .call("function() { return this.searchParams; }", vec![], false)
+            .await?;
+        let remote_object_id = params
+            .object_id
+            .ok_or_else(|| CdpError::msg("No object Id found for URLSearchParams"))?;
+        let _tracked = self
+            .tab
+            .objects()
+            .track(&remote_object_id, "URLSearchParams");
+        Ok(JsUrlSearchParams {
+            remote_object_id,
+            tab: Arc::clone(&self.tab),
+            description: params.description,
+            _tracked,
+        })
+    }
+}
+
+/// A live handle to a JavaScript
+/// [URLSearchParams](https://developer.mozilla.org/en-US/docs/Web/API/URLSearchParams)
+/// object in the page.
+pub struct JsUrlSearchParams {
+    /// The Unique object identifier
+    pub remote_object_id: RemoteObjectId,
+    tab: Arc<PageInner>,
+    /// The description of the object, `URLSearchParams`
+    description: Option<String>,
+    _tracked: Option<Arc<ObjectEntry>>,
+}
+
+impl JsUrlSearchParams {
+    async fn invoke(
+        &self,
+        function_declaration: &str,
+        args: Vec<serde_json::Value>,
+    ) -> Result<RemoteObject> {
+        call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            function_declaration,
+            args,
+            true,
+        )
+        .await
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        function_declaration: &str,
+        args: Vec<serde_json::Value>,
+    ) -> Result<T> {
+        Ok(value_or_null(
+            self.invoke(function_declaration, args).await?,
+        )?)
+    }
+
+    /// Returns the first value associated with the parameter `name`.
+    pub async fn get(&self, name: &str) -> Result<Option<String>> {
+        self.call(
+            "function(name) { return this.get(name); }",
+            vec![name.into()],
+        )
+        .await
+    }
+
+    /// Returns all the values associated with the parameter `name`.
+    pub async fn get_all(&self, name: &str) -> Result<Vec<String>> {
+        self.call(
+            "function(name) { return this.getAll(name); }",
+            vec![name.into()],
+        )
+        .await
+    }
+
+    /// Sets the value of the parameter `name`, removing all other values.
+    pub async fn set(&self, name: &str, value: &str) -> Result<&Self> {
+        self.invoke(
+            "function(name, value) { this.set(name, value); }",
+            vec![name.into(), value.into()],
+        )
+        .await?;
+        Ok(self)
+    }
+
+    /// Appends a new value for the parameter `name`.
+    pub async fn append(&self, name: &str, value: &str) -> Result<&Self> {
+        self.invoke(
+            "function(name, value) { this.append(name, value); }",
+            vec![name.into(), value.into()],
+        )
+        .await?;
+        Ok(self)
+    }
+
+    /// Removes all values of the parameter `name`.
+    pub async fn delete(&self, name: &str) -> Result<&Self> {
+        self.invoke("function(name) { this.delete(name); }", vec![name.into()])
+            .await?;
+        Ok(self)
+    }
+
+    /// Whether the parameter `name` exists.
+    pub async fn has(&self, name: &str) -> Result<bool> {
+        self.call(
+            "function(name) { return this.has(name); }",
+            vec![name.into()],
+        )
+        .await
+    }
+
+    /// Returns all parameters as `(name, value)` pairs in order.
+    pub async fn entries_vec(&self) -> Result<Vec<(String, String)>> {
+        self.call("function() { return Array.from(this.entries()); }", vec![])
+            .await
+    }
+}
+
+/// A live handle to a JavaScript
+/// [Headers](https://developer.mozilla.org/en-US/docs/Web/API/Headers) object
+/// in the page, as used by `fetch`.
+pub struct JsHeaders {
+    /// The Unique object identifier
+    pub remote_object_id: RemoteObjectId,
+    tab: Arc<PageInner>,
+    /// The description of the object, `Headers`
+    description: Option<String>,
+    _tracked: Option<Arc<ObjectEntry>>,
+}
+
+impl JsHeaders {
+    pub(crate) fn new(tab: Arc<PageInner>, remote_object_id: RemoteObjectId) -> Self {
+        let _tracked = tab.objects().track(&remote_object_id, "Headers");
+        Self {
+            remote_object_id,
+            tab,
+            description: None,
+            _tracked,
+        }
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        function_declaration: &str,
+        args: Vec<serde_json::Value>,
+    ) -> Result<T> {
+        let value = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            function_declaration,
+            args,
+            true,
+        )
+        .await?;
+        Ok(value_or_null(value)?)
+    }
+
+    /// Returns the values of the header `name` joined with `, `, or `None` if
+    /// the header is not set.
+    pub async fn get(&self, name: &str) -> Result<Option<String>> {
+        self.call(
+            "function(name) { return this.get(name); }",
+            vec![name.into()],
+        )
+        .await
+    }
+
+    /// Whether the header `name` is set.
+    pub async fn has(&self, name: &str) -> Result<bool> {
+        self.call(
+            "function(name) { return this.has(name); }",
+            vec![name.into()],
+        )
+        .await
+    }
+
+    /// Returns the (lowercase) names of all headers.
+    pub async fn keys(&self) -> Result<Vec<String>> {
+        self.call("function() { return Array.from(this.keys()); }", vec![])
+            .await
+    }
+
+    /// Returns all headers, the values of headers that are set multiple times
+    /// (like `set-cookie`) are joined with `, `.
+    pub async fn to_map(&self) -> Result<HashMap<String, String>> {
+        self.call(
+            "function() {
+                const headers = {};
+                for (const [name, value] of this) {
+                    headers[name] = name in headers ? `${headers[name]}, ${value}` : value;
+                }
+                return headers;
+            }",
+            vec![],
+        )
+        .await
+    }
+}
+
+/// A stream of the changing values of a property, see
+/// [`JsObject::watch_property`] and [`JsObject::watch_property_via_proxy`].
+pub struct PropertyWatch<T> {
+    property: String,
+    values: Pin<Box<dyn Stream<Item = Result<T>> + Send>>,
+    proxy: Option<PropertyProxy>,
+}
+
+/// The state of the stream of [`JsObject::watch_property`].
+struct PollState<T> {
+    last: Option<T>,
+    first: bool,
+    done: bool,
+}
+
+/// The accessor installed by [`JsObject::watch_property_via_proxy`].
+struct PropertyProxy {
+    tab: Arc<PageInner>,
+    /// The function that restores the original property
+    restore: RemoteObjectId,
+    binding: String,
+}
+
+impl<T> PropertyWatch<T> {
+    /// The name of the watched property.
+    pub fn property(&self) -> &str {
+        &self.property
+    }
+}
+
+impl<T> fmt::Debug for PropertyWatch<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PropertyWatch")
+            .field("property", &self.property)
+            .field("proxy", &self.proxy.is_some())
+            .finish()
+    }
+}
+
+impl<T> Stream for PropertyWatch<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.values.as_mut().poll_next(cx)
+    }
+}
+
+impl<T> Drop for PropertyWatch<T> {
+    fn drop(&mut self) {
+        if let Some(proxy) = self.proxy.take() {
+            proxy.tab.execute_detached(
+                CallFunctionOnParams::builder()
+                    .function_declaration("function() { this(); }")
+                    .object_id(proxy.restore.clone())
+                    .build()
+                    .unwrap(),
+            );
+            proxy
+                .tab
+                .execute_detached(ReleaseObjectParams::new(proxy.restore));
+            proxy
+                .tab
+                .execute_detached(RemoveBindingParams::new(proxy.binding));
+        }
+    }
+}
+
+/// A live handle to the
+/// [Selection](https://developer.mozilla.org/en-US/docs/Web/API/Selection) of
+/// a document, see [`Page::selection`].
+pub struct JsSelection {
+    /// The Unique object identifier
+    pub remote_object_id: RemoteObjectId,
+    tab: Arc<PageInner>,
+    /// The description of the object, `Selection`
+    description: Option<String>,
+    _tracked: Option<Arc<ObjectEntry>>,
+}
+
+impl JsSelection {
+    pub(crate) fn new(tab: Arc<PageInner>, remote_object_id: RemoteObjectId) -> Self {
+        let _tracked = tab.objects().track(&remote_object_id, "Selection");
+        Self {
+            remote_object_id,
+            tab,
+            description: None,
+            _tracked,
+        }
+    }
+
+    async fn node(&self, function_declaration: &str) -> Result<Option<Element>> {
+        let node = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            function_declaration,
+            vec![],
+            false,
+        )
+        .await?;
+        match node.object_id {
+            Some(object_id) => Ok(Some(
+                Element::from_object_id(Arc::clone(&self.tab), object_id).await?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Calls the function with the `node` and the `offset` as arguments.
+    async fn call_with_node(
+        &self,
+        function_declaration: &str,
+        node: &Element,
+        offset: u32,
+    ) -> Result<()> {
+        if self.tab.objects().is_released(&self.remote_object_id)
+            || self.tab.objects().is_released(&node.remote_object_id)
+        {
+            return Err(CdpError::StaleHandle);
+        }
+        self.tab.check_object_origin(&self.remote_object_id).await?;
+        let params = CallFunctionOnParams::builder()
+            .function_declaration(function_declaration)
+            .object_id(self.remote_object_id.clone())
+            .argument(
+                CallArgument::builder()
+                    .object_id(node.remote_object_id.clone())
+                    .build(),
+            )
+            .argument(CallArgument::builder().value(offset).build())
+            .build()
+            .unwrap();
+        let resp = self.tab.execute(params).await?.result;
+        if let Some(exception) = resp.exception_details {
+            return Err(CdpError::JavascriptException(Box::new(exception)));
+        }
+        Ok(())
+    }
+
+    /// The node in which the selection begins, `None` if nothing is
+    /// selected.
+    ///
+    /// This is usually a text node, which only supports the generic node
+    /// methods of [`Element`].
+    pub async fn anchor_node(&self) -> Result<Option<Element>> {
+        self.node("function() { return this.anchorNode; }").await
+    }
+
+    /// The node in which the selection ends, `None` if nothing is selected.
+    pub async fn focus_node(&self) -> Result<Option<Element>> {
+        self.node("function() { return this.focusNode; }").await
+    }
+
+    /// The number of ranges in the selection.
+    pub async fn range_count(&self) -> Result<u32> {
+        let count = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() { return this.rangeCount; }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(count)?)
+    }
+
+    /// The selected text.
+    pub async fn to_string(&self) -> Result<String> {
+        let text = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() { return this.toString(); }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(text)?)
+    }
+
+    /// Collapses the selection to the `offset` within the `node`.
+    pub async fn collapse(&self, node: &Element, offset: u32) -> Result<&Self> {
+        self.call_with_node(
+            "function(node, offset) { this.collapse(node, offset); }",
+            node,
+            offset,
+        )
+        .await?;
+        Ok(self)
+    }
+
+    /// Moves the focus of the selection to the `offset` within the `node`,
+    /// keeping its anchor.
+    pub async fn extend(&self, node: &Element, offset: u32) -> Result<&Self> {
+        self.call_with_node(
+            "function(node, offset) { this.extend(node, offset); }",
+            node,
+            offset,
+        )
+        .await?;
+        Ok(self)
+    }
+
+    /// Selects all children of the `node`.
+    ///
+    /// # Example select the text of a paragraph
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let paragraph = page.find_element("p").await?;
+    ///     let selection = page.selection().await?.unwrap();
+    ///     selection.select_all_children(&paragraph).await?;
+    ///     let text = selection.to_string().await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn select_all_children(&self, node: &Element) -> Result<&Self> {
+        self.call_with_node("function(node) { this.selectAllChildren(node); }", node, 0)
+            .await?;
+        Ok(self)
+    }
+
+    /// Removes all ranges, leaving nothing selected.
+    pub async fn remove_all_ranges(&self) -> Result<&Self> {
+        call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() { this.removeAllRanges(); }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(self)
+    }
+}
+
+/// Calls the function with the remote object as `this` and the `args` as its
+/// arguments.
+pub(crate) async fn call_function_on(
+    tab: &PageInner,
+    object_id: &RemoteObjectId,
+    function_declaration: &str,
+    args: Vec<serde_json::Value>,
+    return_by_value: bool,
+) -> Result<RemoteObject> {
+    call_function_on_with(
+        tab,
+        object_id,
+        function_declaration,
+        args,
+        return_by_value,
+        false,
+    )
+    .await
+}
+
+/// Same as [`call_function_on`] but awaits the returned promise and returns
+/// the result by value.
+pub(crate) async fn await_function_on(
+    tab: &PageInner,
+    object_id: &RemoteObjectId,
+    function_declaration: &str,
+    args: Vec<serde_json::Value>,
+) -> Result<RemoteObject> {
+    call_function_on_with(tab, object_id, function_declaration, args, true, true).await
+}
+
+/// Same as [`call_function_on`] but optionally awaits the returned promise.
+pub(crate) async fn call_function_on_with(
+    tab: &PageInner,
+    object_id: &RemoteObjectId,
+    function_declaration: &str,
+    args: Vec<serde_json::Value>,
+    return_by_value: bool,
+    await_promise: bool,
+) -> Result<RemoteObject> {
+    if tab.objects().is_released(object_id) {
+        return Err(CdpError::StaleHandle);
+    }
+    tab.check_object_origin(object_id).await?;
+    let mut params = CallFunctionOnParams::builder()
+        .function_declaration(function_declaration)
+        .object_id(object_id.clone())
+        .return_by_value(return_by_value)
+        .await_promise(await_promise)
+        .build()
+        .unwrap();
+    params.arguments = Some(
+        args.into_iter()
+            .map(|value| CallArgument::builder().value(value).build())
+            .collect(),
+    );
+    let resp = tab.execute(params).await?.result;
+    if let Some(exception) = resp.exception_details {
+        return Err(CdpError::JavascriptException(Box::new(exception)));
+    }
+    Ok(resp.result)
+}
+
+/// A JS function that collects the `[key, name]` pairs of the properties of
+/// an object, where the name is the description for symbol keys.
+const COLLECT_KEYS_FN: &str = "function(object, prototypeChain, symbols) {
+    const keys = [];
+    const seen = new Set();
+    for (let o = object; o !== null; o = prototypeChain ? Object.getPrototypeOf(o) : null) {
+        for (const key of Reflect.ownKeys(o)) {
+            if ((typeof key === 'symbol' && !symbols) || seen.has(key)) {
+                continue;
+            }
+            seen.add(key);
+            keys.push([key, typeof key === 'symbol' ? (key.description ?? '') : key]);
+        }
+    }
+    return keys;
+}";
+
+/// A JS function that encodes an `ArrayBuffer` or a typed array as base64.
+pub(crate) const ENCODE_BASE64_FN: &str = "function(value) {
+    const bytes = ArrayBuffer.isView(value)
+        ? new Uint8Array(value.buffer, value.byteOffset, value.byteLength)
+        : new Uint8Array(value);
+    let binary = '';
+    for (let i = 0; i < bytes.length; i += 0x8000) {
+        binary += String.fromCharCode.apply(null, bytes.subarray(i, i + 0x8000));
+    }
+    return btoa(binary);
+}";
+
+/// A JS function that returns the size in bytes of the UTF-8 encoded JSON
+/// of `this.value`, `0` if it has no JSON representation.
+pub(crate) const JSON_SIZE_FN: &str = "function() {
+    let json;
+    try {
+        json = JSON.stringify(this.value, (key, value) =>
+            typeof value === 'bigint' ? value.toString() : value);
+    } catch (e) {
+        // like a cyclic object, returning it by value fails on its own
+        return 0;
+    }
+    return json === undefined ? 0 : new TextEncoder().encode(json).length;
+}";
+
+/// Deserializes the value of the remote object, treating a missing value
+/// (`null` or `undefined`) as `null`.
+pub(crate) fn value_or_null<T: DeserializeOwned>(object: RemoteObject) -> serde_json::Result<T> {
+    match (object.value, object.unserializable_value) {
+        (Some(value), _) => serde_json::from_value(value),
+        (None, Some(value)) => from_unserializable(value.as_ref()),
+        (None, None) => serde_json::from_value(serde_json::Value::Null),
+    }
+}
+
+/// Whether `this` is an instance of the global constructor with the given
+/// name. Nodes are checked against the globals of their own window, so nodes
+/// of frames are instances of the frame's `HTMLElement`.
+pub(crate) const INSTANCE_OF_NAMED_FN: &str = "function(name) {
+    const window = (this && this.ownerDocument && this.ownerDocument.defaultView) || globalThis;
+    const ctor = window[name];
+    try {
+        return typeof ctor === 'function' && this instanceof ctor;
+    } catch (e) {
+        return false;
+    }
+}";
+
+/// Deserializes a primitive value that has no JSON representation, as
+/// reported in `unserializableValue`: `Infinity`, `-Infinity`, `NaN`, `-0`
+/// or a `BigInt` like `12n`.
+fn from_unserializable<T: DeserializeOwned>(value: &str) -> serde_json::Result<T> {
+    use serde::de::value::{F64Deserializer, I128Deserializer, I64Deserializer, U64Deserializer};
+    use serde::de::Error;
+
+    let float = match value {
+        "Infinity" => Some(f64::INFINITY),
+        "-Infinity" => Some(f64::NEG_INFINITY),
+        "NaN" => Some(f64::NAN),
+        "-0" => Some(-0.0),
+        _ => None,
+    };
+    if let Some(float) = float {
+        return T::deserialize(F64Deserializer::<serde_json::Error>::new(float));
+    }
+    let Some(digits) = value.strip_suffix('n') else {
+        return Err(serde_json::Error::custom(format!(
+            "Unsupported unserializable value {value}"
+        )));
+    };
+    if let Ok(int) = digits.parse::<i64>() {
+        T::deserialize(I64Deserializer::<serde_json::Error>::new(int))
+    } else if let Ok(int) = digits.parse::<u64>() {
+        T::deserialize(U64Deserializer::<serde_json::Error>::new(int))
+    } else if let Ok(int) = digits.parse::<i128>() {
+        T::deserialize(I128Deserializer::<serde_json::Error>::new(int))
+    } else {
+        Err(serde_json::Error::custom(format!(
+            "BigInt {value} is out of range"
+        )))
+    }
+}
+
+/// A live handle to the `document` of a frame, see [`Page::frame_document`].
+pub struct JsDocument {
+    /// The Unique object identifier
+    pub remote_object_id: RemoteObjectId,
+    frame_id: FrameId,
+    tab: Arc<PageInner>,
+    /// The description of the document node, like `#document`
+    description: Option<String>,
+    _tracked: Option<Arc<ObjectEntry>>,
+}
+
+impl JsDocument {
+    pub(crate) fn new(
+        tab: Arc<PageInner>,
+        remote_object_id: RemoteObjectId,
+        frame_id: FrameId,
+    ) -> Self {
+        let _tracked = tab.objects().track(&remote_object_id, "Document");
+        Self {
+            remote_object_id,
+            frame_id,
+            tab,
+            description: None,
+            _tracked,
+        }
+    }
+
+    /// The frame this is the document of.
+    pub fn frame_id(&self) -> &FrameId {
+        &self.frame_id
+    }
+
+    /// The URL of the document.
+    pub async fn url(&self) -> Result<String> {
+        let url = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() { return this.URL; }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(url)?)
+    }
+
+    /// Returns the first element in the document which matches the given CSS
+    /// selector, `None` if there is none.
+    pub async fn query_selector(&self, selector: impl AsRef<str>) -> Result<Option<Element>> {
+        let element = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function(selector) { return this.querySelector(selector); }",
+            vec![selector.as_ref().into()],
+            false,
+        )
+        .await?;
+        match element.object_id {
+            Some(object_id) => Ok(Some(
+                Element::from_object_id(Arc::clone(&self.tab), object_id).await?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Parses the `html` into a new document fragment, like
+    /// `template.innerHTML = html`.
+    ///
+    /// The fragment is parsed without executing scripts, and scripts it
+    /// contains do not run when the fragment is inserted either. Malformed
+    /// HTML is recovered from like the browser does.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let document = page.document().await?;
+    ///     let rows = document
+    ///         .create_fragment_from_html("<li>one</li><li>two</li><li>three</li>")
+    ///         .await?;
+    ///     page.find_element("ul").await?.append_fragment(&rows).await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_fragment_from_html(
+        &self,
+        html: impl AsRef<str>,
+    ) -> Result<JsDocumentFragment> {
+        let fragment = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function(html) {
+                const template = this.createElement('template');
+                template.innerHTML = html;
+                return template.content;
+            }",
+            vec![html.as_ref().into()],
+            false,
+        )
+        .await?;
+        let remote_object_id = fragment
+            .object_id
+            .ok_or_else(|| CdpError::msg("No object Id found for DocumentFragment"))?;
+        Ok(
+            JsDocumentFragment::new(Arc::clone(&self.tab), remote_object_id)
+                .with_description(fragment.description),
+        )
+    }
+
+    /// Returns all elements in the document which match the given CSS
+    /// selector.
+    pub async fn query_selector_all(&self, selector: impl Into<String>) -> Result<Vec<Element>> {
+        if self.tab.objects().is_released(&self.remote_object_id) {
+            return Err(CdpError::StaleHandle);
+        }
+        let root = self
+            .tab
+            .execute(RequestNodeParams::new(self.remote_object_id.clone()))
+            .await?
+            .result
+            .node_id;
+        let node_ids = self.tab.find_elements(selector, root).await?;
+        Element::from_nodes(&self.tab, &node_ids).await
+    }
+}
+
+/// The state of a [`JsPromise`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromiseState {
+    Pending,
+    Fulfilled,
+    Rejected,
+}
+
+/// A live handle to a JavaScript
+/// [Promise](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Promise)
+/// that is awaited on demand, see [`Page::evaluate_promise`].
+pub struct JsPromise {
+    /// The Unique object identifier
+    pub remote_object_id: RemoteObjectId,
+    tab: Arc<PageInner>,
+    /// The description of the promise, `Promise`
+    description: Option<String>,
+    _tracked: Option<Arc<ObjectEntry>>,
+}
+
+impl JsPromise {
+    pub(crate) fn new(tab: Arc<PageInner>, remote_object_id: RemoteObjectId) -> Self {
+        let _tracked = tab.objects().track(&remote_object_id, "Promise");
+        Self {
+            remote_object_id,
+            tab,
+            description: None,
+            _tracked,
+        }
+    }
+
+    /// Whether the promise is still pending, without waiting for it.
+    pub async fn state(&self) -> Result<PromiseState> {
+        if self.tab.objects().is_released(&self.remote_object_id) {
+            return Err(CdpError::StaleHandle);
+        }
+        let mut params = GetPropertiesParams::new(self.remote_object_id.clone());
+        params.own_properties = Some(true);
+        let internal = self
+            .tab
+            .execute(params)
+            .await?
+            .result
+            .internal_properties
+            .unwrap_or_default();
+        let state = internal
+            .into_iter()
+            .find(|p| p.name == "[[PromiseState]]")
+            .and_then(|p| p.value?.value);
+        match state.as_ref().and_then(|state| state.as_str()) {
+            Some("pending") => Ok(PromiseState::Pending),
+            Some("fulfilled") => Ok(PromiseState::Fulfilled),
+            Some("rejected") => Ok(PromiseState::Rejected),
+            _ => Err(CdpError::msg("Failed to read the state of the promise")),
+        }
+    }
+
+    async fn settle(&self, return_by_value: bool) -> Result<RemoteObject> {
+        if self.tab.objects().is_released(&self.remote_object_id) {
+            return Err(CdpError::StaleHandle);
+        }
+        let mut params = AwaitPromiseParams::new(self.remote_object_id.clone());
+        params.return_by_value = Some(return_by_value);
+        let resp = self.tab.execute(params).await?.result;
+        if let Some(exception) = resp.exception_details {
+            return Err(CdpError::JavascriptException(Box::new(exception)));
+        }
+        Ok(resp.result)
+    }
+
+    /// Waits for the promise to settle and returns its deserialized value.
+    ///
+    /// Fails with [`CdpError::JavascriptException`] if the promise was
+    /// rejected.
+    pub async fn value<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(value_or_null(self.settle(true).await?)?)
+    }
+
+    /// Waits for the promise to settle and returns a handle to its value,
+    /// see [`Page::evaluate_handle`].
+    pub async fn handle(&self) -> Result<EvaluationResult> {
+        Ok(EvaluationResult::new(self.settle(false).await?))
+    }
+}
+
+/// A live handle to a
+/// [DocumentFragment](https://developer.mozilla.org/en-US/docs/Web/API/DocumentFragment),
+/// to build DOM subtrees before inserting them at once, see
+/// [`JsDocument::create_fragment_from_html`] and [`Element::template_content`].
+pub struct JsDocumentFragment {
+    /// The Unique object identifier
+    pub remote_object_id: RemoteObjectId,
+    tab: Arc<PageInner>,
+    /// The description of the fragment node, `#document-fragment`
+    description: Option<String>,
+    _tracked: Option<Arc<ObjectEntry>>,
+}
+
+impl JsDocumentFragment {
+    pub(crate) fn new(tab: Arc<PageInner>, remote_object_id: RemoteObjectId) -> Self {
+        let _tracked = tab.objects().track(&remote_object_id, "DocumentFragment");
+        Self {
+            remote_object_id,
+            tab,
+            description: None,
+            _tracked,
+        }
+    }
+
+    /// The number of child elements of the fragment.
+    pub async fn child_element_count(&self) -> Result<u32> {
+        let count = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() { return this.childElementCount; }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(value_or_null(count)?)
+    }
+
+    /// The child elements of the fragment.
+    pub async fn children(&self) -> Result<Vec<Element>> {
+        self.query_selector_all(":scope > *").await
+    }
+
+    /// Returns the first element in the fragment which matches the given CSS
+    /// selector, `None` if there is none.
+    pub async fn query_selector(&self, selector: impl AsRef<str>) -> Result<Option<Element>> {
+        let element = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function(selector) { return this.querySelector(selector); }",
+            vec![selector.as_ref().into()],
+            false,
+        )
+        .await?;
+        match element.object_id {
+            Some(object_id) => Ok(Some(
+                Element::from_object_id(Arc::clone(&self.tab), object_id).await?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns all elements in the fragment which match the given CSS
+    /// selector.
+    pub async fn query_selector_all(&self, selector: impl AsRef<str>) -> Result<Vec<Element>> {
+        let elements = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function(selector) { return [...this.querySelectorAll(selector)]; }",
+            vec![selector.as_ref().into()],
+            false,
+        )
+        .await?
+        .object_id
+        .ok_or_else(|| CdpError::msg("No object Id found for array"))?;
+        let mut params = GetPropertiesParams::new(elements.clone());
+        params.own_properties = Some(true);
+        let properties = self.tab.execute(params).await;
+        self.tab.execute(ReleaseObjectParams::new(elements)).await?;
+
+        let mut indexed = properties?
+            .result
+            .result
+            .into_iter()
+            .filter_map(|p| Some((p.name.parse::<usize>().ok()?, p.value?.object_id?)))
+            .collect::<Vec<_>>();
+        indexed.sort_by_key(|(index, _)| *index);
+        let mut elements = Vec::with_capacity(indexed.len());
+        for (_, object_id) in indexed {
+            elements.push(Element::from_object_id(Arc::clone(&self.tab), object_id).await?);
+        }
+        Ok(elements)
+    }
+
+    /// Appends the parsed `html` to the end of the fragment.
+    pub async fn append_html(&self, html: impl AsRef<str>) -> Result<&Self> {
+        call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function(html) {
+                const template = this.ownerDocument.createElement('template');
+                template.innerHTML = html;
+                this.append(template.content);
+            }",
+            vec![html.as_ref().into()],
+            true,
+        )
+        .await?;
+        Ok(self)
+    }
+
+    /// Returns a deep copy of the fragment.
+    pub async fn clone_fragment(&self) -> Result<JsDocumentFragment> {
+        let fragment = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            "function() { return this.cloneNode(true); }",
+            vec![],
+            false,
+        )
+        .await?;
+        let remote_object_id = fragment
+            .object_id
+            .ok_or_else(|| CdpError::msg("No object Id found for DocumentFragment"))?;
+        Ok(
+            JsDocumentFragment::new(Arc::clone(&self.tab), remote_object_id)
+                .with_description(fragment.description),
+        )
+    }
+
+    /// Moves the `node` to the end of the fragment.
+    pub async fn append(&self, node: &Element) -> Result<&Self> {
+        if self.tab.objects().is_released(&self.remote_object_id)
+            || self.tab.objects().is_released(&node.remote_object_id)
+        {
+            return Err(CdpError::StaleHandle);
+        }
+        self.tab.check_object_origin(&self.remote_object_id).await?;
+        let params = CallFunctionOnParams::builder()
+            .function_declaration("function(node) { this.append(node); }")
+            .object_id(self.remote_object_id.clone())
+            .argument(
+                CallArgument::builder()
+                    .object_id(node.remote_object_id.clone())
+                    .build(),
+            )
+            .build()
+            .unwrap();
+        let resp = self.tab.execute(params).await?.result;
+        if let Some(exception) = resp.exception_details {
+            return Err(CdpError::JavascriptException(Box::new(exception)));
+        }
+        Ok(self)
+    }
+}
+
+/// A live handle to a
+/// [DOMTokenList](https://developer.mozilla.org/en-US/docs/Web/API/DOMTokenList),
+/// like the classes of an element, see [`Element::class_list`].
+pub struct JsDomTokenList {
+    /// The Unique object identifier
+    pub remote_object_id: RemoteObjectId,
+    tab: Arc<PageInner>,
+    /// The description of the list when the handle was created, like
+    /// `DOMTokenList(2)`
+    description: Option<String>,
+    _tracked: Option<Arc<ObjectEntry>>,
+}
+
+impl JsDomTokenList {
+    pub(crate) fn new(tab: Arc<PageInner>, remote_object_id: RemoteObjectId) -> Self {
+        let _tracked = tab.objects().track(&remote_object_id, "DOMTokenList");
+        Self {
+            remote_object_id,
+            tab,
+            description: None,
+            _tracked,
+        }
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        function_declaration: &str,
+        args: Vec<serde_json::Value>,
+    ) -> Result<T> {
+        let value = call_function_on(
+            &self.tab,
+            &self.remote_object_id,
+            function_declaration,
+            args,
+            true,
+        )
+        .await?;
+        Ok(value_or_null(value)?)
+    }
+
+    /// Adds the `tokens` that are not in the list yet.
+    ///
+    /// Fails if a token is empty or contains whitespace.
+    pub async fn add<I, S>(&self, tokens: I) -> Result<&Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let tokens: Vec<String> = tokens.into_iter().map(Into::into).collect();
+        self.call::<()>(
+            "function(tokens) { this.add(...tokens); }",
+            vec![tokens.into()],
+        )
+        .await?;
+        Ok(self)
+    }
+
+    /// Removes the `tokens` that are in the list.
+    pub async fn remove<I, S>(&self, tokens: I) -> Result<&Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let tokens: Vec<String> = tokens.into_iter().map(Into::into).collect();
+        self.call::<()>(
+            "function(tokens) { this.remove(...tokens); }",
+            vec![tokens.into()],
+        )
+        .await?;
+        Ok(self)
+    }
+
+    /// Removes the `token` if it is in the list and adds it otherwise, or,
+    /// with `force`, adds it for `Some(true)` and removes it for
+    /// `Some(false)`. Returns whether the token is in the list afterwards.
+    pub async fn toggle(&self, token: &str, force: Option<bool>) -> Result<bool> {
+        self.call(
+            "function(token, force) {
+                return force === null ? this.toggle(token) : this.toggle(token, force);
+            }",
+            vec![token.into(), force.into()],
+        )
+        .await
+    }
+
+    /// Whether the `token` is in the list.
+    pub async fn contains(&self, token: &str) -> Result<bool> {
+        self.call(
+            "function(token) { return this.contains(token); }",
+            vec![token.into()],
+        )
+        .await
+    }
+
+    /// Replaces the `token` with `new_token` in place. Returns `false` if
+    /// the token is not in the list.
+    pub async fn replace(&self, token: &str, new_token: &str) -> Result<bool> {
+        self.call(
+            "function(token, newToken) { return this.replace(token, newToken); }",
+            vec![token.into(), new_token.into()],
+        )
+        .await
+    }
+
+    /// The number of tokens in the list.
+    pub async fn length(&self) -> Result<usize> {
+        self.call("function() { return this.length; }", vec![])
+            .await
+    }
+
+    /// The token at `index`, `None` if out of bounds.
+    pub async fn item(&self, index: usize) -> Result<Option<String>> {
+        self.call(
+            "function(index) { return this.item(index); }",
+            vec![index.into()],
+        )
+        .await
+    }
+
+    /// All tokens of the list in order.
+    pub async fn to_vec(&self) -> Result<Vec<String>> {
+        self.call("function() { return Array.from(this); }", vec![])
+            .await
+    }
+}
+
+/// Names that match the custom element name production but are reserved for
+/// SVG and MathML elements.
+const RESERVED_CUSTOM_ELEMENT_NAMES: [&str; 8] = [
+    "annotation-xml",
+    "color-profile",
+    "font-face",
+    "font-face-src",
+    "font-face-uri",
+    "font-face-format",
+    "font-face-name",
+    "missing-glyph",
+];
+
+/// Checks that `name` is a [valid custom element name](https://html.spec.whatwg.org/multipage/custom-elements.html#valid-custom-element-name),
+/// i.e. it starts with a lowercase ASCII letter, contains a `-`, has no
+/// uppercase ASCII letters or other ASCII characters than `a-z0-9-._` and is
+/// not reserved.
+pub(crate) fn validate_custom_element_name(name: &str) -> Result<()> {
+    let valid = name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.contains('-')
+        && name.chars().all(|c| {
+            !c.is_ascii()
+                || c.is_ascii_lowercase()
+                || c.is_ascii_digit()
+                || matches!(c, '-' | '.' | '_')
+        })
+        && !RESERVED_CUSTOM_ELEMENT_NAMES.contains(&name);
+    if valid {
+        Ok(())
+    } else {
+        Err(CdpError::msg(format!(
+            "`{name}` is not a valid custom element name, \
+             it must start with a lowercase letter and contain a dash, e.g. `my-widget`"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn optional_arguments_pass_undefined() {
+        let none = JsOptional::<u32>(None).into_argument().unwrap();
+        assert_eq!(serde_json::to_value(&none).unwrap(), serde_json::json!({}));
+        let some = CallArgument::try_from(JsOptional::from(Some(3))).unwrap();
+        assert_eq!(some.value, Some(serde_json::json!(3)));
+        let null = JsOptional(Some(None::<u32>)).into_argument().unwrap();
+        assert_eq!(null.value, Some(serde_json::Value::Null));
+    }
+
+    #[test]
+    fn property_pages_are_truncated_by_limit() {
+        let options = PropertyOptions::new().limit(100);
+        assert!(options.is_truncated(100, 5000));
+        assert!(!options.offset(4900).is_truncated(100, 5000));
+        assert!(!PropertyOptions::new().is_truncated(5000, 5000));
+        assert_eq!(
+            options.offset(200).args(),
+            serde_json::json!([false, false, 200, 100])
+                .as_array()
+                .unwrap()
+                .clone()
+        );
+    }
+
+    #[test]
+    fn unserializable_numbers() {
+        use chromiumoxide_cdp::cdp::js_protocol::runtime::UnserializableValue;
+
+        let unserializable = |value: &str| {
+            let mut object = RemoteObject::new(RemoteObjectType::Number);
+            object.unserializable_value = Some(UnserializableValue::new(value));
+            EvaluationResult::new(object)
+        };
+        let positive: f64 = unserializable("Infinity").into_value().unwrap();
+        assert_eq!(positive, f64::INFINITY);
+        let negative: f32 = unserializable("-Infinity").into_value().unwrap();
+        assert_eq!(negative, f32::NEG_INFINITY);
+        let nan: f64 = unserializable("NaN").into_value().unwrap();
+        assert!(nan.is_nan());
+        let zero: f64 = unserializable("-0").into_value().unwrap();
+        assert!(zero == 0.0 && zero.is_sign_negative());
+        let big: u64 = unserializable("18446744073709551615n")
+            .into_value()
+            .unwrap();
+        assert_eq!(big, u64::MAX);
+        assert!(unserializable("Infinity").into_value::<i64>().is_err());
+
+        let object = unserializable("Infinity").object().clone();
+        assert_eq!(value_or_null::<f64>(object).unwrap(), f64::INFINITY);
+    }
+
+    #[test]
+    fn native_function_source() {
+        assert!(is_native_source("function push() { [native code] }"));
+        assert!(is_native_source("function () {\n    [native code]\n}"));
+        assert!(!is_native_source(
+            "function push(item) { items.push(item) }"
+        ));
+        assert!(!is_native_source("() => '{ [native code] }'"));
+    }
+
+    #[test]
+    fn pointer_segments() {
+        let pointer: Vec<JsonPointerSegment> = vec!["data".into(), 0.into(), "id".into()];
+        assert_eq!(
+            serde_json::to_value(pointer).unwrap(),
+            serde_json::json!(["data", 0, "id"])
+        );
+    }
+
+    #[test]
+    fn dom_exception() {
+        let mut details = ExceptionDetails::new(1, "Uncaught", 0, 15);
+        let mut exception = RemoteObject::new(RemoteObjectType::Object);
+        exception.class_name = Some("DOMException".to_string());
+        exception.description = Some(
+            "NotFoundError: Failed to execute 'removeChild' on 'Node': \
+             The node to be removed is not a child of this node.\n    at <anonymous>:1:15"
+                .to_string(),
+        );
+        details.exception = Some(exception);
+        assert_eq!(
+            DomException::from_exception_details(&details),
+            Some(DomException {
+                name: "NotFoundError".to_string(),
+                message: "Failed to execute 'removeChild' on 'Node': \
+                          The node to be removed is not a child of this node."
+                    .to_string(),
+                code: 8,
+            })
+        );
+
+        details.exception.as_mut().unwrap().class_name = Some("TypeError".to_string());
+        assert_eq!(DomException::from_exception_details(&details), None);
+    }
+
+    #[test]
+    fn property_descriptor_info() {
+        let mut descriptor = PropertyDescriptor::new("Symbol(tag)", true, false);
+        descriptor.symbol = Some(
+            RemoteObject::builder()
+                .r#type(RemoteObjectType::Symbol)
+                .description("Symbol(tag)")
+                .build()
+                .unwrap(),
+        );
+        descriptor.get = Some(RemoteObject::new(RemoteObjectType::Function));
+        descriptor.set = Some(RemoteObject::new(RemoteObjectType::Undefined));
+        descriptor.is_own = Some(false);
+        assert_eq!(
+            PropertyDescriptorInfo::from(&descriptor),
+            PropertyDescriptorInfo {
+                name: "tag".to_string(),
+                is_symbol: true,
+                writable: None,
+                enumerable: false,
+                configurable: true,
+                has_getter: true,
+                has_setter: false,
+                is_own: false,
+            }
+        );
+    }
+
+    #[test]
+    fn custom_element_names() {
+        for name in ["my-widget", "x-1", "math-α", "a-b.c_d"] {
+            assert!(validate_custom_element_name(name).is_ok(), "{name}");
+        }
+        for name in [
+            "widget",
+            "My-widget",
+            "my-Widget",
+            "1-widget",
+            "-widget",
+            "my widget-",
+            "font-face",
+        ] {
+            assert!(validate_custom_element_name(name).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn previews_are_truncated() {
+        assert_eq!(Preview("Array(3)").to_string(), "Array(3)");
+        assert_eq!(
+            Preview("function add(a, b) {\n  return a + b;\n}").to_string(),
+            "function add(a, b) {…"
+        );
+        let long = "x".repeat(100);
+        assert_eq!(
+            Preview(&long).to_string(),
+            format!("{}…", "x".repeat(MAX_PREVIEW_CHARS))
+        );
+    }
+}
@@ -0,0 +1,214 @@
+//! An opt-in cache for the results of repeated evaluations.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chromiumoxide_cdp::cdp::js_protocol::runtime::RemoteObject;
+
+/// How long and how many evaluation results are cached, see
+/// [`Page::enable_eval_cache`](crate::page::Page::enable_eval_cache).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CachePolicy {
+    /// How long a result stays valid
+    pub ttl: Duration,
+    /// The maximum number of cached results, the oldest are evicted first
+    pub max_entries: usize,
+}
+
+impl CachePolicy {
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self { ttl, max_entries }
+    }
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(60), 256)
+    }
+}
+
+/// The hit and miss counters of the evaluation cache, see
+/// [`Page::eval_cache_stats`](crate::page::Page::eval_cache_stats).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EvalCacheStats {
+    /// Evaluations answered from the cache
+    pub hits: u64,
+    /// Evaluations that had to be sent to the browser
+    pub misses: u64,
+    /// The number of currently cached results
+    pub entries: usize,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    value: RemoteObject,
+    inserted: Instant,
+}
+
+/// Caches by-value evaluation results, keyed by the serialized evaluation
+/// including its execution context, so that results of a previous document
+/// are never served after a navigation.
+#[derive(Debug, Default)]
+pub(crate) struct EvalCache {
+    state: Mutex<CacheState>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    policy: Option<CachePolicy>,
+    entries: HashMap<String, CacheEntry>,
+    /// Bumped for every new document of the main frame
+    generation: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl EvalCache {
+    pub fn enable(&self, policy: CachePolicy) {
+        let mut state = self.state.lock().unwrap();
+        state.policy = Some(policy);
+        state.entries.clear();
+    }
+
+    pub fn disable(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.policy = None;
+        state.entries.clear();
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.state.lock().unwrap().policy.is_some()
+    }
+
+    /// The generation of the document, part of every key.
+    pub fn generation(&self) -> u64 {
+        self.state.lock().unwrap().generation
+    }
+
+    /// Starts a new generation and drops the cached results, called when
+    /// the main frame navigated or the execution contexts were cleared.
+    ///
+    /// Context ids repeat after a cross-process navigation, so they alone
+    /// can't tell documents apart.
+    pub fn next_generation(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.generation += 1;
+        state.entries.clear();
+    }
+
+    /// Returns the cached result for the `key` and counts the hit or miss.
+    pub fn get(&self, key: &str) -> Option<RemoteObject> {
+        let mut state = self.state.lock().unwrap();
+        let policy = state.policy?;
+        let now = Instant::now();
+        let value = match state.entries.get(key) {
+            Some(entry) if now.duration_since(entry.inserted) < policy.ttl => {
+                Some(entry.value.clone())
+            }
+            Some(_) => {
+                state.entries.remove(key);
+                None
+            }
+            None => None,
+        };
+        if value.is_some() {
+            state.hits += 1;
+        } else {
+            state.misses += 1;
+        }
+        value
+    }
+
+    pub fn insert(&self, key: String, value: RemoteObject) {
+        let mut state = self.state.lock().unwrap();
+        let Some(policy) = state.policy else {
+            return;
+        };
+        if policy.max_entries == 0 {
+            return;
+        }
+        let now = Instant::now();
+        state
+            .entries
+            .retain(|_, entry| now.duration_since(entry.inserted) < policy.ttl);
+        while state.entries.len() >= policy.max_entries && !state.entries.contains_key(&key) {
+            let oldest = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.inserted)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(oldest) => state.entries.remove(&oldest),
+                None => break,
+            };
+        }
+        state.entries.insert(
+            key,
+            CacheEntry {
+                value,
+                inserted: now,
+            },
+        );
+    }
+
+    pub fn stats(&self) -> EvalCacheStats {
+        let state = self.state.lock().unwrap();
+        EvalCacheStats {
+            hits: state.hits,
+            misses: state.misses,
+            entries: state.entries.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chromiumoxide_cdp::cdp::js_protocol::runtime::RemoteObjectType;
+
+    fn value(n: u32) -> RemoteObject {
+        let mut object = RemoteObject::new(RemoteObjectType::Number);
+        object.value = Some(n.into());
+        object
+    }
+
+    #[test]
+    fn hits_misses_and_eviction() {
+        let cache = EvalCache::default();
+        cache.insert("a".to_string(), value(1));
+        assert!(cache.get("a").is_none());
+        assert_eq!(cache.stats(), EvalCacheStats::default());
+
+        cache.enable(CachePolicy::new(Duration::from_secs(60), 2));
+        assert!(cache.get("a").is_none());
+        cache.insert("a".to_string(), value(1));
+        cache.insert("b".to_string(), value(2));
+        assert_eq!(cache.get("a").unwrap().value, Some(1.into()));
+        cache.insert("c".to_string(), value(3));
+        assert!(cache.get("a").is_none());
+        assert!(cache.get("c").is_some());
+        assert_eq!(
+            cache.stats(),
+            EvalCacheStats {
+                hits: 2,
+                misses: 2,
+                entries: 2
+            }
+        );
+
+        cache.enable(CachePolicy::new(Duration::ZERO, 2));
+        cache.insert("a".to_string(), value(1));
+        assert!(cache.get("a").is_none());
+    }
+
+    #[test]
+    fn new_generation_drops_results() {
+        let cache = EvalCache::default();
+        cache.enable(CachePolicy::default());
+        cache.insert("a".to_string(), value(1));
+        cache.next_generation();
+        assert_eq!(cache.generation(), 1);
+        assert!(cache.get("a").is_none());
+    }
+}
@@ -0,0 +1,240 @@
+//! Counting the event listeners of a page to detect leaks, see
+//! [`Page::event_listener_census`](crate::Page::event_listener_census).
+
+use std::collections::BTreeMap;
+
+use chromiumoxide_cdp::cdp::browser_protocol::dom_debugger::EventListener;
+
+/// The number of event targets a census queries at most, the
+/// [`ListenerCensus::truncated`] ones are left out.
+pub const MAX_CENSUS_TARGETS: usize = 10_000;
+
+/// The number of event targets whose listeners are requested concurrently.
+pub(crate) const CENSUS_BATCH_SIZE: usize = 64;
+
+/// Where the handler of an event listener is defined.
+///
+/// Script ids are assigned by the page's isolate and stay the same for the
+/// lifetime of a document, so censuses of the same document can be compared
+/// by source.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ListenerSource {
+    pub script_id: String,
+    /// Zero based
+    pub line_number: i64,
+    /// Zero based
+    pub column_number: i64,
+    /// The name of the handler function, `None` for anonymous functions
+    pub function_name: Option<String>,
+}
+
+impl ListenerSource {
+    fn new(listener: &EventListener) -> Self {
+        Self {
+            script_id: listener.script_id.inner().clone(),
+            line_number: listener.line_number,
+            column_number: listener.column_number,
+            function_name: listener
+                .handler
+                .as_ref()
+                .and_then(|handler| handler.description.as_deref())
+                .and_then(function_name),
+        }
+    }
+}
+
+/// The event listeners of a page counted by event type and by the source
+/// location of their handlers.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ListenerCensus {
+    /// The number of listeners
+    pub total: usize,
+    pub by_type: BTreeMap<String, usize>,
+    pub by_source: BTreeMap<ListenerSource, usize>,
+    /// The number of event targets whose listeners were counted
+    pub targets: usize,
+    /// Whether there were more than [`MAX_CENSUS_TARGETS`] event targets and
+    /// the others were left out
+    pub truncated: bool,
+}
+
+impl ListenerCensus {
+    pub(crate) fn record(&mut self, listeners: &[EventListener]) {
+        self.targets += 1;
+        for listener in listeners {
+            self.total += 1;
+            *self.by_type.entry(listener.r#type.clone()).or_default() += 1;
+            *self
+                .by_source
+                .entry(ListenerSource::new(listener))
+                .or_default() += 1;
+        }
+    }
+
+    /// The listeners added and removed from this census to the `later` one.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let before = page.event_listener_census(None).await?;
+    ///     for _ in 0..10 {
+    ///         page.find_element("#open").await?.click().await?;
+    ///         page.find_element("#close").await?.click().await?;
+    ///     }
+    ///     let diff = before.diff(&page.event_listener_census(None).await?);
+    ///     assert!(diff.is_empty(), "leaked listeners: {:?}", diff.added_by_source);
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub fn diff(&self, later: &ListenerCensus) -> ListenerCensusDiff {
+        let (added_by_type, removed_by_type) = count_changes(&self.by_type, &later.by_type);
+        let (added_by_source, removed_by_source) = count_changes(&self.by_source, &later.by_source);
+        ListenerCensusDiff {
+            added_by_type,
+            removed_by_type,
+            added_by_source,
+            removed_by_source,
+        }
+    }
+}
+
+/// The changes between two [`ListenerCensus`]es, see
+/// [`ListenerCensus::diff`]. Every map holds the number of listeners added
+/// or removed per key, keys without change are left out.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ListenerCensusDiff {
+    pub added_by_type: BTreeMap<String, usize>,
+    pub removed_by_type: BTreeMap<String, usize>,
+    pub added_by_source: BTreeMap<ListenerSource, usize>,
+    pub removed_by_source: BTreeMap<ListenerSource, usize>,
+}
+
+impl ListenerCensusDiff {
+    /// Whether no listener was added or removed.
+    pub fn is_empty(&self) -> bool {
+        self.added_by_type.is_empty()
+            && self.removed_by_type.is_empty()
+            && self.added_by_source.is_empty()
+            && self.removed_by_source.is_empty()
+    }
+}
+
+type Changes<K> = (BTreeMap<K, usize>, BTreeMap<K, usize>);
+
+fn count_changes<K: Ord + Clone>(
+    before: &BTreeMap<K, usize>,
+    after: &BTreeMap<K, usize>,
+) -> Changes<K> {
+    let mut added = BTreeMap::new();
+    let mut removed = BTreeMap::new();
+    for key in before.keys().chain(after.keys()) {
+        let old = before.get(key).copied().unwrap_or_default();
+        let new = after.get(key).copied().unwrap_or_default();
+        if new > old {
+            added.insert(key.clone(), new - old);
+        } else if old > new {
+            removed.insert(key.clone(), old - new);
+        }
+    }
+    (added, removed)
+}
+
+/// The name of a function from the description of its remote object, which
+/// is its source text, like `function onClick(event) { … }`.
+fn function_name(description: &str) -> Option<String> {
+    let mut rest = description.trim_start();
+    for prefix in ["async ", "function", "*", "get ", "set "] {
+        rest = rest.strip_prefix(prefix).unwrap_or(rest).trim_start();
+    }
+    let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))?;
+    let name = &rest[..end];
+    let is_function = rest[end..].trim_start().starts_with('(');
+    (is_function && !name.is_empty()).then(|| name.to_string())
+}
+
+/// Collects the event targets of a census into an array: the `roots` and
+/// their descendants including those in open shadow roots, in document
+/// order, at most `max + 1` so that truncation can be told. Called with
+/// `max` followed by the roots, the window and its document if there are
+/// none.
+pub(crate) const COLLECT_TARGETS_FN: &str = "function(max, ...roots) {
+    if (!roots.length) {
+        roots = [window, document];
+    }
+    const targets = new Set();
+    // an explicit stack, deeply nested documents can't exhaust the call stack
+    const stack = roots.slice().reverse();
+    while (stack.length && targets.size <= max) {
+        const target = stack.pop();
+        if (targets.has(target)) {
+            continue;
+        }
+        targets.add(target);
+        if (target.nodeType === undefined) {
+            continue;
+        }
+        const children = Array.from(target.children || []);
+        if (target.shadowRoot) {
+            children.unshift(target.shadowRoot);
+        }
+        for (let i = children.length - 1; i >= 0; i--) {
+            stack.push(children[i]);
+        }
+    }
+    return Array.from(targets).slice(0, max + 1);
+}";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(line_number: i64) -> ListenerSource {
+        ListenerSource {
+            script_id: "12".to_string(),
+            line_number,
+            column_number: 0,
+            function_name: None,
+        }
+    }
+
+    #[test]
+    fn parses_function_names() {
+        assert_eq!(
+            function_name("function onClick(event) { }").as_deref(),
+            Some("onClick")
+        );
+        assert_eq!(
+            function_name("async function* $load () {}").as_deref(),
+            Some("$load")
+        );
+        assert_eq!(function_name("handle(e) { }").as_deref(), Some("handle"));
+        assert_eq!(function_name("(e) => e.preventDefault()"), None);
+        assert_eq!(function_name("e => e"), None);
+        assert_eq!(function_name("function () { [native code] }"), None);
+    }
+
+    #[test]
+    fn diff_counts_added_and_removed() {
+        let before = ListenerCensus {
+            total: 3,
+            by_type: [("click".to_string(), 2), ("scroll".to_string(), 1)].into(),
+            by_source: [(source(1), 2), (source(2), 1)].into(),
+            ..Default::default()
+        };
+        let after = ListenerCensus {
+            total: 4,
+            by_type: [("click".to_string(), 4)].into(),
+            by_source: [(source(1), 4)].into(),
+            ..Default::default()
+        };
+        let diff = before.diff(&after);
+        assert_eq!(diff.added_by_type, [("click".to_string(), 2)].into());
+        assert_eq!(diff.removed_by_type, [("scroll".to_string(), 1)].into());
+        assert_eq!(diff.added_by_source, [(source(1), 2)].into());
+        assert_eq!(diff.removed_by_source, [(source(2), 1)].into());
+        assert!(before.diff(&before).is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+//! The content of simulated clipboard and drag and drop operations, see
+//! [`Element::dispatch_paste`](crate::element::Element::dispatch_paste) and
+//! [`Element::dispatch_drop`](crate::element::Element::dispatch_drop).
+
+use serde::Serialize;
+
+use crate::utils;
+
+/// The content of a
+/// [DataTransfer](https://developer.mozilla.org/en-US/docs/Web/API/DataTransfer)
+/// that is constructed in the page: string items keyed by their mime type and
+/// files.
+///
+/// # Example
+///
+/// ```
+/// use chromiumoxide::js::data_transfer::DataTransferInit;
+///
+/// let data = DataTransferInit::new()
+///     .text("hello")
+///     .html("<b>hello</b>")
+///     .file("logo.png", "image/png", vec![0x89, b'P', b'N', b'G']);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DataTransferInit {
+    items: Vec<DataTransferItem>,
+    files: Vec<DataTransferFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct DataTransferItem {
+    mime: String,
+    data: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct DataTransferFile {
+    name: String,
+    mime: String,
+    /// The base64 encoded content
+    data: String,
+}
+
+impl DataTransferInit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a string item of the `mime` type, as read by
+    /// `dataTransfer.getData(mime)`.
+    pub fn item(mut self, mime: impl Into<String>, data: impl Into<String>) -> Self {
+        self.items.push(DataTransferItem {
+            mime: mime.into(),
+            data: data.into(),
+        });
+        self
+    }
+
+    /// Adds plain text, the `text/plain` item.
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.item("text/plain", text)
+    }
+
+    /// Adds markup, the `text/html` item.
+    pub fn html(self, html: impl Into<String>) -> Self {
+        self.item("text/html", html)
+    }
+
+    /// Adds a file with the `name`, `mime` type and content, as listed in
+    /// `dataTransfer.files`.
+    pub fn file(
+        mut self,
+        name: impl Into<String>,
+        mime: impl Into<String>,
+        bytes: impl AsRef<[u8]>,
+    ) -> Self {
+        self.files.push(DataTransferFile {
+            name: name.into(),
+            mime: mime.into(),
+            data: utils::base64::encode(bytes),
+        });
+        self
+    }
+}
+
+/// A JS function that constructs a `DataTransfer` from a serialized
+/// [`DataTransferInit`].
+pub(crate) const NEW_DATA_TRANSFER_FN: &str = "function(init) {
+    const transfer = new DataTransfer();
+    for (const { mime, data } of init.items) {
+        transfer.setData(mime, data);
+    }
+    for (const { name, mime, data } of init.files) {
+        const bytes = Uint8Array.from(atob(data), c => c.charCodeAt(0));
+        transfer.items.add(new File([bytes], name, { type: mime }));
+    }
+    return transfer;
+}";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_files_as_base64() {
+        let data = DataTransferInit::new().text("hi").file(
+            "a.bin",
+            "application/octet-stream",
+            [0u8, 1, 2, 255],
+        );
+        assert_eq!(
+            serde_json::to_value(&data).unwrap(),
+            serde_json::json!({
+                "items": [{ "mime": "text/plain", "data": "hi" }],
+                "files": [{ "name": "a.bin", "mime": "application/octet-stream", "data": "AAEC/w==" }],
+            })
+        );
+    }
+}
@@ -0,0 +1,344 @@
+//! DOM events that can be synthesized and dispatched from rust, see
+//! [`Element::dispatch_event`](crate::element::Element::dispatch_event).
+//!
+//! In contrast to the input events of the `Input` domain, these are not
+//! trusted (`isTrusted` is `false`), but they reach handlers of frameworks
+//! that ignore the protocol's input events.
+
+use serde::Serialize;
+
+use chromiumoxide_cdp::cdp::js_protocol::runtime::RemoteObjectId;
+
+/// An event that is constructed in the page with
+/// `new <constructor>(type, init)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsEvent {
+    /// The name of the event class, like `Event` or `MouseEvent`
+    pub constructor: String,
+    /// The type of the event, like `click`
+    pub event_type: String,
+    /// The init dictionary passed to the constructor
+    pub init: serde_json::Value,
+    /// A remote object that is passed as `detail` of the init dictionary,
+    /// in place of a JSON value
+    pub detail_object: Option<RemoteObjectId>,
+}
+
+impl JsEvent {
+    /// Creates a plain [Event](https://developer.mozilla.org/en-US/docs/Web/API/Event/Event).
+    pub fn new(event_type: impl Into<String>, bubbles: bool, cancelable: bool) -> Self {
+        Self {
+            constructor: "Event".to_string(),
+            event_type: event_type.into(),
+            init: serde_json::json!({
+                "bubbles": bubbles,
+                "cancelable": cancelable,
+            }),
+            detail_object: None,
+        }
+    }
+
+    /// Creates an event of any class, the `init` is serialized into the init
+    /// dictionary of the constructor.
+    pub fn with_constructor(
+        constructor: impl Into<String>,
+        event_type: impl Into<String>,
+        init: impl Serialize,
+    ) -> serde_json::Result<Self> {
+        Ok(Self {
+            constructor: constructor.into(),
+            event_type: event_type.into(),
+            init: serde_json::to_value(init)?,
+            detail_object: None,
+        })
+    }
+}
+
+/// A [MouseEvent](https://developer.mozilla.org/en-US/docs/Web/API/MouseEvent/MouseEvent),
+/// like `click` or `mousedown`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsMouseEvent {
+    #[serde(skip)]
+    pub event_type: String,
+    pub bubbles: bool,
+    pub cancelable: bool,
+    pub composed: bool,
+    pub detail: i64,
+    pub screen_x: f64,
+    pub screen_y: f64,
+    pub client_x: f64,
+    pub client_y: f64,
+    pub ctrl_key: bool,
+    pub shift_key: bool,
+    pub alt_key: bool,
+    pub meta_key: bool,
+    /// The button that changed: `0` main, `1` auxiliary, `2` secondary
+    pub button: i64,
+    /// Bitmask of the buttons that are pressed
+    pub buttons: i64,
+}
+
+impl JsMouseEvent {
+    /// Creates a mouse event that bubbles and is cancelable, like the ones the
+    /// browser fires for user input.
+    pub fn new(event_type: impl Into<String>) -> Self {
+        Self {
+            event_type: event_type.into(),
+            bubbles: true,
+            cancelable: true,
+            composed: true,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the position of the event relative to the viewport.
+    pub fn at(mut self, client_x: f64, client_y: f64) -> Self {
+        self.client_x = client_x;
+        self.client_y = client_y;
+        self
+    }
+}
+
+impl From<JsMouseEvent> for JsEvent {
+    fn from(event: JsMouseEvent) -> Self {
+        Self {
+            constructor: "MouseEvent".to_string(),
+            init: serde_json::to_value(&event).unwrap_or_default(),
+            event_type: event.event_type,
+            detail_object: None,
+        }
+    }
+}
+
+/// A [KeyboardEvent](https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent/KeyboardEvent),
+/// like `keydown` or `keyup`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsKeyboardEvent {
+    #[serde(skip)]
+    pub event_type: String,
+    pub bubbles: bool,
+    pub cancelable: bool,
+    pub composed: bool,
+    /// The value of the key, like `a` or `Enter`
+    pub key: String,
+    /// The physical key, like `KeyA` or `Enter`
+    pub code: String,
+    pub location: i64,
+    pub repeat: bool,
+    pub is_composing: bool,
+    pub ctrl_key: bool,
+    pub shift_key: bool,
+    pub alt_key: bool,
+    pub meta_key: bool,
+}
+
+impl JsKeyboardEvent {
+    /// Creates a keyboard event for the `key` that bubbles and is cancelable,
+    /// like the ones the browser fires for user input.
+    pub fn new(event_type: impl Into<String>, key: impl Into<String>) -> Self {
+        Self {
+            event_type: event_type.into(),
+            key: key.into(),
+            bubbles: true,
+            cancelable: true,
+            composed: true,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the physical key code, like `KeyA`.
+    pub fn code(mut self, code: impl Into<String>) -> Self {
+        self.code = code.into();
+        self
+    }
+}
+
+impl From<JsKeyboardEvent> for JsEvent {
+    fn from(event: JsKeyboardEvent) -> Self {
+        Self {
+            constructor: "KeyboardEvent".to_string(),
+            init: serde_json::to_value(&event).unwrap_or_default(),
+            event_type: event.event_type,
+            detail_object: None,
+        }
+    }
+}
+
+/// An [InputEvent](https://developer.mozilla.org/en-US/docs/Web/API/InputEvent/InputEvent),
+/// like `input` or `beforeinput`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsInputEvent {
+    #[serde(skip)]
+    pub event_type: String,
+    pub bubbles: bool,
+    pub cancelable: bool,
+    pub composed: bool,
+    /// The inserted characters, if any
+    pub data: Option<String>,
+    /// The kind of change, like `insertText` or `deleteContentBackward`
+    pub input_type: String,
+    pub is_composing: bool,
+}
+
+impl JsInputEvent {
+    /// Creates an input event that bubbles, like the ones the browser fires
+    /// for user input. Only `beforeinput` is cancelable.
+    pub fn new(event_type: impl Into<String>) -> Self {
+        let event_type = event_type.into();
+        Self {
+            cancelable: event_type == "beforeinput",
+            event_type,
+            bubbles: true,
+            composed: true,
+            ..Default::default()
+        }
+    }
+
+    /// An `input` event for inserting the `text`.
+    pub fn insert_text(text: impl Into<String>) -> Self {
+        Self {
+            data: Some(text.into()),
+            input_type: "insertText".to_string(),
+            ..Self::new("input")
+        }
+    }
+
+    /// Sets the kind of change, like `insertText`.
+    pub fn input_type(mut self, input_type: impl Into<String>) -> Self {
+        self.input_type = input_type.into();
+        self
+    }
+
+    /// Sets the inserted characters.
+    pub fn data(mut self, data: impl Into<String>) -> Self {
+        self.data = Some(data.into());
+        self
+    }
+}
+
+impl From<JsInputEvent> for JsEvent {
+    fn from(event: JsInputEvent) -> Self {
+        Self {
+            constructor: "InputEvent".to_string(),
+            init: serde_json::to_value(&event).unwrap_or_default(),
+            event_type: event.event_type,
+            detail_object: None,
+        }
+    }
+}
+
+/// A [CustomEvent](https://developer.mozilla.org/en-US/docs/Web/API/CustomEvent/CustomEvent)
+/// that carries arbitrary `detail`.
+///
+/// Like the constructor in the page, it neither bubbles, nor is cancelable or
+/// composed by default.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JsCustomEvent {
+    pub event_type: String,
+    pub bubbles: bool,
+    pub cancelable: bool,
+    pub composed: bool,
+    /// The `detail` as JSON value
+    pub detail: serde_json::Value,
+    /// A remote object to pass as `detail` instead of the JSON value
+    pub detail_object: Option<RemoteObjectId>,
+}
+
+impl JsCustomEvent {
+    /// Creates a custom event with `null` as `detail`.
+    pub fn new(event_type: impl Into<String>) -> Self {
+        Self {
+            event_type: event_type.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the `detail` to the serialized `detail`.
+    pub fn detail(mut self, detail: impl Serialize) -> serde_json::Result<Self> {
+        self.detail = serde_json::to_value(detail)?;
+        self.detail_object = None;
+        Ok(self)
+    }
+
+    /// Sets the `detail` to the remote object, like the
+    /// `remote_object_id` of an [`Element`](crate::element::Element) or a
+    /// [`JsObject`](crate::js::JsObject).
+    pub fn detail_object(mut self, object_id: RemoteObjectId) -> Self {
+        self.detail_object = Some(object_id);
+        self
+    }
+
+    pub fn bubbles(mut self, bubbles: bool) -> Self {
+        self.bubbles = bubbles;
+        self
+    }
+
+    pub fn cancelable(mut self, cancelable: bool) -> Self {
+        self.cancelable = cancelable;
+        self
+    }
+
+    pub fn composed(mut self, composed: bool) -> Self {
+        self.composed = composed;
+        self
+    }
+}
+
+impl From<JsCustomEvent> for JsEvent {
+    fn from(event: JsCustomEvent) -> Self {
+        Self {
+            constructor: "CustomEvent".to_string(),
+            init: serde_json::json!({
+                "bubbles": event.bubbles,
+                "cancelable": event.cancelable,
+                "composed": event.composed,
+                "detail": event.detail,
+            }),
+            event_type: event.event_type,
+            detail_object: event.detail_object,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_init() {
+        let event: JsEvent = JsInputEvent::insert_text("a").into();
+        assert_eq!(event.constructor, "InputEvent");
+        assert_eq!(event.event_type, "input");
+        assert_eq!(
+            event.init,
+            serde_json::json!({
+                "bubbles": true,
+                "cancelable": false,
+                "composed": true,
+                "data": "a",
+                "inputType": "insertText",
+                "isComposing": false,
+            })
+        );
+
+        let event: JsEvent = JsCustomEvent::new("item-added")
+            .detail(serde_json::json!({ "id": 1 }))
+            .unwrap()
+            .bubbles(true)
+            .into();
+        assert_eq!(event.constructor, "CustomEvent");
+        assert_eq!(
+            event.init,
+            serde_json::json!({
+                "bubbles": true,
+                "cancelable": false,
+                "composed": false,
+                "detail": { "id": 1 },
+            })
+        );
+        assert_eq!(event.detail_object, None);
+    }
+}
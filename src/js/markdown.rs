@@ -0,0 +1,305 @@
+//! Conversion of the content of elements into Markdown, see
+//! [`Element::to_markdown`](crate::element::Element::to_markdown).
+
+use serde::{Deserialize, Serialize};
+
+/// Appended to Markdown that was cut at the
+/// [`max_length`](MarkdownOptions::max_length).
+pub const TRUNCATION_MARKER: &str = "\n\n[…]";
+
+/// How elements are converted into Markdown.
+///
+/// # Example
+///
+/// ```
+/// use chromiumoxide::js::markdown::MarkdownOptions;
+///
+/// let options = MarkdownOptions::new().skip_landmarks(true).max_length(10_000);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownOptions {
+    skip_landmarks: bool,
+    max_length: Option<usize>,
+}
+
+impl MarkdownOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Leaves out navigation, complementary content and footers: `<nav>`,
+    /// `<aside>` and `<footer>` and the elements with the matching landmark
+    /// roles.
+    pub fn skip_landmarks(mut self, skip: bool) -> Self {
+        self.skip_landmarks = skip;
+        self
+    }
+
+    /// Cuts the Markdown after at most `max_length` characters, preferably at
+    /// a line break, and appends the [`TRUNCATION_MARKER`].
+    ///
+    /// The page stops converting once the limit is exceeded, so this also
+    /// bounds the work for large documents.
+    pub fn max_length(mut self, max_length: impl Into<Option<usize>>) -> Self {
+        self.max_length = max_length.into();
+        self
+    }
+}
+
+/// The Markdown as returned by [`TO_MARKDOWN_FN`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub(crate) struct MarkdownOutput {
+    pub markdown: String,
+    /// Whether the conversion stopped early at the maximum length
+    pub truncated: bool,
+}
+
+impl MarkdownOutput {
+    /// The Markdown cut to the maximum length of the options.
+    pub fn finish(self, options: &MarkdownOptions) -> String {
+        let Some(max_length) = options.max_length else {
+            return self.markdown;
+        };
+        let mut markdown = self.markdown;
+        match markdown.char_indices().nth(max_length) {
+            Some((end, _)) => {
+                // prefer a line break, unless that drops more than half
+                let end = match markdown[..end].rfind('\n') {
+                    Some(line_end) if line_end >= end / 2 => line_end,
+                    _ => end,
+                };
+                markdown.truncate(end);
+            }
+            None if !self.truncated => return markdown,
+            None => {}
+        }
+        markdown.truncate(markdown.trim_end().len());
+        markdown.push_str(TRUNCATION_MARKER);
+        markdown
+    }
+}
+
+/// Converts `this` element into a [`MarkdownOutput`], called with the
+/// [`MarkdownOptions`].
+///
+/// The tree is walked with an explicit stack, so deeply nested documents
+/// can't exhaust the call stack. Every element records where its output
+/// starts and rewrites it when it is left, e.g. to wrap it into a link.
+pub(crate) const TO_MARKDOWN_FN: &str = r#"function(options) {
+    const SKIP = new Set([
+        'script', 'style', 'noscript', 'template', 'svg', 'canvas', 'iframe', 'object', 'embed',
+        'head', 'input', 'select', 'textarea', 'button', 'dialog',
+    ]);
+    const LANDMARKS = new Set(['nav', 'aside', 'footer']);
+    const LANDMARK_ROLES = new Set(['navigation', 'complementary', 'contentinfo']);
+    const BLOCKS = new Set([
+        'address', 'article', 'aside', 'caption', 'dd', 'details', 'div', 'dl', 'dt', 'fieldset',
+        'figcaption', 'figure', 'footer', 'form', 'header', 'main', 'nav', 'p', 'section', 'summary',
+    ]);
+    const EMPHASIS = { strong: '**', b: '**', em: '_', i: '_', del: '~~', s: '~~' };
+    const maxLength = options.maxLength;
+    const lists = [];
+    let md = '';
+    let truncated = false;
+
+    const collapse = (text) => text.replace(/\s+/g, ' ');
+    const fence = (text, min) => '`'.repeat(
+        Math.max(min, ...(text.match(/`+/g) || []).map((run) => run.length + 1)),
+    );
+    // keeps the whitespace around inline content outside of its markup
+    const inline = (content, wrap) => {
+        const inner = content.trim();
+        if (!inner) {
+            return content;
+        }
+        const lead = content.match(/^\s*/)[0];
+        const trail = content.match(/\s*$/)[0];
+        return lead + wrap(inner) + trail;
+    };
+    const skipped = (el) => {
+        if (SKIP.has(el.localName) || el.hidden) {
+            return true;
+        }
+        if (options.skipLandmarks
+            && (LANDMARKS.has(el.localName) || LANDMARK_ROLES.has(el.getAttribute('role')))) {
+            return true;
+        }
+        return typeof el.checkVisibility === 'function' && !el.checkVisibility();
+    };
+    const table = (el) => {
+        const cell = (c) => collapse(c.textContent).trim().replace(/\|/g, '\\|');
+        const rows = Array.from(el.rows, (row) => Array.from(row.cells, cell));
+        if (!rows.length) {
+            return '';
+        }
+        const width = Math.max(...rows.map((row) => row.length));
+        const line = (row) => '| ' + Array.from({ length: width }, (_, i) => row[i] || '').join(' | ') + ' |';
+        return [line(rows[0]), '|' + ' --- |'.repeat(width), ...rows.slice(1).map(line)].join('\n');
+    };
+
+    const enter = (node, stack) => {
+        if (node.nodeType === Node.TEXT_NODE) {
+            let text = collapse(node.data);
+            if (!md || /\s$/.test(md)) {
+                text = text.replace(/^ /, '');
+            }
+            md += text;
+            return;
+        }
+        if (node.nodeType !== Node.ELEMENT_NODE || skipped(node)) {
+            return;
+        }
+        const name = node.localName;
+        switch (name) {
+            case 'br':
+                md += '\n';
+                return;
+            case 'hr':
+                md += '\n\n---\n\n';
+                return;
+            case 'img': {
+                const alt = collapse(node.alt || '').trim();
+                const src = node.currentSrc || node.src;
+                if (src && !src.startsWith('data:')) {
+                    md += `![${alt}](${src})`;
+                } else if (alt) {
+                    md += `![${alt}]`;
+                }
+                return;
+            }
+            case 'pre': {
+                const code = node.textContent.replace(/\n$/, '');
+                const classes = (node.querySelector('code') || node).getAttribute('class') || '';
+                const language = (/(?:^|\s)lang(?:uage)?-(\S+)/.exec(classes) || [])[1] || '';
+                const marks = fence(code, 3);
+                md += `\n\n${marks}${language}\n${code}\n${marks}\n\n`;
+                return;
+            }
+            case 'table':
+                md += '\n\n' + table(node) + '\n\n';
+                return;
+        }
+        const frame = { node, exit: true };
+        if (/^h[1-6]$/.test(name) || BLOCKS.has(name) || name === 'blockquote') {
+            md += '\n\n';
+        } else if (name === 'ul' || name === 'ol') {
+            md += lists.length ? '\n' : '\n\n';
+            lists.push({ ordered: name === 'ol', next: name === 'ol' ? node.start : 1 });
+        } else if (name === 'li') {
+            const list = lists[lists.length - 1];
+            frame.marker = list && list.ordered ? `${list.next++}. ` : '- ';
+            if (md && !md.endsWith('\n')) {
+                md += '\n';
+            }
+        }
+        frame.start = md.length;
+        stack.push(frame);
+        for (let i = node.childNodes.length - 1; i >= 0; i--) {
+            stack.push({ node: node.childNodes[i] });
+        }
+    };
+
+    const exit = (frame) => {
+        const node = frame.node;
+        const name = node.localName;
+        const content = md.slice(frame.start);
+        const replace = (text) => {
+            md = md.slice(0, frame.start) + text;
+        };
+        if (/^h[1-6]$/.test(name)) {
+            const text = collapse(content).trim();
+            replace(text ? '#'.repeat(Number(name[1])) + ' ' + text + '\n\n' : '');
+        } else if (name in EMPHASIS) {
+            replace(inline(content, (inner) => EMPHASIS[name] + inner + EMPHASIS[name]));
+        } else if (name === 'code' || name === 'kbd' || name === 'samp') {
+            replace(inline(content, (inner) => {
+                const marks = fence(inner, 1);
+                const pad = inner.startsWith('`') || inner.endsWith('`') ? ' ' : '';
+                return marks + pad + inner + pad + marks;
+            }));
+        } else if (name === 'a') {
+            const href = node.getAttribute('href') ? node.href : '';
+            if (href && !/^javascript:/i.test(href)) {
+                replace(inline(content, (inner) => `[${inner}](${href})`));
+            }
+        } else if (name === 'li') {
+            const body = content.replace(/\n{3,}/g, '\n\n').trim();
+            const indent = ' '.repeat(frame.marker.length);
+            const lines = body.split('\n').map((line, i) => (i && line ? indent + line : line));
+            replace(frame.marker + lines.join('\n') + '\n');
+        } else if (name === 'ul' || name === 'ol') {
+            lists.pop();
+            md += lists.length ? '\n' : '\n\n';
+        } else if (name === 'blockquote') {
+            const body = content.replace(/\n{3,}/g, '\n\n').trim();
+            replace(body.split('\n').map((line) => (line ? '> ' + line : '>')).join('\n') + '\n\n');
+        } else if (name === 'dt') {
+            const text = collapse(content).trim();
+            replace(text ? `**${text}**\n\n` : '');
+        } else if (BLOCKS.has(name)) {
+            md += '\n\n';
+        }
+    };
+
+    const stack = [{ node: this }];
+    while (stack.length) {
+        const frame = stack.pop();
+        if (frame.exit) {
+            exit(frame);
+        } else if (!truncated) {
+            enter(frame.node, stack);
+            truncated = maxLength !== null && md.length > maxLength;
+        }
+    }
+    return {
+        markdown: md.replace(/[ \t]+\n/g, '\n').replace(/\n{3,}/g, '\n\n').trim(),
+        truncated,
+    };
+}"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(markdown: &str, truncated: bool) -> MarkdownOutput {
+        MarkdownOutput {
+            markdown: markdown.to_string(),
+            truncated,
+        }
+    }
+
+    #[test]
+    fn cuts_at_line_break() {
+        let options = MarkdownOptions::new().max_length(20);
+        let markdown = output("# Title\n\nIntro\n\nSome paragraph text", false).finish(&options);
+        assert_eq!(markdown, format!("# Title\n\nIntro{TRUNCATION_MARKER}"));
+
+        // a line break in the first half is too early to cut at
+        let markdown = output("# Title\n\nSome paragraph text", false).finish(&options);
+        assert_eq!(
+            markdown,
+            format!("# Title\n\nSome paragr{TRUNCATION_MARKER}")
+        );
+
+        let short = output("# Title", false).finish(&options);
+        assert_eq!(short, "# Title");
+    }
+
+    #[test]
+    fn marks_early_stop_and_respects_char_boundaries() {
+        let options = MarkdownOptions::new().max_length(3);
+        assert_eq!(
+            output("äöü", true).finish(&options),
+            format!("äöü{TRUNCATION_MARKER}")
+        );
+        assert_eq!(
+            output("äöüß", false).finish(&options),
+            format!("äöü{TRUNCATION_MARKER}")
+        );
+        assert_eq!(
+            output("unbounded", true).finish(&MarkdownOptions::new()),
+            "unbounded"
+        );
+    }
+}
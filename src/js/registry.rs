@@ -0,0 +1,282 @@
+//! Diagnostics for the remote object handles held by a page.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use chromiumoxide_cdp::cdp::js_protocol::runtime::RemoteObjectId;
+
+/// Statistics about the live handles of a class of remote objects, see
+/// [`Page::live_object_stats`](crate::page::Page::live_object_stats).
+#[derive(Debug, Clone)]
+pub struct ObjectStat {
+    /// The class name of the objects, e.g. `HTMLDivElement`
+    pub class: String,
+    /// How many handles of this class are alive
+    pub count: usize,
+    /// The age of the oldest live handle
+    pub oldest_age: Duration,
+    /// Where the oldest live handle was created
+    #[cfg(feature = "object-backtrace")]
+    pub oldest_backtrace: String,
+}
+
+/// A tracked remote object, alive as long as a handle to it exists.
+#[derive(Debug)]
+pub(crate) struct ObjectEntry {
+    id: RemoteObjectId,
+    class: String,
+    created: Instant,
+    released: AtomicBool,
+    /// The live counter of the registry, decremented when the entry is
+    /// released or dropped, whatever comes first
+    live: Arc<AtomicUsize>,
+    #[cfg(feature = "object-backtrace")]
+    backtrace: std::backtrace::Backtrace,
+}
+
+impl ObjectEntry {
+    fn release(&self) {
+        if !self.released.swap(true, Ordering::Relaxed) {
+            self.live.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for ObjectEntry {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Keeps track of the remote object handles of a page once diagnostics are
+/// enabled. Only weak references are held, so dropped handles disappear from
+/// the registry on their own.
+#[derive(Debug, Default)]
+pub(crate) struct ObjectRegistry {
+    state: Mutex<RegistryState>,
+}
+
+#[derive(Debug, Default)]
+struct RegistryState {
+    enabled: bool,
+    warn_threshold: Option<usize>,
+    warned: bool,
+    /// The entries by object id, several handles may share an object
+    objects: HashMap<RemoteObjectId, Vec<Weak<ObjectEntry>>>,
+    /// The number of entries in `objects`, including dropped ones
+    entries: usize,
+    /// The number of entries at which dropped ones are pruned next
+    prune_at: usize,
+    /// The number of entries that are neither dropped nor released
+    live: Arc<AtomicUsize>,
+}
+
+impl RegistryState {
+    fn live(&self) -> impl Iterator<Item = Arc<ObjectEntry>> + '_ {
+        self.objects
+            .values()
+            .flatten()
+            .filter_map(Weak::upgrade)
+            .filter(|entry| !entry.released.load(Ordering::Relaxed))
+    }
+
+    /// Removes the dropped entries once their number doubled since the last
+    /// time, which keeps tracking amortized constant time.
+    fn prune(&mut self) {
+        if self.entries < self.prune_at {
+            return;
+        }
+        self.objects.retain(|_, entries| {
+            entries.retain(|entry| entry.strong_count() > 0);
+            !entries.is_empty()
+        });
+        self.entries = self.objects.values().map(Vec::len).sum();
+        self.prune_at = (self.entries * 2).max(MIN_PRUNE_AT);
+    }
+
+    fn reset(&mut self) {
+        self.objects.clear();
+        self.entries = 0;
+        self.prune_at = MIN_PRUNE_AT;
+        // entries that are still alive count down their own counter
+        self.live = Default::default();
+    }
+}
+
+/// The number of entries below which dropped ones are never pruned
+const MIN_PRUNE_AT: usize = 64;
+
+impl ObjectRegistry {
+    pub fn enable(&self, warn_threshold: Option<usize>) {
+        let mut state = self.state.lock().unwrap();
+        if !state.enabled {
+            state.reset();
+        }
+        state.enabled = true;
+        state.warn_threshold = warn_threshold;
+        state.warned = false;
+    }
+
+    pub fn disable(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.enabled = false;
+        state.reset();
+    }
+
+    /// Registers a new handle, returns `None` if diagnostics are disabled.
+    pub fn track(&self, id: &RemoteObjectId, class: &str) -> Option<Arc<ObjectEntry>> {
+        let mut state = self.state.lock().unwrap();
+        if !state.enabled {
+            return None;
+        }
+        state.live.fetch_add(1, Ordering::Relaxed);
+        let entry = Arc::new(ObjectEntry {
+            id: id.clone(),
+            class: class.to_string(),
+            created: Instant::now(),
+            released: AtomicBool::new(false),
+            live: Arc::clone(&state.live),
+            #[cfg(feature = "object-backtrace")]
+            backtrace: std::backtrace::Backtrace::force_capture(),
+        });
+        state.prune();
+        state
+            .objects
+            .entry(id.clone())
+            .or_default()
+            .push(Arc::downgrade(&entry));
+        state.entries += 1;
+
+        if let Some(threshold) = state.warn_threshold {
+            let live = state.live.load(Ordering::Relaxed);
+            if live > threshold && !state.warned {
+                state.warned = true;
+                tracing::warn!(
+                    "{live} remote object handles are alive, exceeding the threshold of {threshold}"
+                );
+            } else if live <= threshold {
+                state.warned = false;
+            }
+        }
+        Some(entry)
+    }
+
+    /// Whether the object was released by [`ObjectRegistry::release_all`]
+    /// while a handle to it is still alive.
+    pub fn is_released(&self, id: &RemoteObjectId) -> bool {
+        let state = self.state.lock().unwrap();
+        state.objects.get(id).is_some_and(|entries| {
+            entries
+                .iter()
+                .filter_map(Weak::upgrade)
+                .any(|entry| entry.released.load(Ordering::Relaxed))
+        })
+    }
+
+    /// Marks all live objects as released and returns their ids.
+    pub fn release_all(&self) -> Vec<RemoteObjectId> {
+        let state = self.state.lock().unwrap();
+        let mut ids: Vec<_> = state
+            .live()
+            .map(|entry| {
+                entry.release();
+                entry.id.clone()
+            })
+            .collect();
+        // handles that share an object release it once
+        ids.sort_by(|a, b| a.inner().cmp(b.inner()));
+        ids.dedup();
+        ids
+    }
+
+    /// The live handles grouped by class, the largest groups first.
+    pub fn stats(&self) -> Vec<ObjectStat> {
+        let state = self.state.lock().unwrap();
+        let mut groups: HashMap<String, (usize, Arc<ObjectEntry>)> = HashMap::new();
+        for entry in state.live() {
+            match groups.get_mut(&entry.class) {
+                Some((count, oldest)) => {
+                    *count += 1;
+                    if entry.created < oldest.created {
+                        *oldest = entry;
+                    }
+                }
+                None => {
+                    groups.insert(entry.class.clone(), (1, entry));
+                }
+            }
+        }
+        let now = Instant::now();
+        let mut stats: Vec<_> = groups
+            .into_iter()
+            .map(|(class, (count, oldest))| ObjectStat {
+                class,
+                count,
+                oldest_age: now.duration_since(oldest.created),
+                #[cfg(feature = "object-backtrace")]
+                oldest_backtrace: oldest.backtrace.to_string(),
+            })
+            .collect();
+        stats.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.class.cmp(&b.class)));
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn track_and_release() {
+        let registry = ObjectRegistry::default();
+        let a = RemoteObjectId::new("a");
+        assert!(registry.track(&a, "Object").is_none());
+
+        registry.enable(None);
+        let first = registry.track(&a, "Object");
+        let second = registry.track(&RemoteObjectId::new("b"), "Object");
+        let third = registry.track(&RemoteObjectId::new("c"), "Array");
+        let stats = registry.stats();
+        assert_eq!(stats.len(), 2);
+        assert_eq!((stats[0].class.as_str(), stats[0].count), ("Object", 2));
+
+        drop(second);
+        drop(third);
+        assert_eq!(registry.stats()[0].count, 1);
+
+        assert_eq!(registry.release_all(), vec![a.clone()]);
+        assert!(registry.is_released(&a));
+        assert!(registry.stats().is_empty());
+        drop(first);
+        assert!(!registry.is_released(&a));
+    }
+
+    #[test]
+    fn prune_dropped_and_count_shared() {
+        let registry = ObjectRegistry::default();
+        registry.enable(Some(1));
+        for i in 0..10_000 {
+            drop(registry.track(&RemoteObjectId::new(i.to_string()), "Object"));
+        }
+        assert!(registry.state.lock().unwrap().entries < 2 * MIN_PRUNE_AT);
+
+        let id = RemoteObjectId::new("shared");
+        let first = registry.track(&id, "Object");
+        let second = registry.track(&id, "Object");
+        assert_eq!(
+            registry.state.lock().unwrap().live.load(Ordering::Relaxed),
+            2
+        );
+        assert_eq!(registry.release_all(), vec![id.clone()]);
+        assert_eq!(
+            registry.state.lock().unwrap().live.load(Ordering::Relaxed),
+            0
+        );
+        drop(first);
+        assert!(registry.is_released(&id));
+        drop(second);
+        assert!(!registry.is_released(&id));
+    }
+}
@@ -0,0 +1,255 @@
+//! Scripts with a name for their exception locations, like scripts loaded
+//! from files, see [`Page::evaluate_script`](crate::Page::evaluate_script).
+
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use chromiumoxide_cdp::cdp::js_protocol::runtime::{
+    CallFunctionOnParams, EvaluateParams, ExceptionDetails,
+};
+
+use crate::error::{CdpError, Result};
+use crate::js::Evaluation;
+use crate::utils::{self, is_likely_js_function};
+
+/// The line the function of a [`JsScript::function_body`] starts with.
+const FUNCTION_BODY_PREFIX: &str = "function() {\n";
+
+/// The source of a script together with the URL it is reported under, so
+/// that the frames of its exceptions and its console messages point at the
+/// file rather than at an anonymous evaluation.
+///
+/// # Example
+///
+/// ```no_run
+/// # use chromiumoxide::page::Page;
+/// # use chromiumoxide::error::Result;
+/// use chromiumoxide::js::script::JsScript;
+///
+/// # async fn demo(page: Page) -> Result<()> {
+///     let script = JsScript::from_file("scripts/collect.js").await?.function_body(true);
+///     let links: Vec<String> = page.evaluate_script(&script).await?.into_value()?;
+///     # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsScript {
+    source: String,
+    source_url: Option<String>,
+    source_map: Option<serde_json::Value>,
+    function_body: bool,
+}
+
+impl JsScript {
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            source_url: None,
+            source_map: None,
+            function_body: false,
+        }
+    }
+
+    /// Reads the script from the file at `path`, reported under the
+    /// `file://` URL of the file.
+    pub async fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = utils::canonicalize(path.as_ref()).await?;
+        let source = utils::read_to_string(&path).await?;
+        let url = url::Url::from_file_path(&path)
+            .map_err(|_| CdpError::msg(format!("Invalid script path {}", path.display())))?;
+        Ok(Self::new(source).source_url(url.to_string()))
+    }
+
+    /// The URL the script is reported under, attached as `//# sourceURL=`.
+    pub fn source_url(mut self, source_url: impl Into<String>) -> Self {
+        self.source_url = Some(source_url.into());
+        self
+    }
+
+    /// A [source map](https://sourcemaps.info/spec.html) of the script,
+    /// attached inline as `//# sourceMappingURL=`, so that DevTools shows the
+    /// original sources.
+    ///
+    /// The protocol reports locations in the script itself, the map is not
+    /// applied to the locations of [`CdpError::JavascriptException`].
+    pub fn source_map(mut self, source_map: serde_json::Value) -> Self {
+        self.source_map = Some(source_map);
+        self
+    }
+
+    /// Whether the script is the body of a function rather than an
+    /// expression, so it can `return` its result. A returned promise is
+    /// awaited.
+    ///
+    /// The body is wrapped into a function that starts on a line of its
+    /// own. The locations of exceptions are shifted back by that line, so
+    /// they still refer to the lines of the script.
+    pub fn function_body(mut self, function_body: bool) -> Self {
+        self.function_body = function_body;
+        self
+    }
+
+    /// The source of the script as given.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The number of lines the generated code adds before the script.
+    fn line_offset(&self) -> i64 {
+        if self.function_body {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// The `//#` comments that name the script and attach its source map.
+    fn annotations(&self) -> Result<String> {
+        let mut annotations = String::new();
+        if let Some(url) = &self.source_url {
+            annotations.push_str(&format!("\n//# sourceURL={url}"));
+        }
+        if let Some(source_map) = &self.source_map {
+            let mut source_map = source_map.clone();
+            // an empty generated line for each line of the wrapper
+            if let Some(serde_json::Value::String(mappings)) = source_map.get_mut("mappings") {
+                mappings.insert_str(0, &";".repeat(self.line_offset() as usize));
+            }
+            let json = serde_json::to_vec(&source_map)?;
+            annotations.push_str(&format!(
+                "\n//# sourceMappingURL=data:application/json;charset=utf-8;base64,{}",
+                STANDARD.encode(json)
+            ));
+        }
+        Ok(annotations)
+    }
+
+    /// The evaluation that runs the script.
+    pub(crate) fn evaluation(&self) -> Result<Evaluation> {
+        let annotations = self.annotations()?;
+        if self.function_body {
+            Ok(CallFunctionOnParams::new(format!(
+                "{FUNCTION_BODY_PREFIX}{}\n}}{annotations}\n",
+                self.source
+            ))
+            .into())
+        } else if is_likely_js_function(&self.source) {
+            Ok(CallFunctionOnParams::new(format!("{}{annotations}\n", self.source)).into())
+        } else {
+            Ok(EvaluateParams::new(format!("{}{annotations}", self.source)).into())
+        }
+    }
+
+    /// Maps the locations of the `error` that are in the generated code back
+    /// onto the lines of the script.
+    pub(crate) fn map_error(&self, error: CdpError) -> CdpError {
+        match error {
+            CdpError::JavascriptException(mut details) => {
+                self.map_exception(&mut details);
+                CdpError::JavascriptException(details)
+            }
+            error => error,
+        }
+    }
+
+    fn map_exception(&self, details: &mut ExceptionDetails) {
+        let offset = self.line_offset();
+        let Some(url) = self.source_url.as_deref().filter(|_| offset > 0) else {
+            return;
+        };
+        if details.url.as_deref() == Some(url) {
+            details.line_number = (details.line_number - offset).max(0);
+        }
+        let mut stack_trace = details.stack_trace.as_mut();
+        while let Some(trace) = stack_trace {
+            for frame in trace
+                .call_frames
+                .iter_mut()
+                .filter(|frame| frame.url == url)
+            {
+                frame.line_number = (frame.line_number - offset).max(0);
+            }
+            stack_trace = trace.parent.as_deref_mut();
+        }
+        if let Some(description) = details
+            .exception
+            .as_mut()
+            .and_then(|exception| exception.description.as_mut())
+        {
+            *description = shift_stack_lines(description, url, offset);
+        }
+    }
+}
+
+/// Shifts the line of every `url:line:column` location in the `stack` text
+/// back by `offset` lines.
+fn shift_stack_lines(stack: &str, url: &str, offset: i64) -> String {
+    let mut shifted = String::with_capacity(stack.len());
+    let mut rest = stack;
+    while let Some(start) = rest.find(url) {
+        let (before, location) = rest.split_at(start + url.len());
+        shifted.push_str(before);
+        rest = location;
+        let Some(digits) = location.strip_prefix(':') else {
+            continue;
+        };
+        let len = digits
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(digits.len());
+        let line = digits[..len].parse::<i64>();
+        match line {
+            Ok(line) if digits[len..].starts_with(':') => {
+                shifted.push_str(&format!(":{}", (line - offset).max(1)));
+                rest = &digits[len..];
+            }
+            _ => {}
+        }
+    }
+    shifted.push_str(rest);
+    shifted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shifts_stack_locations_of_script() {
+        let stack = "Error: boom\n    at fail (file:///tmp/a.js:3:11)\n    at file:///tmp/a.js:5:1\n    at other.js:3:1";
+        assert_eq!(
+            shift_stack_lines(stack, "file:///tmp/a.js", 1),
+            "Error: boom\n    at fail (file:///tmp/a.js:2:11)\n    at file:///tmp/a.js:4:1\n    at other.js:3:1"
+        );
+        assert_eq!(
+            shift_stack_lines("at file:///tmp/a.js", "file:///tmp/a.js", 1),
+            "at file:///tmp/a.js"
+        );
+    }
+
+    #[test]
+    fn wraps_function_body_and_shifts_source_map() {
+        let script = JsScript::new("return 1;")
+            .function_body(true)
+            .source_url("a.js")
+            .source_map(serde_json::json!({ "version": 3, "mappings": "AAAA" }));
+        let Evaluation::Function(params) = script.evaluation().unwrap() else {
+            panic!("expected a function");
+        };
+        let (code, map) = params
+            .function_declaration
+            .split_once("\n//# sourceMappingURL=data:application/json;charset=utf-8;base64,")
+            .unwrap();
+        assert_eq!(code, "function() {\nreturn 1;\n}\n//# sourceURL=a.js");
+        let map: serde_json::Value =
+            serde_json::from_slice(&STANDARD.decode(map.trim_end()).unwrap()).unwrap();
+        assert_eq!(map["mappings"], ";AAAA");
+
+        let script = JsScript::new("1 + 1").source_url("b.js");
+        let Evaluation::Expression(params) = script.evaluation().unwrap() else {
+            panic!("expected an expression");
+        };
+        assert_eq!(params.expression, "1 + 1\n//# sourceURL=b.js");
+    }
+}
@@ -0,0 +1,161 @@
+//! Extraction of the content of `<table>` elements, see
+//! [`Element::table_matrix`](crate::element::Element::table_matrix).
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// How the cells of a table are read.
+///
+/// # Example
+///
+/// ```
+/// use chromiumoxide::js::table::TableOptions;
+///
+/// let options = TableOptions::new().expand_spans(true).inner_text(true);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableOptions {
+    expand_spans: bool,
+    inner_text: bool,
+}
+
+impl TableOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Repeats the text of cells with a `colspan` or `rowspan` in every
+    /// column and row they span, so all rows line up with the header.
+    ///
+    /// By default each cell is reported once, at the first free column of
+    /// its row.
+    pub fn expand_spans(mut self, expand: bool) -> Self {
+        self.expand_spans = expand;
+        self
+    }
+
+    /// Reads the rendered `innerText` of the cells instead of their
+    /// `textContent`, which skips hidden content and respects line breaks.
+    pub fn inner_text(mut self, inner_text: bool) -> Self {
+        self.inner_text = inner_text;
+        self
+    }
+}
+
+/// The trimmed text of the cells of a table, row by row.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TableMatrix {
+    /// The number of rows in the `<thead>`
+    pub header_rows: usize,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl TableMatrix {
+    /// The index of the row with the column names: the last row of the
+    /// `<thead>`, or the first row if there is none.
+    fn header_index(&self) -> usize {
+        self.header_rows.max(1) - 1
+    }
+
+    pub fn headers(&self) -> Vec<String> {
+        self.rows
+            .get(self.header_index())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Zips the headers with each row after them. Missing cells of ragged rows
+    /// are empty and cells without a header are skipped.
+    pub fn records(self) -> Vec<HashMap<String, String>> {
+        let headers = self.headers();
+        let skip = self.header_index() + 1;
+        self.rows
+            .into_iter()
+            .skip(skip)
+            .map(|row| {
+                let mut row = row.into_iter();
+                headers
+                    .iter()
+                    .map(|header| (header.clone(), row.next().unwrap_or_default()))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Reads the cells of `this` table into a `TableMatrix`, called with the
+/// `TableOptions`.
+pub(crate) const TABLE_MATRIX_FN: &str = "function(options) {
+    const text = (cell) => (options.innerText ? cell.innerText : cell.textContent).trim();
+    const rows = Array.from(this.rows);
+    const matrix = rows.map(() => []);
+    rows.forEach((row, r) => {
+        const out = matrix[r];
+        let col = 0;
+        for (const cell of row.cells) {
+            while (out[col] !== undefined) {
+                col++;
+            }
+            const value = text(cell);
+            if (!options.expandSpans) {
+                out[col++] = value;
+                continue;
+            }
+            const colSpan = Math.max(1, cell.colSpan);
+            const rowSpan = cell.rowSpan === 0 ? rows.length - r : Math.max(1, cell.rowSpan);
+            for (let dr = 0; dr < rowSpan && r + dr < rows.length; dr++) {
+                for (let dc = 0; dc < colSpan; dc++) {
+                    matrix[r + dr][col + dc] = value;
+                }
+            }
+            col += colSpan;
+        }
+    });
+    return {
+        headerRows: this.tHead ? this.tHead.rows.length : 0,
+        rows: matrix.map((row) => Array.from(row, (value) => value ?? '')),
+    };
+}";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|cell| cell.to_string()).collect()
+    }
+
+    #[test]
+    fn records_pad_ragged_rows() {
+        let matrix = TableMatrix {
+            header_rows: 0,
+            rows: vec![
+                row(&["name", "price"]),
+                row(&["apple", "1"]),
+                row(&["pear"]),
+            ],
+        };
+        assert_eq!(matrix.headers(), row(&["name", "price"]));
+        let records = matrix.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1]["name"], "pear");
+        assert_eq!(records[1]["price"], "");
+    }
+
+    #[test]
+    fn headers_from_last_thead_row() {
+        let matrix = TableMatrix {
+            header_rows: 2,
+            rows: vec![
+                row(&["fruit", "fruit"]),
+                row(&["name", "price"]),
+                row(&["apple", "1"]),
+            ],
+        };
+        assert_eq!(matrix.headers(), row(&["name", "price"]));
+        assert_eq!(matrix.records()[0]["price"], "1");
+    }
+}
@@ -0,0 +1,295 @@
+//! Serde helpers to exchange [`Duration`] and [`SystemTime`](std::time::SystemTime) values with
+//! JavaScript, which represents both as milliseconds.
+//!
+//! Use them with `#[serde(with = "...")]` on the fields of types that are
+//! passed to or returned from the page:
+//!
+//! ```
+//! use std::time::{Duration, SystemTime};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Schedule {
+//!     #[serde(with = "chromiumoxide::js::time::duration")]
+//!     interval: Duration,
+//!     #[serde(with = "chromiumoxide::js::time::system_time")]
+//!     start: SystemTime,
+//! }
+//! ```
+
+use std::time::Duration;
+
+/// The largest integer a JavaScript number can represent exactly
+/// (`Number.MAX_SAFE_INTEGER`).
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.;
+
+/// Converts the duration into (fractional) milliseconds, fails if the whole
+/// milliseconds can not be represented exactly by a JavaScript number.
+fn to_millis(duration: Duration) -> Result<f64, String> {
+    let millis = duration.as_secs() as f64 * 1000. + duration.subsec_nanos() as f64 / 1_000_000.;
+    if millis.trunc() > MAX_SAFE_INTEGER {
+        return Err(format!(
+            "{duration:?} exceeds the range of milliseconds that JavaScript can represent"
+        ));
+    }
+    Ok(millis)
+}
+
+/// Converts (fractional) milliseconds into a duration, fails for negative or
+/// non-finite numbers.
+fn from_millis(millis: f64) -> Result<Duration, String> {
+    if !millis.is_finite() || millis < 0. {
+        return Err(format!("{millis} is not a valid number of milliseconds"));
+    }
+    if millis.trunc() > MAX_SAFE_INTEGER {
+        return Err(format!(
+            "{millis} exceeds the range of milliseconds that JavaScript can represent"
+        ));
+    }
+    let whole = millis.trunc() as u64;
+    let nanos = ((millis - millis.trunc()) * 1_000_000.).round() as u64;
+    Ok(Duration::from_millis(whole) + Duration::from_nanos(nanos))
+}
+
+/// Serializes a [`Duration`] as number of milliseconds, sub-millisecond
+/// precision is preserved as fraction.
+pub mod duration {
+    use std::time::Duration;
+
+    use serde::{de, ser, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(super::to_millis(*duration).map_err(ser::Error::custom)?)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        super::from_millis(f64::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+/// Serializes a [`SystemTime`](std::time::SystemTime) as milliseconds since the unix epoch, like
+/// `Date.now()`.
+///
+/// Deserializing also accepts ISO-8601 strings in the format of
+/// `Date.prototype.toISOString()`.
+pub mod system_time {
+    use std::fmt;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use serde::{de, ser, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+        let since_epoch = time
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| ser::Error::custom(format!("{time:?} is before the unix epoch")))?;
+        serializer.serialize_f64(super::to_millis(since_epoch).map_err(ser::Error::custom)?)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SystemTime, D::Error> {
+        deserializer.deserialize_any(SystemTimeVisitor)
+    }
+
+    struct SystemTimeVisitor;
+
+    impl<'de> de::Visitor<'de> for SystemTimeVisitor {
+        type Value = SystemTime;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("milliseconds since the unix epoch or an ISO-8601 date string")
+        }
+
+        fn visit_f64<E: de::Error>(self, millis: f64) -> Result<SystemTime, E> {
+            if millis < 0. {
+                return Err(E::custom(format!(
+                    "{millis} milliseconds is before the unix epoch"
+                )));
+            }
+            Ok(UNIX_EPOCH + super::from_millis(millis).map_err(E::custom)?)
+        }
+
+        fn visit_i64<E: de::Error>(self, millis: i64) -> Result<SystemTime, E> {
+            self.visit_f64(millis as f64)
+        }
+
+        fn visit_u64<E: de::Error>(self, millis: u64) -> Result<SystemTime, E> {
+            self.visit_f64(millis as f64)
+        }
+
+        fn visit_str<E: de::Error>(self, date: &str) -> Result<SystemTime, E> {
+            self.visit_f64(super::parse_iso8601(date).map_err(E::custom)?)
+        }
+    }
+}
+
+/// Parses a date in the format of `Date.prototype.toISOString()`
+/// (`YYYY-MM-DDTHH:mm:ss.sssZ` or with an expanded year `±YYYYYY`) into
+/// milliseconds since the unix epoch.
+fn parse_iso8601(date: &str) -> Result<f64, String> {
+    let invalid = || format!("`{date}` is not a valid ISO-8601 date");
+
+    let (sign, rest) = match date.as_bytes().first() {
+        Some(b'+') => (1, &date[1..]),
+        Some(b'-') => (-1, &date[1..]),
+        _ => (1, date),
+    };
+    let (day, time) = rest.split_once('T').ok_or_else(invalid)?;
+    let time = time.strip_suffix('Z').ok_or_else(invalid)?;
+
+    let mut day = day.split('-');
+    let year: i64 = day
+        .next()
+        .ok_or_else(invalid)?
+        .parse()
+        .map_err(|_| invalid())?;
+    let month: i64 = day
+        .next()
+        .ok_or_else(invalid)?
+        .parse()
+        .map_err(|_| invalid())?;
+    let day_of_month: i64 = day
+        .next()
+        .ok_or_else(invalid)?
+        .parse()
+        .map_err(|_| invalid())?;
+    let year = sign * year;
+    if day.next().is_some()
+        || !(1..=12).contains(&month)
+        || !(1..=days_in_month(year, month)).contains(&day_of_month)
+    {
+        return Err(invalid());
+    }
+
+    let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let mut time = time.split(':');
+    let mut component = |max: i64| -> Result<i64, String> {
+        let value: i64 = time
+            .next()
+            .ok_or_else(invalid)?
+            .parse()
+            .map_err(|_| invalid())?;
+        if (0..=max).contains(&value) {
+            Ok(value)
+        } else {
+            Err(invalid())
+        }
+    };
+    let hours = component(23)?;
+    let minutes = component(59)?;
+    let seconds = component(59)?;
+    if time.next().is_some() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let millis: f64 = if fraction.is_empty() {
+        0.
+    } else {
+        format!("0.{fraction}")
+            .parse::<f64>()
+            .map_err(|_| invalid())?
+            * 1000.
+    };
+
+    let days = days_from_civil(year, month, day_of_month);
+    let seconds = ((days * 24 + hours) * 60 + minutes) * 60 + seconds;
+    Ok(seconds as f64 * 1000. + millis)
+}
+
+/// Number of days of the month in the proleptic Gregorian calendar.
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Number of days since the unix epoch of the given date in the proleptic
+/// Gregorian calendar.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let month = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Times {
+        #[serde(with = "duration")]
+        duration: Duration,
+        #[serde(with = "system_time")]
+        time: SystemTime,
+    }
+
+    #[test]
+    fn millis_roundtrip() {
+        let times = Times {
+            duration: Duration::from_micros(1_500),
+            time: UNIX_EPOCH + Duration::from_millis(1_700_000_000_123),
+        };
+        let value = serde_json::to_value(&times).unwrap();
+        assert_eq!(
+            value,
+            json!({ "duration": 1.5, "time": 1_700_000_000_123.0 })
+        );
+        assert_eq!(serde_json::from_value::<Times>(value).unwrap(), times);
+    }
+
+    #[test]
+    fn iso8601_time() {
+        let times: Times = serde_json::from_value(json!({
+            "duration": 0,
+            "time": "2023-11-14T22:13:20.123Z"
+        }))
+        .unwrap();
+        assert_eq!(
+            times.time,
+            UNIX_EPOCH + Duration::from_millis(1_700_000_000_123)
+        );
+        assert!(parse_iso8601("2023-11-14 22:13:20Z").is_err());
+        assert_eq!(
+            parse_iso8601("+002000-01-01T00:00:00Z").unwrap(),
+            946_684_800_000.
+        );
+    }
+
+    #[test]
+    fn invalid_dates() {
+        assert!(parse_iso8601("2024-02-29T00:00:00Z").is_ok());
+        assert!(parse_iso8601("2000-02-29T00:00:00Z").is_ok());
+        for date in [
+            "2023-02-29T00:00:00Z",
+            "1900-02-29T00:00:00Z",
+            "2023-04-31T00:00:00Z",
+            "2023-11-14T24:00:00Z",
+            "2023-11-14T22:13:20:00Z",
+            "2023-11-14T22:13:20.1e3Z",
+        ] {
+            assert!(parse_iso8601(date).is_err(), "{date}");
+        }
+    }
+
+    #[test]
+    fn out_of_range() {
+        let pre_epoch = Times {
+            duration: Duration::ZERO,
+            time: UNIX_EPOCH - Duration::from_secs(1),
+        };
+        assert!(serde_json::to_value(pre_epoch).is_err());
+        assert!(serde_json::from_value::<Times>(json!({ "duration": -1, "time": 0 })).is_err());
+        assert!(to_millis(Duration::from_secs(u64::MAX)).is_err());
+    }
+}
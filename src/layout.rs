@@ -1,12 +1,14 @@
 //! Code based on [rust-headless-chrome](https://github.com/atroche/rust-headless-chrome/blob/master/src/browser/tab/element/box_model.rs)
 
+use serde::Deserialize;
+
 use chromiumoxide_cdp::cdp::browser_protocol::dom::Quad;
 use chromiumoxide_cdp::cdp::browser_protocol::input::{
     DispatchMouseEventParams, DispatchMouseEventType, MouseButton,
 };
 use chromiumoxide_cdp::cdp::browser_protocol::page::Viewport;
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Deserialize)]
 pub struct Point {
     pub x: f64,
     pub y: f64,
@@ -21,6 +23,79 @@ impl Point {
     fn area(&self, other: &Self) -> f64 {
         (self.x * other.y - other.x * self.y) / 2.
     }
+
+    /// Converts a point in CSS pixels of the layout viewport into the visual
+    /// viewport, which is scrolled by `offset` within the layout viewport and
+    /// magnified by the page `scale`.
+    ///
+    /// Input events are dispatched in visual viewport coordinates, while
+    /// `getBoundingClientRect` and the DOM quads report layout viewport
+    /// coordinates. Both agree at a page scale of `1`.
+    pub fn to_visual_viewport(self, offset: Point, scale: f64) -> Self {
+        (self - offset) * scale
+    }
+
+    /// Converts a point in the visual viewport back into CSS pixels of the
+    /// layout viewport, see [`Point::to_visual_viewport`].
+    pub fn from_visual_viewport(self, offset: Point, scale: f64) -> Self {
+        self / scale + offset
+    }
+}
+
+/// The coordinate system of a [`Point`], see
+/// [`Page::translate_point`](crate::page::Page::translate_point).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CoordinateSpace {
+    /// CSS pixels of the layout viewport, as reported by
+    /// `getBoundingClientRect` and expected by the mouse helpers
+    Viewport,
+    /// CSS pixels of the whole document, as reported by `pageX`/`pageY` and
+    /// the pixels of a (not stitched) full page screenshot, which is taken
+    /// with a device scale factor of `1`
+    Document,
+    /// Pixels of a screenshot of the viewport, which are scaled by the
+    /// `devicePixelRatio` and the page scale
+    Screenshot,
+}
+
+/// The state of the viewports needed to translate between
+/// [`CoordinateSpace`]s.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewportMetrics {
+    /// `window.devicePixelRatio`
+    pub device_pixel_ratio: f64,
+    /// The page scale (pinch zoom) of the visual viewport
+    pub scale: f64,
+    /// The offset of the visual viewport within the layout viewport
+    pub offset: Point,
+    /// The scroll position of the layout viewport within the document
+    pub scroll: Point,
+}
+
+impl Default for ViewportMetrics {
+    fn default() -> Self {
+        Self {
+            device_pixel_ratio: 1.,
+            scale: 1.,
+            offset: Point::default(),
+            scroll: Point::default(),
+        }
+    }
+}
+
+impl ViewportMetrics {
+    /// Translates the `point` from the coordinate space `from` into CSS
+    /// pixels of the layout viewport.
+    pub fn to_viewport(&self, point: Point, from: CoordinateSpace) -> Point {
+        match from {
+            CoordinateSpace::Viewport => point,
+            CoordinateSpace::Document => point - self.scroll,
+            CoordinateSpace::Screenshot => {
+                (point / self.device_pixel_ratio).from_visual_viewport(self.offset, self.scale)
+            }
+        }
+    }
 }
 
 impl std::ops::Add<Point> for Point {
@@ -45,6 +120,17 @@ impl std::ops::Sub<Point> for Point {
     }
 }
 
+impl std::ops::Mul<f64> for Point {
+    type Output = Self;
+
+    fn mul(self, other: f64) -> Self {
+        Self {
+            x: self.x * other,
+            y: self.y * other,
+        }
+    }
+}
+
 impl std::ops::Div<f64> for Point {
     type Output = Self;
 
@@ -295,3 +381,51 @@ pub struct BoundingBox {
     /// the height of the element in pixels.
     pub height: f64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visual_viewport_conversion() {
+        let point = Point::new(300., 200.);
+
+        let offset = Point::new(0., 0.);
+        assert_eq!(
+            point.to_visual_viewport(offset, 0.5),
+            Point::new(150., 100.)
+        );
+        assert_eq!(point.to_visual_viewport(offset, 2.), Point::new(600., 400.));
+
+        let offset = Point::new(100., 50.);
+        let zoomed = point.to_visual_viewport(offset, 2.);
+        assert_eq!(zoomed, Point::new(400., 300.));
+        assert_eq!(zoomed.from_visual_viewport(offset, 2.), point);
+        let zoomed = point.to_visual_viewport(offset, 0.5);
+        assert_eq!(zoomed, Point::new(100., 75.));
+        assert_eq!(zoomed.from_visual_viewport(offset, 0.5), point);
+    }
+
+    #[test]
+    fn translate_to_viewport() {
+        let metrics = ViewportMetrics {
+            device_pixel_ratio: 2.,
+            scale: 2.,
+            offset: Point::new(10., 20.),
+            scroll: Point::new(0., 500.),
+        };
+        let point = Point::new(100., 700.);
+        assert_eq!(metrics.to_viewport(point, CoordinateSpace::Viewport), point);
+        assert_eq!(
+            metrics.to_viewport(point, CoordinateSpace::Document),
+            Point::new(100., 200.)
+        );
+        let viewport = metrics.to_viewport(point, CoordinateSpace::Screenshot);
+        assert_eq!(viewport, Point::new(35., 195.));
+        // clicking the translated point hits the screenshot pixel
+        assert_eq!(
+            viewport.to_visual_viewport(metrics.offset, metrics.scale) * 2.,
+            point
+        );
+    }
+}
@@ -68,7 +68,9 @@ use std::sync::Arc;
 pub use chromiumoxide_cdp::cdp;
 pub use chromiumoxide_types::{self as types, Binary, Command, Method, MethodType};
 
-pub use crate::browser::{Browser, BrowserConfig};
+pub use crate::browser::{
+    Browser, BrowserConfig, BrowserVersion, ShutdownEscalation, ShutdownReport,
+};
 pub use crate::conn::Connection;
 pub use crate::element::Element;
 pub use crate::error::Result;
@@ -79,6 +81,7 @@ pub use crate::page::Page;
 
 pub mod auth;
 pub mod browser;
+pub mod canvas;
 pub mod cmd;
 pub mod conn;
 pub mod detection;
@@ -89,12 +92,20 @@ pub mod fetcher {
     pub use chromiumoxide_fetcher::*;
 }
 pub mod async_process;
+pub mod form;
 pub mod handler;
 pub mod js;
 pub mod keys;
 pub mod layout;
 pub mod listeners;
+pub mod media;
+pub mod metadata;
+pub mod origin;
 pub mod page;
+pub mod retry;
+pub mod selector;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub(crate) mod utils;
 
 pub type ArcHttpRequest = Option<Arc<HttpRequest>>;
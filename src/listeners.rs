@@ -7,9 +7,10 @@ use std::task::{Context, Poll};
 
 use futures::channel::mpsc::{SendError, UnboundedReceiver, UnboundedSender};
 use futures::{Sink, Stream};
+use serde::Deserialize;
 
-use chromiumoxide_cdp::cdp::{Event, EventKind, IntoEventKind};
-use chromiumoxide_types::MethodId;
+use chromiumoxide_cdp::cdp::{CustomEvent, Event, EventKind, IntoEventKind};
+use chromiumoxide_types::{MethodId, MethodType};
 
 /// All the currently active listeners
 #[derive(Debug, Default)]
@@ -111,6 +112,24 @@ impl EventListenerRequest {
     }
 }
 
+impl EventListenerRequest {
+    /// Register a subscription for the events of the `method` as
+    /// [`RawEvent`]s
+    pub fn new_raw(listener: UnboundedSender<Arc<dyn Event>>, method: MethodId) -> Self {
+        let event_method = method.clone();
+        Self {
+            listener,
+            method,
+            kind: EventKind::Custom(Box::new(move |params| {
+                Ok(Arc::new(RawEvent {
+                    method: event_method.clone(),
+                    params,
+                }))
+            })),
+        }
+    }
+}
+
 impl fmt::Debug for EventListenerRequest {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("EventListenerRequest")
@@ -167,6 +186,28 @@ impl fmt::Debug for EventListener {
     }
 }
 
+/// An event that is not covered by the protocol definitions, with its
+/// `params` as they were received.
+///
+/// Subscriptions for these events are created with
+/// [`Page::raw_event_listener`](crate::page::Page::raw_event_listener).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct RawEvent {
+    /// The identifier of the event like `Domain.eventName`
+    #[serde(skip)]
+    pub method: MethodId,
+    pub params: serde_json::Value,
+}
+
+impl MethodType for RawEvent {
+    fn method_id() -> MethodId {
+        "Raw.Event".into()
+    }
+}
+
+impl CustomEvent for RawEvent {}
+
 /// The receiver part of an event subscription
 pub struct EventStream<T: IntoEventKind> {
     events: UnboundedReceiver<Arc<dyn Event>>,
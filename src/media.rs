@@ -0,0 +1,604 @@
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chromiumoxide_cdp::cdp::js_protocol::runtime::{CallArgument, CallFunctionOnParams};
+use serde::Deserialize;
+
+use crate::element::Element;
+use crate::error::{CdpError, Result};
+use crate::js::{call_function_on, value_or_null};
+use crate::utils;
+
+/// Represents a [HTMLMediaElement](https://developer.mozilla.org/en-US/docs/Web/API/HTMLMediaElement),
+/// the `<audio>` and `<video>` elements.
+///
+/// Derefs to the underlying [`Element`].
+#[derive(Debug)]
+pub struct MediaElement {
+    element: Element,
+}
+
+impl MediaElement {
+    /// Creates a new `MediaElement` from the `element`, fails if the element
+    /// is not an `HTMLMediaElement`.
+    ///
+    /// # Example Seek and capture a video frame
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::media::{MediaElement, ReadyState};
+    /// # use std::time::Duration;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let video = MediaElement::new(page.find_element("video").await?).await?;
+    ///     video
+    ///         .wait_for_ready_state(ReadyState::HaveMetadata, Duration::from_secs(10))
+    ///         .await?;
+    ///     video.seek(5., Duration::from_secs(10)).await?;
+    ///     let png = video.captured_frame().await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn new(element: Element) -> Result<Self> {
+        element.ensure_instance_of("HTMLMediaElement").await?;
+        Ok(Self { element })
+    }
+
+    /// Returns the underlying `Element`.
+    pub fn into_element(self) -> Element {
+        self.element
+    }
+
+    async fn call<T: serde::de::DeserializeOwned>(
+        &self,
+        function_declaration: &str,
+        args: Vec<serde_json::Value>,
+    ) -> Result<T> {
+        let value = call_function_on(
+            self.element.tab(),
+            &self.element.remote_object_id,
+            function_declaration,
+            args,
+            true,
+        )
+        .await?;
+        Ok(value_or_null(value)?)
+    }
+
+    async fn property<T: serde::de::DeserializeOwned>(&self, name: &str) -> Result<T> {
+        self.call("function(name) { return this[name]; }", vec![name.into()])
+            .await
+    }
+
+    async fn set_property(&self, name: &str, value: serde_json::Value) -> Result<&Self> {
+        call_function_on(
+            self.element.tab(),
+            &self.element.remote_object_id,
+            "function(name, value) { this[name] = value; }",
+            vec![name.into(), value],
+            true,
+        )
+        .await?;
+        Ok(self)
+    }
+
+    /// Calls the function and awaits the returned promise.
+    async fn call_async(
+        &self,
+        function_declaration: &str,
+        args: Vec<serde_json::Value>,
+        user_gesture: bool,
+    ) -> Result<serde_json::Value> {
+        let params = CallFunctionOnParams::builder()
+            .function_declaration(function_declaration)
+            .object_id(self.element.remote_object_id.clone())
+            .arguments(
+                args.into_iter()
+                    .map(|value| CallArgument::builder().value(value).build())
+                    .collect::<Vec<_>>(),
+            )
+            .await_promise(true)
+            .return_by_value(true)
+            .user_gesture(user_gesture)
+            .build()
+            .unwrap();
+        let result = self.element.tab().evaluate_function(params).await?;
+        Ok(result.value().cloned().unwrap_or_default())
+    }
+
+    /// The current playback position in seconds.
+    pub async fn current_time(&self) -> Result<f64> {
+        self.property("currentTime").await
+    }
+
+    /// Sets the current playback position in seconds, without waiting for the
+    /// seek to complete, see [`MediaElement::seek`].
+    pub async fn set_current_time(&self, seconds: f64) -> Result<&Self> {
+        self.set_property("currentTime", seconds.into()).await
+    }
+
+    /// The length of the media in seconds, `None` if it is unknown or
+    /// unbounded (e.g. a live stream).
+    pub async fn duration(&self) -> Result<Option<f64>> {
+        self.call(
+            "function() { return Number.isFinite(this.duration) ? this.duration : null; }",
+            vec![],
+        )
+        .await
+    }
+
+    /// Whether the media is paused.
+    pub async fn paused(&self) -> Result<bool> {
+        self.property("paused").await
+    }
+
+    /// Whether the media has finished playing.
+    pub async fn ended(&self) -> Result<bool> {
+        self.property("ended").await
+    }
+
+    /// Whether the audio is muted.
+    pub async fn muted(&self) -> Result<bool> {
+        self.property("muted").await
+    }
+
+    /// Mutes or unmutes the audio.
+    pub async fn set_muted(&self, muted: bool) -> Result<&Self> {
+        self.set_property("muted", muted.into()).await
+    }
+
+    /// The audio volume, from `0.0` (silent) to `1.0` (loudest).
+    pub async fn volume(&self) -> Result<f64> {
+        self.property("volume").await
+    }
+
+    /// Sets the audio volume, from `0.0` (silent) to `1.0` (loudest).
+    pub async fn set_volume(&self, volume: f64) -> Result<&Self> {
+        self.set_property("volume", volume.into()).await
+    }
+
+    /// The rate at which the media is played back.
+    pub async fn playback_rate(&self) -> Result<f64> {
+        self.property("playbackRate").await
+    }
+
+    /// Sets the rate at which the media is played back.
+    pub async fn set_playback_rate(&self, rate: f64) -> Result<&Self> {
+        self.set_property("playbackRate", rate.into()).await
+    }
+
+    /// The readiness state of the media.
+    pub async fn ready_state(&self) -> Result<ReadyState> {
+        let state: u8 = self.property("readyState").await?;
+        Ok(ReadyState::from(state))
+    }
+
+    /// Starts the playback and waits until it has begun.
+    ///
+    /// Browsers may block playback that was not triggered by a user gesture
+    /// (autoplay policy), which fails with [`CdpError::AutoplayBlocked`].
+    /// Pass `user_gesture` to treat the call as initiated by the user.
+    pub async fn play(&self, user_gesture: bool) -> Result<&Self> {
+        match self
+            .call_async("function() { return this.play(); }", vec![], user_gesture)
+            .await
+        {
+            Err(CdpError::JavascriptException(exception))
+                if exception
+                    .exception
+                    .as_ref()
+                    .and_then(|e| e.description.as_deref())
+                    .is_some_and(|d| d.starts_with("NotAllowedError")) =>
+            {
+                Err(CdpError::AutoplayBlocked)
+            }
+            res => res.map(|_| self),
+        }
+    }
+
+    /// Pauses the playback.
+    pub async fn pause(&self) -> Result<&Self> {
+        call_function_on(
+            self.element.tab(),
+            &self.element.remote_object_id,
+            "function() { this.pause(); }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(self)
+    }
+
+    /// Resets the media and reloads its source.
+    pub async fn load(&self) -> Result<&Self> {
+        call_function_on(
+            self.element.tab(),
+            &self.element.remote_object_id,
+            "function() { this.load(); }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(self)
+    }
+
+    /// Waits until the media reached at least the `state`.
+    ///
+    /// Returns [`CdpError::Timeout`] if the state was not reached within the
+    /// `timeout`.
+    pub async fn wait_for_ready_state(
+        &self,
+        state: ReadyState,
+        timeout: Duration,
+    ) -> Result<&Self> {
+        let ready = self
+            .call_async(
+                "function(state, timeout) {
+                    return new Promise(resolve => {
+                        const events = ['loadedmetadata', 'loadeddata', 'canplay', 'canplaythrough'];
+                        const done = ready => {
+                            clearTimeout(timer);
+                            events.forEach(e => this.removeEventListener(e, check));
+                            resolve(ready);
+                        };
+                        const check = () => {
+                            if (this.readyState >= state) {
+                                done(true);
+                            }
+                        };
+                        const timer = setTimeout(() => done(false), timeout);
+                        events.forEach(e => this.addEventListener(e, check));
+                        check();
+                    });
+                }",
+                vec![(state as u8).into(), (timeout.as_millis() as u64).into()],
+                false,
+            )
+            .await?;
+        if ready.as_bool() == Some(true) {
+            Ok(self)
+        } else {
+            Err(CdpError::Timeout)
+        }
+    }
+
+    /// Sets the current playback position in seconds and waits until the
+    /// seek completed.
+    ///
+    /// Fails right away if the media has no metadata yet, its seek would be
+    /// deferred until it has, see [`MediaElement::wait_for_ready_state`].
+    /// Returns [`CdpError::Timeout`] if the seek did not complete within the
+    /// `timeout`.
+    pub async fn seek(&self, seconds: f64, timeout: Duration) -> Result<&Self> {
+        let seeked = self
+            .call_async(
+                "function(seconds, timeout) {
+                    if (this.readyState === HTMLMediaElement.HAVE_NOTHING) {
+                        return null;
+                    }
+                    return new Promise(resolve => {
+                        const seeked = () => {
+                            clearTimeout(timer);
+                            resolve(true);
+                        };
+                        const timer = setTimeout(() => {
+                            this.removeEventListener('seeked', seeked);
+                            resolve(false);
+                        }, timeout);
+                        this.addEventListener('seeked', seeked, { once: true });
+                        this.currentTime = seconds;
+                    });
+                }",
+                vec![seconds.into(), (timeout.as_millis() as u64).into()],
+                false,
+            )
+            .await?;
+        match seeked.as_bool() {
+            Some(true) => Ok(self),
+            Some(false) => Err(CdpError::Timeout),
+            None => Err(CdpError::msg(
+                "Can not seek before the media has loaded its metadata",
+            )),
+        }
+    }
+
+    /// Draws the current frame of a `<video>` element on a canvas and returns
+    /// it as PNG image.
+    ///
+    /// Fails for `<audio>` elements and for cross-origin videos, that taint
+    /// the canvas.
+    pub async fn captured_frame(&self) -> Result<Vec<u8>> {
+        let data_url: String = self
+            .call(
+                "function() {
+                    if (!(this instanceof HTMLVideoElement)) {
+                        throw new TypeError('Element is not an HTMLVideoElement');
+                    }
+                    const canvas = document.createElement('canvas');
+                    canvas.width = this.videoWidth;
+                    canvas.height = this.videoHeight;
+                    canvas.getContext('2d').drawImage(this, 0, 0);
+                    return canvas.toDataURL('image/png');
+                }",
+                vec![],
+            )
+            .await?;
+        let data = data_url
+            .strip_prefix("data:image/png;base64,")
+            .ok_or_else(|| CdpError::msg("Video frame could not be captured"))?;
+        Ok(utils::base64::decode(data)?)
+    }
+}
+
+impl Deref for MediaElement {
+    type Target = Element;
+
+    fn deref(&self) -> &Self::Target {
+        &self.element
+    }
+}
+
+/// Represents a [HTMLVideoElement](https://developer.mozilla.org/en-US/docs/Web/API/HTMLVideoElement),
+/// the `<video>` element.
+///
+/// Derefs to the underlying [`MediaElement`].
+#[derive(Debug)]
+pub struct VideoElement {
+    media: MediaElement,
+}
+
+impl VideoElement {
+    /// Creates a new `VideoElement` from the `element`, fails if the element
+    /// is not an `HTMLVideoElement`.
+    pub async fn new(element: Element) -> Result<Self> {
+        element.ensure_instance_of("HTMLVideoElement").await?;
+        Ok(Self {
+            media: MediaElement { element },
+        })
+    }
+
+    /// Returns the underlying `MediaElement`.
+    pub fn into_media(self) -> MediaElement {
+        self.media
+    }
+
+    /// The intrinsic width of the video in pixels, `0` until the metadata
+    /// is available.
+    pub async fn video_width(&self) -> Result<u32> {
+        self.media.property("videoWidth").await
+    }
+
+    /// The intrinsic height of the video in pixels, `0` until the metadata
+    /// is available.
+    pub async fn video_height(&self) -> Result<u32> {
+        self.media.property("videoHeight").await
+    }
+}
+
+impl Deref for VideoElement {
+    type Target = MediaElement;
+
+    fn deref(&self) -> &Self::Target {
+        &self.media
+    }
+}
+
+/// Represents a [HTMLAudioElement](https://developer.mozilla.org/en-US/docs/Web/API/HTMLAudioElement),
+/// the `<audio>` element.
+///
+/// Derefs to the underlying [`MediaElement`].
+#[derive(Debug)]
+pub struct AudioElement {
+    media: MediaElement,
+}
+
+impl AudioElement {
+    /// Creates a new `AudioElement` from the `element`, fails if the element
+    /// is not an `HTMLAudioElement`.
+    pub async fn new(element: Element) -> Result<Self> {
+        element.ensure_instance_of("HTMLAudioElement").await?;
+        Ok(Self {
+            media: MediaElement { element },
+        })
+    }
+
+    /// Returns the underlying `MediaElement`.
+    pub fn into_media(self) -> MediaElement {
+        self.media
+    }
+}
+
+impl Deref for AudioElement {
+    type Target = MediaElement;
+
+    fn deref(&self) -> &Self::Target {
+        &self.media
+    }
+}
+
+/// The [readiness state](https://developer.mozilla.org/en-US/docs/Web/API/HTMLMediaElement/readyState)
+/// of a media element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReadyState {
+    /// No information is available about the media.
+    HaveNothing = 0,
+    /// The metadata, like the duration, is available.
+    HaveMetadata = 1,
+    /// Data for the current playback position is available.
+    HaveCurrentData = 2,
+    /// Data for the current and at least the next frame is available.
+    HaveFutureData = 3,
+    /// Enough data is available to play the media to the end.
+    HaveEnoughData = 4,
+}
+
+impl From<u8> for ReadyState {
+    fn from(state: u8) -> Self {
+        match state {
+            0 => ReadyState::HaveNothing,
+            1 => ReadyState::HaveMetadata,
+            2 => ReadyState::HaveCurrentData,
+            3 => ReadyState::HaveFutureData,
+            _ => ReadyState::HaveEnoughData,
+        }
+    }
+}
+
+/// Fake camera and microphone devices for testing `getUserMedia` flows, see
+/// [`BrowserConfigBuilder::fake_media`](crate::browser::BrowserConfigBuilder::fake_media).
+///
+/// By default Chrome generates a test pattern and a beep, the permission
+/// prompt is accepted automatically.
+///
+/// # Example
+///
+/// ```no_run
+/// # use chromiumoxide::browser::{Browser, BrowserConfig};
+/// # use chromiumoxide::error::Result;
+/// # use chromiumoxide::media::FakeMediaConfig;
+/// # async fn demo() -> Result<()> {
+///     let config = BrowserConfig::builder()
+///         .fake_media(FakeMediaConfig::new().video_file("fixtures/camera.y4m"))
+///         .build()
+///         .unwrap();
+///     let (browser, handler) = Browser::launch(config).await?;
+///     let page = browser.new_page("about:blank").await?;
+///     let _recording = page.record_media_streams().await?;
+///     page.goto("https://example.com/call").await?;
+///     let streams = page.active_media_streams().await?;
+///     assert!(streams.iter().any(|s| s.tracks.iter().any(|t| t.kind == "video")));
+///     page.stop_all_media_tracks().await?;
+///     # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FakeMediaConfig {
+    video_file: Option<PathBuf>,
+    audio_file: Option<PathBuf>,
+}
+
+impl FakeMediaConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Plays the file as the camera, a `.y4m` or `.mjpeg` video.
+    pub fn video_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.video_file = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Plays the file as the microphone, a `.wav` audio.
+    pub fn audio_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.audio_file = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// The command line arguments to launch the browser with.
+    pub fn args(&self) -> Vec<String> {
+        let mut args = vec![
+            "--use-fake-device-for-media-stream".to_string(),
+            "--use-fake-ui-for-media-stream".to_string(),
+        ];
+        if let Some(video) = &self.video_file {
+            args.push(format!(
+                "--use-file-for-fake-video-capture={}",
+                video.display()
+            ));
+        }
+        if let Some(audio) = &self.audio_file {
+            args.push(format!(
+                "--use-file-for-fake-audio-capture={}",
+                audio.display()
+            ));
+        }
+        args
+    }
+}
+
+/// A stream obtained by the page with `getUserMedia`, see
+/// [`Page::active_media_streams`](crate::page::Page::active_media_streams).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaStreamInfo {
+    pub id: String,
+    pub active: bool,
+    /// The constraints `getUserMedia` was called with
+    pub constraints: serde_json::Value,
+    pub tracks: Vec<MediaTrackInfo>,
+}
+
+/// A track of a [`MediaStreamInfo`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaTrackInfo {
+    pub id: String,
+    /// `audio` or `video`
+    pub kind: String,
+    /// The label of the device, like `fake_device_0`
+    pub label: String,
+    /// `live` or `ended`
+    pub ready_state: String,
+    pub enabled: bool,
+    pub muted: bool,
+    /// The result of `getSettings()`, like the `width` and `height` of the
+    /// video or the `deviceId`
+    pub settings: serde_json::Value,
+}
+
+/// Records the streams returned by `getUserMedia` together with their
+/// constraints. The promise is passed on as is, rejections included.
+pub(crate) const RECORD_MEDIA_STREAMS_SCRIPT: &str = "(() => {
+    const devices = globalThis.navigator && navigator.mediaDevices;
+    const key = Symbol.for('chromiumoxide.mediaStreams');
+    if (!devices || typeof devices.getUserMedia !== 'function' || devices[key]) {
+        return;
+    }
+    const streams = [];
+    Object.defineProperty(devices, key, { value: streams });
+    const getUserMedia = devices.getUserMedia;
+    devices.getUserMedia = function(constraints) {
+        const result = getUserMedia.apply(this, arguments);
+        result.then((stream) => streams.push({ stream, constraints }), () => {});
+        return result;
+    };
+})()";
+
+/// Returns the recorded streams of the page that are still active.
+pub(crate) const ACTIVE_MEDIA_STREAMS_FN: &str = "() => {
+    const devices = globalThis.navigator && navigator.mediaDevices;
+    const streams = (devices && devices[Symbol.for('chromiumoxide.mediaStreams')]) || [];
+    return streams
+        .filter(({ stream }) => stream.active)
+        .map(({ stream, constraints }) => ({
+            id: stream.id,
+            active: stream.active,
+            constraints: constraints === undefined ? null : constraints,
+            tracks: stream.getTracks().map((track) => ({
+                id: track.id,
+                kind: track.kind,
+                label: track.label,
+                readyState: track.readyState,
+                enabled: track.enabled,
+                muted: track.muted,
+                settings: track.getSettings(),
+            })),
+        }));
+}";
+
+/// Stops all tracks of the recorded streams and returns how many were live.
+pub(crate) const STOP_MEDIA_TRACKS_FN: &str = "() => {
+    const devices = globalThis.navigator && navigator.mediaDevices;
+    const streams = (devices && devices[Symbol.for('chromiumoxide.mediaStreams')]) || [];
+    let stopped = 0;
+    for (const { stream } of streams.splice(0)) {
+        for (const track of stream.getTracks()) {
+            if (track.readyState === 'live') {
+                stopped++;
+            }
+            track.stop();
+        }
+    }
+    return stopped;
+}";
@@ -0,0 +1,294 @@
+//! Metadata of a document, see [`Page::metadata`](crate::Page::metadata).
+
+use serde::Deserialize;
+
+/// The metadata of a document: title, meta tags, OpenGraph and Twitter card
+/// fields, icons and JSON-LD blocks.
+///
+/// Where a tag occurs more than once, the typed fields hold the first
+/// occurrence, as crawlers do. All occurrences are listed in
+/// [`PageMetadata::meta`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PageMetadata {
+    /// The title of the document, `document.title`
+    pub title: String,
+    /// The language of the document, the `lang` attribute of `<html>`
+    pub lang: Option<String>,
+    /// The character encoding of the document, like `UTF-8`
+    pub charset: String,
+    /// The base URL relative URLs are resolved against
+    pub base_url: String,
+    /// The absolute URL of the first `<link rel="canonical">`
+    pub canonical: Option<String>,
+    /// All `<meta>` tags with a `content`, in document order
+    pub meta: Vec<MetaTag>,
+    /// The `og:` fields of the meta tags
+    pub open_graph: OpenGraph,
+    /// The `twitter:` fields of the meta tags
+    pub twitter: TwitterCard,
+    /// The absolute URLs of the icons, like `<link rel="icon">` or
+    /// `<link rel="apple-touch-icon">`, without duplicates
+    pub icons: Vec<String>,
+    /// The `<script type="application/ld+json">` blocks in document order
+    pub json_ld: Vec<JsonLd>,
+}
+
+impl PageMetadata {
+    /// The content of the first meta tag whose `name` or `property` is `key`,
+    /// compared ignoring ASCII case.
+    pub fn meta_content(&self, key: &str) -> Option<&str> {
+        first_content(&self.meta, key)
+    }
+}
+
+/// A `<meta>` tag with a `content` attribute.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct MetaTag {
+    /// The `name` attribute, like `description`
+    pub name: Option<String>,
+    /// The `property` attribute, like `og:title`
+    pub property: Option<String>,
+    /// The `content` attribute, resolved into an absolute URL for fields
+    /// that hold one, like `og:image`
+    pub content: String,
+}
+
+impl MetaTag {
+    fn has_key(&self, key: &str) -> bool {
+        [&self.name, &self.property]
+            .into_iter()
+            .flatten()
+            .any(|k| k.trim().eq_ignore_ascii_case(key))
+    }
+}
+
+/// The [OpenGraph](https://ogp.me) fields of a document.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OpenGraph {
+    pub title: Option<String>,
+    /// `og:type`, like `website` or `article`
+    pub kind: Option<String>,
+    pub url: Option<String>,
+    pub image: Option<String>,
+    pub description: Option<String>,
+    pub site_name: Option<String>,
+    pub locale: Option<String>,
+}
+
+impl OpenGraph {
+    fn from_meta(meta: &[MetaTag]) -> Self {
+        let field = |key: &str| first_content(meta, key).map(str::to_string);
+        Self {
+            title: field("og:title"),
+            kind: field("og:type"),
+            url: field("og:url"),
+            image: field("og:image").or_else(|| field("og:image:url")),
+            description: field("og:description"),
+            site_name: field("og:site_name"),
+            locale: field("og:locale"),
+        }
+    }
+}
+
+/// The [Twitter card](https://developer.x.com/en/docs/x-for-websites/cards/overview/markup)
+/// fields of a document.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TwitterCard {
+    /// `twitter:card`, like `summary_large_image`
+    pub card: Option<String>,
+    pub site: Option<String>,
+    pub creator: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+}
+
+impl TwitterCard {
+    fn from_meta(meta: &[MetaTag]) -> Self {
+        let field = |key: &str| first_content(meta, key).map(str::to_string);
+        Self {
+            card: field("twitter:card"),
+            site: field("twitter:site"),
+            creator: field("twitter:creator"),
+            title: field("twitter:title"),
+            description: field("twitter:description"),
+            image: field("twitter:image").or_else(|| field("twitter:image:src")),
+        }
+    }
+}
+
+/// A JSON-LD block of a document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonLd {
+    /// The parsed content of the block
+    Valid(serde_json::Value),
+    /// A block that is not valid JSON
+    Invalid {
+        /// The text of the block
+        source: String,
+        /// Why the text could not be parsed
+        error: String,
+    },
+}
+
+impl JsonLd {
+    fn parse(source: String) -> Self {
+        match serde_json::from_str(&source) {
+            Ok(value) => JsonLd::Valid(value),
+            Err(err) => JsonLd::Invalid {
+                error: err.to_string(),
+                source,
+            },
+        }
+    }
+
+    /// The parsed content, `None` if the block is invalid.
+    pub fn value(&self) -> Option<&serde_json::Value> {
+        match self {
+            JsonLd::Valid(value) => Some(value),
+            JsonLd::Invalid { .. } => None,
+        }
+    }
+}
+
+fn first_content<'a>(meta: &'a [MetaTag], key: &str) -> Option<&'a str> {
+    meta.iter()
+        .find(|tag| tag.has_key(key))
+        .map(|tag| tag.content.as_str())
+}
+
+/// The metadata as collected by [`METADATA_FN`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RawMetadata {
+    title: String,
+    lang: Option<String>,
+    charset: String,
+    base_url: String,
+    canonical: Option<String>,
+    meta: Vec<MetaTag>,
+    icons: Vec<String>,
+    json_ld: Vec<String>,
+}
+
+impl From<RawMetadata> for PageMetadata {
+    fn from(raw: RawMetadata) -> Self {
+        Self {
+            open_graph: OpenGraph::from_meta(&raw.meta),
+            twitter: TwitterCard::from_meta(&raw.meta),
+            title: raw.title,
+            lang: raw.lang,
+            charset: raw.charset,
+            base_url: raw.base_url,
+            canonical: raw.canonical,
+            meta: raw.meta,
+            icons: raw.icons,
+            json_ld: raw.json_ld.into_iter().map(JsonLd::parse).collect(),
+        }
+    }
+}
+
+/// Collects the metadata of the document into a [`RawMetadata`]. URLs are
+/// resolved against the base URL of the document.
+pub(crate) const METADATA_FN: &str = "() => {
+    const URL_KEYS = new Set([
+        'og:url', 'og:image', 'og:image:url', 'og:image:secure_url', 'og:video', 'og:audio',
+        'twitter:image', 'twitter:image:src',
+    ]);
+    const resolve = (url) => {
+        try {
+            return new URL(url, document.baseURI).href;
+        } catch (e) {
+            return url;
+        }
+    };
+    const links = Array.from(document.querySelectorAll('link[href][rel]'));
+    const rels = (link) => link.rel.toLowerCase().split(/\\s+/);
+    const canonical = links.find((link) => rels(link).includes('canonical'));
+    const icons = links
+        .filter((link) => rels(link).some((rel) => rel.includes('icon')))
+        .map((link) => link.href);
+    const meta = Array.from(document.querySelectorAll('meta[content]'), (tag) => {
+        const name = tag.getAttribute('name');
+        const property = tag.getAttribute('property');
+        const key = (property || name || '').trim().toLowerCase();
+        const content = tag.getAttribute('content');
+        return { name, property, content: URL_KEYS.has(key) ? resolve(content) : content };
+    });
+    const lang = document.documentElement ? document.documentElement.lang : '';
+    return {
+        title: document.title,
+        lang: lang || null,
+        charset: document.characterSet,
+        baseUrl: document.baseURI,
+        canonical: canonical ? canonical.href : null,
+        meta,
+        icons: Array.from(new Set(icons)),
+        jsonLd: Array.from(
+            document.querySelectorAll('script[type=\"application/ld+json\" i]'),
+            (script) => script.textContent,
+        ),
+    };
+}";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(name: Option<&str>, property: Option<&str>, content: &str) -> MetaTag {
+        MetaTag {
+            name: name.map(str::to_string),
+            property: property.map(str::to_string),
+            content: content.to_string(),
+        }
+    }
+
+    fn raw(meta: Vec<MetaTag>, json_ld: Vec<&str>) -> RawMetadata {
+        RawMetadata {
+            title: "Title".to_string(),
+            lang: None,
+            charset: "UTF-8".to_string(),
+            base_url: "https://example.com/".to_string(),
+            canonical: None,
+            meta,
+            icons: vec![],
+            json_ld: json_ld.into_iter().map(str::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn first_duplicate_wins() {
+        let metadata = PageMetadata::from(raw(
+            vec![
+                tag(None, Some("og:title"), "First"),
+                tag(None, Some("OG:TITLE"), "Second"),
+                tag(Some("twitter:card"), None, "summary"),
+                tag(None, Some("twitter:card"), "player"),
+                tag(Some("description"), None, "About"),
+            ],
+            vec![],
+        ));
+        assert_eq!(metadata.open_graph.title.as_deref(), Some("First"));
+        assert_eq!(metadata.twitter.card.as_deref(), Some("summary"));
+        assert_eq!(metadata.meta_content("Description"), Some("About"));
+        assert_eq!(metadata.open_graph.image, None);
+        assert_eq!(metadata.meta.len(), 5);
+    }
+
+    #[test]
+    fn invalid_json_ld_is_reported_per_block() {
+        let metadata = PageMetadata::from(raw(
+            vec![],
+            vec![r#"{"@type": "Article"}"#, "{ invalid", "[]"],
+        ));
+        assert_eq!(metadata.json_ld.len(), 3);
+        assert_eq!(
+            metadata.json_ld[0].value(),
+            Some(&serde_json::json!({"@type": "Article"}))
+        );
+        assert!(
+            matches!(&metadata.json_ld[1], JsonLd::Invalid { source, .. } if source == "{ invalid")
+        );
+        assert_eq!(metadata.json_ld[2].value(), Some(&serde_json::json!([])));
+    }
+}
@@ -0,0 +1,184 @@
+use chromiumoxide_cdp::cdp::js_protocol::runtime::{ExecutionContextId, RemoteObjectId};
+use url::Url;
+
+/// Restricts the origins a [`Page`](crate::page::Page) may evaluate scripts
+/// in.
+///
+/// Patterns are origins like `https://example.com`, optionally with a port
+/// (`http://localhost:8080`). The scheme may be omitted to match any scheme
+/// and the host may start with `*.` to match all its subdomains, e.g.
+/// `https://*.example.com` matches `https://a.b.example.com` but not
+/// `https://example.com` itself. Without an explicit port only the default
+/// port of the scheme matches.
+///
+/// Opaque origins, as reported for `about:blank` and `data:` documents, are
+/// rejected unless enabled via [`EvalOriginAllowlist::allow_opaque_origins`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EvalOriginAllowlist {
+    patterns: Vec<String>,
+    allow_opaque_origins: bool,
+}
+
+impl EvalOriginAllowlist {
+    pub fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            patterns: patterns
+                .into_iter()
+                .map(|p| p.into().to_ascii_lowercase())
+                .collect(),
+            allow_opaque_origins: false,
+        }
+    }
+
+    /// Whether evaluation in opaque origins (`about:blank`, `data:`) is
+    /// allowed. Defaults to `false`.
+    pub fn allow_opaque_origins(mut self, allow: bool) -> Self {
+        self.allow_opaque_origins = allow;
+        self
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Whether the serialized `origin` of an execution context matches the
+    /// allowlist.
+    pub fn allows(&self, origin: &str) -> bool {
+        if is_opaque(origin) {
+            return self.allow_opaque_origins;
+        }
+        let Ok(url) = Url::parse(origin) else {
+            return false;
+        };
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        self.patterns
+            .iter()
+            .any(|pattern| matches(pattern, url.scheme(), host, url.port()))
+    }
+}
+
+impl From<Vec<String>> for EvalOriginAllowlist {
+    fn from(patterns: Vec<String>) -> Self {
+        Self::new(patterns)
+    }
+}
+
+fn is_opaque(origin: &str) -> bool {
+    origin.is_empty()
+        || origin == "null"
+        || origin.starts_with("about:")
+        || origin.starts_with("data:")
+}
+
+fn matches(pattern: &str, scheme: &str, host: &str, port: Option<u16>) -> bool {
+    let rest = match pattern.split_once("://") {
+        Some((pattern_scheme, rest)) => {
+            if pattern_scheme != scheme {
+                return false;
+            }
+            rest
+        }
+        None => pattern,
+    };
+    let rest = rest.trim_end_matches('/');
+    let (pattern_host, pattern_port) = match rest.rsplit_once(':') {
+        // ipv6 hosts are bracketed, `[::1]`
+        Some((h, p)) if !rest.ends_with(']') => match p.parse::<u16>() {
+            Ok(p) => (h, Some(p)),
+            Err(_) => return false,
+        },
+        _ => (rest, None),
+    };
+    if pattern_port.is_some() && pattern_port != port.or_else(|| default_port(scheme)) {
+        return false;
+    }
+    if pattern_port.is_none() && port.is_some() {
+        return false;
+    }
+    match pattern_host.strip_prefix("*.") {
+        Some(domain) => host
+            .strip_suffix(domain)
+            .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.')),
+        None => host == pattern_host,
+    }
+}
+
+fn default_port(scheme: &str) -> Option<u16> {
+    match scheme {
+        "http" | "ws" => Some(80),
+        "https" | "wss" => Some(443),
+        _ => None,
+    }
+}
+
+/// The execution context the remote object was created in.
+///
+/// Chromium records the context in the id of every remote object, as
+/// `{isolate}.{context}.{object}` or, before Chrome 91, as
+/// `{"injectedScriptId":context,"id":object}`, so this needs no round trip.
+pub(crate) fn object_context_id(object_id: &RemoteObjectId) -> Option<ExecutionContextId> {
+    let id = object_id.inner();
+    let context = if id.starts_with('{') {
+        serde_json::from_str::<serde_json::Value>(id)
+            .ok()?
+            .get("injectedScriptId")?
+            .as_i64()?
+    } else {
+        let mut parts = id.split('.');
+        let (_isolate, context, _object) = (parts.next()?, parts.next()?, parts.next()?);
+        if parts.next().is_some() {
+            return None;
+        }
+        context.parse().ok()?
+    };
+    Some(ExecutionContextId::new(context))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn origin_patterns() {
+        let allowlist = EvalOriginAllowlist::new([
+            "https://example.com",
+            "*.Test.org",
+            "http://localhost:8080",
+        ]);
+
+        assert!(allowlist.allows("https://example.com"));
+        assert!(!allowlist.allows("http://example.com"));
+        assert!(!allowlist.allows("https://example.com:8443"));
+        assert!(!allowlist.allows("https://www.example.com"));
+
+        assert!(allowlist.allows("https://a.test.org"));
+        assert!(allowlist.allows("http://a.b.test.org"));
+        assert!(!allowlist.allows("https://test.org"));
+        assert!(!allowlist.allows("https://eviltest.org"));
+
+        assert!(allowlist.allows("http://localhost:8080"));
+        assert!(!allowlist.allows("http://localhost"));
+
+        assert!(!allowlist.allows("null"));
+        assert!(!allowlist.allows("about:blank"));
+        let allowlist = allowlist.allow_opaque_origins(true);
+        assert!(allowlist.allows("null"));
+        assert!(allowlist.allows(""));
+    }
+
+    #[test]
+    fn object_contexts() {
+        let context = |id: &str| object_context_id(&RemoteObjectId::new(id)).map(|id| *id.inner());
+        assert_eq!(context("-6371384405245096712.3.17"), Some(3));
+        assert_eq!(context(r#"{"injectedScriptId":2,"id":5}"#), Some(2));
+        assert_eq!(context("3.17"), None);
+        assert_eq!(context("1.2.3.4"), None);
+        assert_eq!(context("a.b.c"), None);
+    }
+}
@@ -1,14 +1,28 @@
-use std::path::Path;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
 use futures::channel::mpsc::unbounded;
 use futures::channel::oneshot::channel as oneshot_channel;
-use futures::{stream, SinkExt, StreamExt};
+use futures::io::{AsyncWrite, AsyncWriteExt};
+use futures::{ready, stream, FutureExt, SinkExt, Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 
+use chromiumoxide_cdp::cdp::browser_protocol::animation::SetPlaybackRateParams;
+use chromiumoxide_cdp::cdp::browser_protocol::browser::{
+    Bounds, GetWindowForTargetParams, PermissionType, SetWindowBoundsParams, WindowId, WindowState,
+};
 use chromiumoxide_cdp::cdp::browser_protocol::dom::*;
+use chromiumoxide_cdp::cdp::browser_protocol::dom_debugger::GetEventListenersParams;
 use chromiumoxide_cdp::cdp::browser_protocol::emulation::{
-    MediaFeature, SetEmulatedMediaParams, SetGeolocationOverrideParams, SetLocaleOverrideParams,
-    SetTimezoneOverrideParams,
+    EventVirtualTimeBudgetExpired, MediaFeature, SetEmulatedMediaParams,
+    SetFocusEmulationEnabledParams, SetGeolocationOverrideParams, SetLocaleOverrideParams,
+    SetTimezoneOverrideParams, SetVirtualTimePolicyParams, VirtualTimePolicy,
 };
 use chromiumoxide_cdp::cdp::browser_protocol::network::{
     Cookie, CookieParam, DeleteCookiesParams, GetCookiesParams, SetCookiesParams,
@@ -18,25 +32,54 @@ use chromiumoxide_cdp::cdp::browser_protocol::page::*;
 use chromiumoxide_cdp::cdp::browser_protocol::performance::{GetMetricsParams, Metric};
 use chromiumoxide_cdp::cdp::browser_protocol::target::{SessionId, TargetId};
 use chromiumoxide_cdp::cdp::js_protocol;
-use chromiumoxide_cdp::cdp::js_protocol::debugger::GetScriptSourceParams;
+use chromiumoxide_cdp::cdp::js_protocol::debugger::{GetScriptSourceParams, ResumeParams};
+use chromiumoxide_cdp::cdp::js_protocol::heap_profiler::{
+    self, EventAddHeapSnapshotChunk, TakeHeapSnapshotParams,
+};
 use chromiumoxide_cdp::cdp::js_protocol::runtime::{
-    AddBindingParams, CallArgument, CallFunctionOnParams, EvaluateParams, ExecutionContextId,
-    RemoteObjectType, ScriptId,
+    AddBindingParams, CallArgument, CallFunctionOnParams, EvaluateParams, EventBindingCalled,
+    EventExecutionContextCreated, EventExecutionContextDestroyed, EventExecutionContextsCleared,
+    ExecutionContextId, GetHeapUsageParams, GetPropertiesParams, ReleaseObjectGroupParams,
+    ReleaseObjectParams, RemoteObjectId, RemoteObjectSubtype, RemoteObjectType,
+    RemoveBindingParams, ScriptId,
 };
 use chromiumoxide_cdp::cdp::{browser_protocol, IntoEventKind};
 use chromiumoxide_types::*;
 
 use crate::auth::Credentials;
-use crate::element::Element;
+use crate::cmd::{is_valid_method, RawCommand};
+use crate::element::{Element, ScrollBehavior};
 use crate::error::{CdpError, Result};
+use crate::form::{
+    fill_form_fn, find_target_fn, FieldReport, FieldStatus, FieldTarget, FieldValue, FillReport,
+    FormField, RawFieldResult,
+};
 use crate::handler::commandfuture::CommandFuture;
-use crate::handler::domworld::DOMWorldKind;
+use crate::handler::commandqueue::CommandStats;
+use crate::handler::domworld::{DOMWorldKind, ExecutionContextChange, ExecutionContextInfo};
 use crate::handler::httpfuture::HttpFuture;
 use crate::handler::target::{GetName, GetParent, GetUrl, TargetMessage};
 use crate::handler::PageInner;
-use crate::js::{Evaluation, EvaluationResult};
-use crate::layout::Point;
-use crate::listeners::{EventListenerRequest, EventStream};
+use crate::js::cache::{CachePolicy, EvalCacheStats};
+use crate::js::census::{
+    ListenerCensus, CENSUS_BATCH_SIZE, COLLECT_TARGETS_FN, MAX_CENSUS_TARGETS,
+};
+use crate::js::registry::ObjectStat;
+use crate::js::script::JsScript;
+use crate::js::{
+    call_function_on, validate_custom_element_name, value_or_null, Evaluation, EvaluationResult,
+    JsArray, JsDocument, JsFunction, JsHeaders, JsObject, JsPromise, JsReadableStream, JsSelection,
+    ENCODE_BASE64_FN, JSON_SIZE_FN,
+};
+use crate::layout::{CoordinateSpace, Point, ViewportMetrics};
+use crate::listeners::{EventListenerRequest, EventStream, RawEvent};
+use crate::media::{
+    MediaStreamInfo, ACTIVE_MEDIA_STREAMS_FN, RECORD_MEDIA_STREAMS_SCRIPT, STOP_MEDIA_TRACKS_FN,
+};
+use crate::metadata::{PageMetadata, RawMetadata, METADATA_FN};
+use crate::origin::EvalOriginAllowlist;
+use crate::retry::{RetryPolicy, RetryStats};
+use crate::selector::DeepSelector;
 use crate::{utils, ArcHttpRequest};
 
 #[derive(Debug, Clone)]
@@ -45,6 +88,10 @@ pub struct Page {
 }
 
 impl Page {
+    pub(crate) fn inner(&self) -> &Arc<PageInner> {
+        &self.inner
+    }
+
     /// Removes the `navigator.webdriver` property
     /// changes permissions, pluggins rendering contexts and the `window.chrome`
     /// property to make it harder to detect the scraper as a bot
@@ -187,6 +234,41 @@ impl Page {
         self.command_future(cmd)?.await
     }
 
+    /// Execute a command that has no typed bindings and return its raw
+    /// response.
+    ///
+    /// The command is sent to the page's session, protocol errors are returned
+    /// as [`CdpError::Chrome`] with the original code and message.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let result = page
+    ///         .execute_raw("Runtime.evaluate", serde_json::json!({ "expression": "1 + 1" }))
+    ///         .await?;
+    ///     assert_eq!(result["result"]["value"], 2);
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_raw(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        if !is_valid_method(method) {
+            return Err(CdpError::msg(format!(
+                "Invalid method `{method}`, expected `Domain.method`"
+            )));
+        }
+        Ok(self
+            .execute(RawCommand::new(method.to_string(), params))
+            .await?
+            .result)
+    }
+
     /// Execute a command and return the `Command::Response`
     pub fn command_future<T: Command>(&self, cmd: T) -> Result<CommandFuture<T>> {
         self.inner.command_future(cmd)
@@ -262,18 +344,146 @@ impl Page {
     /// # }
     /// ```
     pub async fn event_listener<T: IntoEventKind>(&self) -> Result<EventStream<T>> {
+        self.inner.event_listener().await
+    }
+
+    /// Returns an event listener for the events of the `method`, for events
+    /// that have no typed bindings.
+    ///
+    /// This only receives events that are not covered by the protocol
+    /// definitions, for those use [`Page::event_listener`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use futures::StreamExt;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let mut events = page.raw_event_listener("Domain.newEvent").await?;
+    ///     while let Some(event) = events.next().await {
+    ///         println!("{}: {}", event.method, event.params);
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn raw_event_listener(&self, method: &str) -> Result<EventStream<RawEvent>> {
+        if !is_valid_method(method) {
+            return Err(CdpError::msg(format!(
+                "Invalid method `{method}`, expected `Domain.event`"
+            )));
+        }
         let (tx, rx) = unbounded();
         self.inner
             .sender()
             .clone()
             .send(TargetMessage::AddEventListener(
-                EventListenerRequest::new::<T>(tx),
+                EventListenerRequest::new_raw(tx, method.to_string().into()),
             ))
             .await?;
 
         Ok(EventStream::new(rx))
     }
 
+    /// Waits for the first event of type `T` that matches the `predicate`.
+    ///
+    /// Fails with [`CdpError::Timeout`] if no matching event arrives within
+    /// `timeout`. The listener is removed once this returns.
+    ///
+    /// Note that the listener is only registered when this is called, so
+    /// events triggered before are missed. Use [`Page::expect_event`] to wait
+    /// for an event caused by an action.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::cdp::browser_protocol::network::EventResponseReceived;
+    /// # use std::time::Duration;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let response = page
+    ///         .wait_for_event::<EventResponseReceived>(
+    ///             |ev| ev.response.url.ends_with("/api/items"),
+    ///             Duration::from_secs(10),
+    ///         )
+    ///         .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_for_event<T: IntoEventKind + Unpin>(
+        &self,
+        predicate: impl Fn(&T) -> bool,
+        timeout: Duration,
+    ) -> Result<Arc<T>> {
+        let events = self.event_listener::<T>().await?;
+        next_matching(events, predicate, timeout).await
+    }
+
+    /// Collects all events of type `T` that arrive within `duration`.
+    pub async fn collect_events<T: IntoEventKind + Unpin>(
+        &self,
+        duration: Duration,
+    ) -> Result<Vec<Arc<T>>> {
+        let mut events = self.event_listener::<T>().await?;
+        let mut collected = Vec::new();
+        let deadline = Instant::now() + duration;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match utils::with_timeout(events.next(), remaining).await {
+                Some(Some(event)) => collected.push(event),
+                Some(None) | None => return Ok(collected),
+            }
+        }
+    }
+
+    /// Runs the `action` and waits for the first event of type `T` that
+    /// matches the `predicate`.
+    ///
+    /// The listener is registered before the `action` runs, so that an
+    /// event that fires while the action is still in progress is not missed.
+    /// Fails with [`CdpError::Timeout`] if no matching event arrives within
+    /// `timeout` after the action completed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::cdp::browser_protocol::page::EventFrameNavigated;
+    /// # use std::time::Duration;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let (_, navigated) = page
+    ///         .expect_event::<EventFrameNavigated, _, _, _>(
+    ///             |ev| ev.frame.parent_id.is_none(),
+    ///             || async { page.find_element("a").await?.click().await.map(|_| ()) },
+    ///             Duration::from_secs(10),
+    ///         )
+    ///         .await?;
+    ///     println!("navigated to {}", navigated.frame.url);
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn expect_event<T, A, F, R>(
+        &self,
+        predicate: impl Fn(&T) -> bool,
+        action: A,
+        timeout: Duration,
+    ) -> Result<(R, Arc<T>)>
+    where
+        T: IntoEventKind + Unpin,
+        A: FnOnce() -> F,
+        F: Future<Output = Result<R>>,
+    {
+        let events = self.event_listener::<T>().await?;
+        let output = action().await?;
+        let event = next_matching(events, predicate, timeout).await?;
+        Ok((output, event))
+    }
+
+    /// Fails with [`CdpError::OriginNotAllowed`] if an
+    /// [evaluation allowlist](Page::set_eval_origin_allowlist) is set and the
+    /// origin of the page is not part of it.
     pub async fn expose_function(
         &self,
         name: impl Into<String>,
@@ -282,6 +492,9 @@ impl Page {
         let name = name.into();
         let expression = utils::evaluation_string(function, &["exposedFun", name.as_str()]);
 
+        self.inner
+            .check_eval_origin(self.inner.execution_context().await?)
+            .await?;
         self.execute(AddBindingParams::new(name)).await?;
         self.execute(AddScriptToEvaluateOnNewDocumentParams::new(
             expression.clone(),
@@ -350,7 +563,13 @@ impl Page {
         Ok(rx.await?)
     }
 
+    /// Answers HTTP authentication challenges with the `credentials`.
+    ///
+    /// Needs the `Fetch` domain of Chrome 74 or newer, fails with
+    /// [`CdpError::UnsupportedByBrowser`] on older versions.
     pub async fn authenticate(&self, credentials: Credentials) -> Result<()> {
+        self.inner
+            .require_browser("Fetch.enable handleAuthRequests", 74)?;
         self.inner
             .sender()
             .clone()
@@ -419,11 +638,20 @@ impl Page {
     }
 
     /// Allows overriding user agent with the given string.
+    ///
+    /// Overriding the `user_agent_metadata` of client hints needs Chrome 84
+    /// or newer, fails with [`CdpError::UnsupportedByBrowser`] on older
+    /// versions.
     pub async fn set_user_agent(
         &self,
         params: impl Into<SetUserAgentOverrideParams>,
     ) -> Result<&Self> {
-        self.execute(params.into()).await?;
+        let params = params.into();
+        if params.user_agent_metadata.is_some() {
+            self.inner
+                .require_browser("Emulation.setUserAgentOverride userAgentMetadata", 84)?;
+        }
+        self.execute(params).await?;
         Ok(self)
     }
 
@@ -446,16 +674,27 @@ impl Page {
     ///
     /// Execute a query selector on the document's node.
     pub async fn find_element(&self, selector: impl Into<String>) -> Result<Element> {
-        let root = self.get_document().await?.node_id;
-        let node_id = self.inner.find_element(selector, root).await?;
-        Element::new(Arc::clone(&self.inner), node_id).await
+        let selector = selector.into();
+        // the document is looked up again if it was replaced in between
+        self.inner
+            .retry(None, false, || async {
+                let root = self.get_document().await?.node_id;
+                let node_id = self.inner.find_element(selector.clone(), root).await?;
+                Element::new(Arc::clone(&self.inner), node_id).await
+            })
+            .await
     }
 
     /// Return all `Element`s in the document that match the given selector
     pub async fn find_elements(&self, selector: impl Into<String>) -> Result<Vec<Element>> {
-        let root = self.get_document().await?.node_id;
-        let node_ids = self.inner.find_elements(selector, root).await?;
-        Element::from_nodes(&self.inner, &node_ids).await
+        let selector = selector.into();
+        self.inner
+            .retry(None, false, || async {
+                let root = self.get_document().await?.node_id;
+                let node_ids = self.inner.find_elements(selector.clone(), root).await?;
+                Element::from_nodes(&self.inner, &node_ids).await
+            })
+            .await
     }
 
     /// Returns the first element in the document which matches the given xpath
@@ -561,6 +800,62 @@ impl Page {
         Ok(self)
     }
 
+    /// Returns the device pixel ratio, the page scale and the scroll offsets
+    /// of the viewports, see [`Page::translate_point`].
+    pub async fn viewport_metrics(&self) -> Result<ViewportMetrics> {
+        Ok(self
+            .evaluate_expression(
+                "(() => {
+                    const viewport = window.visualViewport;
+                    return {
+                        devicePixelRatio: window.devicePixelRatio,
+                        scale: viewport ? viewport.scale : 1,
+                        offset: {
+                            x: viewport ? viewport.offsetLeft : 0,
+                            y: viewport ? viewport.offsetTop : 0,
+                        },
+                        scroll: { x: window.scrollX, y: window.scrollY },
+                    };
+                })()",
+            )
+            .await?
+            .into_value()?)
+    }
+
+    /// Translates the `point` from the coordinate space `from` into the
+    /// layout viewport coordinates that [`Page::click`] and
+    /// [`Page::move_mouse`] expect.
+    ///
+    /// Points taken from `getBoundingClientRect` or the element helpers
+    /// already are in viewport coordinates and need no translation, even
+    /// when the page is zoomed. Translation is needed for points taken from
+    /// elsewhere: `pageX`/`pageY` and full page screenshots are relative to
+    /// the document, and the pixels of a screenshot on a high-DPI screen or
+    /// of a zoomed page are scaled by the `devicePixelRatio` and the page
+    /// scale.
+    ///
+    /// # Example click a position found in a screenshot
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::{Page, ScreenshotParams};
+    /// # use chromiumoxide::layout::{CoordinateSpace, Point};
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let png = page.screenshot(ScreenshotParams::builder().build()).await?;
+    ///     // ... locate the target in the image
+    ///     let pixel = Point::new(640., 360.);
+    ///     let point = page.translate_point(pixel, CoordinateSpace::Screenshot).await?;
+    ///     page.click(point).await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn translate_point(&self, point: Point, from: CoordinateSpace) -> Result<Point> {
+        if from == CoordinateSpace::Viewport {
+            return Ok(point);
+        }
+        Ok(self.viewport_metrics().await?.to_viewport(point, from))
+    }
+
     /// Dispatches a `mousemove` event and moves the mouse to the position of
     /// the `point` where `Point.x` is the horizontal position of the mouse and
     /// `Point.y` the vertical position of the mouse.
@@ -631,103 +926,815 @@ impl Page {
         Ok(pdf)
     }
 
-    /// Brings page to front (activates tab)
-    pub async fn bring_to_front(&self) -> Result<&Self> {
-        self.execute(BringToFrontParams::default()).await?;
-        Ok(self)
-    }
-
-    /// Emulates the given media type or media feature for CSS media queries
-    pub async fn emulate_media_features(&self, features: Vec<MediaFeature>) -> Result<&Self> {
-        self.execute(SetEmulatedMediaParams::builder().features(features).build())
-            .await?;
-        Ok(self)
-    }
-
-    /// Changes the CSS media type of the page
-    // Based on https://pptr.dev/api/puppeteer.page.emulatemediatype
-    pub async fn emulate_media_type(
-        &self,
-        media_type: impl Into<MediaTypeParams>,
-    ) -> Result<&Self> {
-        self.execute(
-            SetEmulatedMediaParams::builder()
-                .media(media_type.into())
-                .build(),
+    /// Waits until the main frame reached the `networkIdle` lifecycle
+    /// event, i.e. there were no network connections for 500ms after it
+    /// loaded. Returns right away if it already did.
+    pub async fn wait_for_network_idle(&self, timeout: Duration) -> Result<&Self> {
+        const NETWORK_IDLE: &str = "networkIdle";
+        let events = self.event_listener::<EventLifecycleEvent>().await?;
+        let lifecycle = self.inner.main_frame_lifecycle_events().await?;
+        if lifecycle.iter().any(|ev| ev == NETWORK_IDLE) {
+            return Ok(self);
+        }
+        let main_frame = self.mainframe().await?;
+        next_matching(
+            events,
+            |ev| ev.name == NETWORK_IDLE && Some(&ev.frame_id) == main_frame.as_ref(),
+            timeout,
         )
         .await?;
         Ok(self)
     }
 
-    /// Overrides default host system timezone
-    pub async fn emulate_timezone(
+    /// Waits until the custom element `tag_name` is defined, i.e.
+    /// `customElements.whenDefined(tag_name)` resolved.
+    ///
+    /// Fails right away if `tag_name` is not a valid custom element name, and
+    /// with [`CdpError::Timeout`] if it is not defined within `timeout`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use std::time::Duration;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.wait_for_custom_element("my-widget", Duration::from_secs(5))
+    ///         .await?
+    ///         .find_element("my-widget")
+    ///         .await?
+    ///         .click()
+    ///         .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_for_custom_element(
         &self,
-        timezoune_id: impl Into<SetTimezoneOverrideParams>,
+        tag_name: impl AsRef<str>,
+        timeout: Duration,
     ) -> Result<&Self> {
-        self.execute(timezoune_id.into()).await?;
-        Ok(self)
+        let tag_name = tag_name.as_ref();
+        validate_custom_element_name(tag_name)?;
+        let defined: bool = self
+            .evaluate_expression(format!(
+                "Promise.race([
+                    customElements.whenDefined({}).then(() => true),
+                    new Promise(resolve => setTimeout(() => resolve(false), {})),
+                ])",
+                serde_json::to_string(tag_name)?,
+                timeout.as_millis()
+            ))
+            .await?
+            .into_value()?;
+        if defined {
+            Ok(self)
+        } else {
+            Err(CdpError::Timeout)
+        }
     }
 
-    /// Overrides default host system locale with the specified one
-    pub async fn emulate_locale(
-        &self,
-        locale: impl Into<SetLocaleOverrideParams>,
-    ) -> Result<&Self> {
-        self.execute(locale.into()).await?;
-        Ok(self)
+    /// Waits until the fonts of the document finished loading, i.e.
+    /// `document.fonts.ready` resolved and `document.fonts.status` is
+    /// `"loaded"`.
+    ///
+    /// Fonts that fail to load, like a font file that responds with 404,
+    /// count as finished. Fails with [`CdpError::Timeout`] if fonts are still
+    /// loading after `timeout`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::{Page, ScreenshotParams};
+    /// # use chromiumoxide::error::Result;
+    /// # use std::time::Duration;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let png = page
+    ///         .wait_for_fonts(Duration::from_secs(5))
+    ///         .await?
+    ///         .screenshot(ScreenshotParams::default())
+    ///         .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_for_fonts(&self, timeout: Duration) -> Result<&Self> {
+        let loaded: bool = self
+            .evaluate_expression(format!(
+                "(async () => {{
+                    const timedOut = new Promise(resolve => setTimeout(() => resolve(false), {}));
+                    // fonts that start loading after `ready` resolved replace it
+                    // with a new promise
+                    while (true) {{
+                        const ready = await Promise.race([
+                            document.fonts.ready.then(() => true, () => true),
+                            timedOut,
+                        ]);
+                        if (!ready) {{
+                            return false;
+                        }}
+                        if (document.fonts.status === 'loaded') {{
+                            return true;
+                        }}
+                        // a settled `ready` resolves again right away, yield to
+                        // tasks like the timer instead of spinning on microtasks
+                        await new Promise(resolve => setTimeout(resolve, 0));
+                    }}
+                }})()",
+                timeout.as_millis()
+            ))
+            .await?
+            .into_value()?;
+        if loaded {
+            Ok(self)
+        } else {
+            Err(CdpError::Timeout)
+        }
     }
 
-    /// Overrides the Geolocation Position or Error. Omitting any of the parameters emulates position unavailable.
-    pub async fn emulate_geolocation(
-        &self,
-        geolocation: impl Into<SetGeolocationOverrideParams>,
-    ) -> Result<&Self> {
-        self.execute(geolocation.into()).await?;
-        Ok(self)
+    /// Forces generic font families on all elements of the current and all
+    /// new documents, so layout does not depend on web fonts. Text of
+    /// `<code>`, `<kbd>`, `<pre>` and `<samp>` uses `monospace`, everything
+    /// else `sans-serif`.
+    ///
+    /// The style is injected by an init script before the first paint of new
+    /// documents. Dropping the returned handle keeps the style in the current
+    /// document but stops injecting it in new ones. Icon fonts render as
+    /// plain characters.
+    pub async fn disable_web_fonts(&self) -> Result<InitScriptHandle> {
+        const SCRIPT: &str = "(() => {
+            const css = `*, *::before, *::after { font-family: sans-serif !important; }
+                code, kbd, pre, samp, tt, code *, kbd *, pre *, samp *, tt * {
+                    font-family: monospace !important;
+                }`;
+            const install = () => {
+                if (document.querySelector('style[data-chromiumoxide-fonts]')) {
+                    return;
+                }
+                const style = document.createElement('style');
+                style.dataset.chromiumoxideFonts = '';
+                style.textContent = css;
+                (document.head || document.documentElement).appendChild(style);
+            };
+            if (document.documentElement) {
+                install();
+            } else {
+                new MutationObserver((_, observer) => {
+                    if (document.documentElement) {
+                        observer.disconnect();
+                        install();
+                    }
+                }).observe(document, { childList: true });
+            }
+        })()";
+        let script = self.add_init_script(SCRIPT).await?;
+        self.evaluate_expression(SCRIPT).await?;
+        Ok(script)
     }
 
-    /// Reloads given page
+    /// Returns the font faces of the document, `document.fonts`, including
+    /// the ones that were not loaded yet or failed to load.
+    pub async fn loaded_fonts(&self) -> Result<Vec<FontFaceInfo>> {
+        Ok(self
+            .evaluate_expression(
+                "Array.from(document.fonts, (font) => ({
+                    family: font.family.replace(/^(['\"])(.*)\\1$/, '$2'),
+                    weight: font.weight,
+                    style: font.style,
+                    status: font.status,
+                }))",
+            )
+            .await?
+            .into_value()?)
+    }
+
+    /// Returns the sorted names of the defined custom elements that are in
+    /// use in the document, including its shadow roots, either as tag name
+    /// or as `is` attribute of a customized built-in element.
+    ///
+    /// The custom element registry can not be enumerated, so definitions
+    /// without any element in the document are not included. Meant for
+    /// debugging.
+    pub async fn defined_custom_elements(&self) -> Result<Vec<String>> {
+        Ok(self
+            .evaluate_expression(
+                "(() => {
+                    const names = new Set();
+                    const visit = root => {
+                        for (const el of root.querySelectorAll('*')) {
+                            for (const name of [el.localName, el.getAttribute('is')]) {
+                                if (name && name.includes('-') && customElements.get(name)) {
+                                    names.add(name);
+                                }
+                            }
+                            if (el.shadowRoot) {
+                                visit(el.shadowRoot);
+                            }
+                        }
+                    };
+                    visit(document);
+                    return [...names].sort();
+                })()",
+            )
+            .await?
+            .into_value()?)
+    }
+
+    /// Collects the metadata of the main frame's document in one round trip:
+    /// title, language, charset, canonical URL, meta tags with their
+    /// OpenGraph and Twitter card fields, icons and JSON-LD blocks.
+    ///
+    /// URLs are resolved against the base URL of the document. A JSON-LD
+    /// block that is not valid JSON is reported as [`JsonLd::Invalid`]
+    /// without failing the call.
+    ///
+    /// [`JsonLd::Invalid`]: crate::metadata::JsonLd::Invalid
+    ///
+    /// # Example
     ///
-    /// To reload ignoring cache run:
     /// ```no_run
     /// # use chromiumoxide::page::Page;
     /// # use chromiumoxide::error::Result;
-    /// # use chromiumoxide_cdp::cdp::browser_protocol::page::ReloadParams;
     /// # async fn demo(page: Page) -> Result<()> {
-    ///     page.execute(ReloadParams::builder().ignore_cache(true).build()).await?;
-    ///     page.wait_for_navigation().await?;
+    ///     let metadata = page.goto("https://example.com").await?.metadata().await?;
+    ///     println!("{} {:?}", metadata.title, metadata.open_graph.image);
     ///     # Ok(())
     /// # }
     /// ```
-    pub async fn reload(&self) -> Result<&Self> {
-        self.execute(ReloadParams::default()).await?;
-        self.wait_for_navigation().await
+    pub async fn metadata(&self) -> Result<PageMetadata> {
+        let raw: RawMetadata = self
+            .evaluate_function(CallFunctionOnParams::new(METADATA_FN))
+            .await?
+            .into_value()?;
+        Ok(raw.into())
     }
 
-    /// Enables log domain. Enabled by default.
+    /// Fills the `fields` of a form and reports per field whether its control
+    /// was found and filled, and the validation message it reports
+    /// afterwards.
     ///
-    /// Sends the entries collected so far to the client by means of the
-    /// entryAdded notification.
+    /// Text, checkboxes, radio buttons and selects are filled in a single
+    /// call into the page, through the native setters of the controls so
+    /// that frameworks like React notice the change. File inputs are set
+    /// beforehand with `DOM.setFileInputFiles`, one call per field. Fields
+    /// in open shadow roots are found through [`FieldTarget::Deep`].
     ///
-    /// See https://chromedevtools.github.io/devtools-protocol/tot/Log#method-enable
-    pub async fn enable_log(&self) -> Result<&Self> {
-        self.execute(browser_protocol::log::EnableParams::default())
-            .await?;
-        Ok(self)
-    }
-
-    /// Disables log domain
+    /// A missing control or a value it can't take is reported in the
+    /// [`FillReport`] rather than failing the call.
     ///
-    /// Prevents further log entries from being reported to the client
+    /// # Example
     ///
-    /// See https://chromedevtools.github.io/devtools-protocol/tot/Log#method-disable
-    pub async fn disable_log(&self) -> Result<&Self> {
-        self.execute(browser_protocol::log::DisableParams::default())
-            .await?;
-        Ok(self)
-    }
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// use chromiumoxide::form::FormField;
+    ///
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let report = page
+    ///         .fill_form(vec![
+    ///             FormField::text("#email", "user@example.com"),
+    ///             FormField::check("#terms", true),
+    ///             FormField::select_value("#plan", "pro"),
+    ///             FormField::file("#avatar", ["avatar.png"]),
+    ///         ])
+    ///         .await?;
+    ///     for field in report.invalid() {
+    ///         println!("{}: {:?}", field.target, field.validation_message);
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`FieldTarget::Deep`]: crate::form::FieldTarget::Deep
+    pub async fn fill_form(&self, fields: Vec<FormField>) -> Result<FillReport> {
+        // files first, so that their validation is gathered with the others
+        let mut file_status = Vec::with_capacity(fields.len());
+        for field in &fields {
+            file_status.push(match &field.value {
+                FieldValue::File(paths) => Some(self.set_field_files(field, paths).await?),
+                _ => None,
+            });
+        }
 
-    /// Enables runtime domain. Activated by default.
+        let mut elements = Vec::new();
+        let specs: Vec<_> = fields
+            .iter()
+            .map(|field| {
+                let element = match &field.target {
+                    FieldTarget::Element(id) => {
+                        elements.push(CallArgument::builder().object_id(id.clone()).build());
+                        Some(elements.len() - 1)
+                    }
+                    _ => None,
+                };
+                field.spec(element)
+            })
+            .collect();
+        let mut params = CallFunctionOnParams::new(fill_form_fn());
+        params.arguments = Some(
+            std::iter::once(CallArgument::builder().value(specs).build())
+                .chain(elements)
+                .collect(),
+        );
+        let results: Vec<RawFieldResult> = self.evaluate_function(params).await?.into_value()?;
+
+        let fields = fields
+            .into_iter()
+            .zip(file_status)
+            .zip(results)
+            .map(|((field, file_status), result)| FieldReport {
+                target: field.target,
+                status: file_status.unwrap_or_else(|| result.status()),
+                validation_message: result.validation_message,
+            })
+            .collect();
+        Ok(FillReport { fields })
+    }
+
+    /// Sets the files of the input of the `field` with
+    /// `DOM.setFileInputFiles`.
+    async fn set_field_files(&self, field: &FormField, paths: &[PathBuf]) -> Result<FieldStatus> {
+        let (object_id, resolved) = match &field.target {
+            FieldTarget::Element(id) => (id.clone(), false),
+            _ => {
+                let mut params = CallFunctionOnParams::new(find_target_fn());
+                params.arguments = Some(vec![CallArgument::builder()
+                    .value(field.spec(None))
+                    .build()]);
+                params.return_by_value = Some(false);
+                let found = self.evaluate_function(params).await?;
+                match found.object().object_id.clone() {
+                    Some(id) => (id, true),
+                    None => return Ok(FieldStatus::NotFound),
+                }
+            }
+        };
+        let files: Vec<_> = paths
+            .iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect();
+        let set = self
+            .execute(
+                SetFileInputFilesParams::builder()
+                    .files(files)
+                    .object_id(object_id.clone())
+                    .build()
+                    .unwrap(),
+            )
+            .await;
+        if resolved {
+            self.execute(ReleaseObjectParams::new(object_id)).await?;
+        }
+        match set {
+            Ok(_) => Ok(FieldStatus::Filled),
+            Err(CdpError::Chrome(err)) => Ok(FieldStatus::Failed(err.message)),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Counts the event listeners of the `roots` and their descendants, or of
+    /// the window, the document and all its elements if there are no
+    /// `roots`, grouped by event type and by where their handlers are
+    /// defined.
+    ///
+    /// Comparing censuses taken before and after repeating an interaction
+    /// with [`ListenerCensus::diff`] reveals listeners that are added but
+    /// never removed. Elements in open shadow roots are included. The
+    /// listeners of at most [`MAX_CENSUS_TARGETS`] event targets are
+    /// requested, in batches, and [`ListenerCensus::truncated`] tells whether
+    /// there were more.
+    ///
+    /// [`MAX_CENSUS_TARGETS`]: crate::js::census::MAX_CENSUS_TARGETS
+    pub async fn event_listener_census(
+        &self,
+        roots: Option<Vec<RemoteObjectId>>,
+    ) -> Result<ListenerCensus> {
+        static CENSUSES: AtomicUsize = AtomicUsize::new(0);
+        let group = format!(
+            "chromiumoxide-listener-census-{}",
+            CENSUSES.fetch_add(1, Ordering::Relaxed)
+        );
+        let census = self
+            .take_listener_census(roots.unwrap_or_default(), &group)
+            .await;
+        self.execute(ReleaseObjectGroupParams::new(group)).await?;
+        census
+    }
+
+    async fn take_listener_census(
+        &self,
+        roots: Vec<RemoteObjectId>,
+        group: &str,
+    ) -> Result<ListenerCensus> {
+        let mut params = CallFunctionOnParams::new(COLLECT_TARGETS_FN);
+        // with roots, the targets are collected in the context of the roots
+        params.object_id = roots.first().cloned();
+        params.arguments = Some(
+            std::iter::once(CallArgument::builder().value(MAX_CENSUS_TARGETS).build())
+                .chain(
+                    roots
+                        .into_iter()
+                        .map(|root| CallArgument::builder().object_id(root).build()),
+                )
+                .collect(),
+        );
+        params.return_by_value = Some(false);
+        params.object_group = Some(group.to_string());
+        let targets = self
+            .evaluate_function(params)
+            .await?
+            .object()
+            .object_id
+            .clone()
+            .ok_or_else(|| CdpError::msg("No object Id found for the event targets"))?;
+
+        let mut params = GetPropertiesParams::new(targets);
+        params.own_properties = Some(true);
+        let mut targets: Vec<(usize, RemoteObjectId)> = self
+            .execute(params)
+            .await?
+            .result
+            .result
+            .into_iter()
+            .filter_map(|property| Some((property.name.parse().ok()?, property.value?.object_id?)))
+            .collect();
+        targets.sort_by_key(|(index, _)| *index);
+
+        let mut census = ListenerCensus {
+            truncated: targets.len() > MAX_CENSUS_TARGETS,
+            ..Default::default()
+        };
+        targets.truncate(MAX_CENSUS_TARGETS);
+        for batch in targets.chunks(CENSUS_BATCH_SIZE) {
+            let listeners = futures::future::try_join_all(batch.iter().map(|(_, target)| {
+                self.execute(
+                    GetEventListenersParams::builder()
+                        .object_id(target.clone())
+                        .depth(0)
+                        .build()
+                        .unwrap(),
+                )
+            }))
+            .await?;
+            for listeners in listeners {
+                census.record(&listeners.result.listeners);
+            }
+        }
+        Ok(census)
+    }
+
+    /// Captures the page, including its resources and frames, as a single
+    /// [MHTML](https://en.wikipedia.org/wiki/MHTML) archive.
+    ///
+    /// With `wait_for_network_idle`, waits up to that long for the network to
+    /// become idle first, so that lazy-loaded resources are included, see
+    /// [`Page::wait_for_network_idle`].
+    pub async fn snapshot_mhtml(&self, wait_for_network_idle: Option<Duration>) -> Result<String> {
+        if let Some(timeout) = wait_for_network_idle {
+            self.wait_for_network_idle(timeout).await?;
+        }
+        let params = CaptureSnapshotParams::builder()
+            .format(CaptureSnapshotFormat::Mhtml)
+            .build();
+        Ok(self.execute(params).await?.result.data)
+    }
+
+    /// Captures the page as MHTML archive, see [`Page::snapshot_mhtml`], and
+    /// saves it to the `output` path.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use std::time::Duration;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.goto("https://example.com").await?;
+    ///     page.save_mhtml("example.mhtml", Some(Duration::from_secs(10))).await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn save_mhtml(
+        &self,
+        output: impl AsRef<Path>,
+        wait_for_network_idle: Option<Duration>,
+    ) -> Result<&Self> {
+        let mhtml = self.snapshot_mhtml(wait_for_network_idle).await?;
+        utils::write(output.as_ref(), mhtml.as_bytes()).await?;
+        Ok(self)
+    }
+
+    /// Brings page to front (activates tab)
+    pub async fn bring_to_front(&self) -> Result<&Self> {
+        self.execute(BringToFrontParams::default()).await?;
+        Ok(self)
+    }
+
+    /// Returns the id and the bounds of the browser window of this page.
+    ///
+    /// Fails with [`CdpError::Unsupported`] in headless mode without a
+    /// window.
+    async fn window_for_target(&self) -> Result<(WindowId, Bounds)> {
+        let resp = self
+            .inner
+            .execute_browser(
+                GetWindowForTargetParams::builder()
+                    .target_id(self.target_id().clone())
+                    .build(),
+            )
+            .await;
+        match resp {
+            Ok(resp) => Ok((resp.result.window_id, resp.result.bounds)),
+            Err(err) => {
+                let user_agent = self.user_agent().await.unwrap_or_default();
+                if user_agent.contains("HeadlessChrome") {
+                    Err(CdpError::Unsupported(format!(
+                        "headless browsers have no window: {err}"
+                    )))
+                } else {
+                    Err(err)
+                }
+            }
+        }
+    }
+
+    /// Returns the position, size and state of the browser window of this
+    /// page, see [`Page::set_window_bounds`].
+    ///
+    /// Fails with [`CdpError::Unsupported`] in headless mode without a
+    /// window.
+    pub async fn window_bounds(&self) -> Result<Bounds> {
+        Ok(self.window_for_target().await?.1)
+    }
+
+    /// Moves, resizes or changes the state of the browser window of this
+    /// page, fields that are `None` are left unchanged.
+    ///
+    /// The browser only changes the position or size of a window in the
+    /// `normal` state and can not change a window from one of the
+    /// `minimized`, `maximized` or `fullscreen` states into another one
+    /// directly. This takes care of restoring the window to the `normal`
+    /// state first and applies position and size before a requested
+    /// non-normal state.
+    ///
+    /// Fails with [`CdpError::Unsupported`] in headless mode without a
+    /// window.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::cdp::browser_protocol::browser::Bounds;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let bounds = Bounds::builder().left(0).top(0).width(1280).height(720).build();
+    ///     page.set_window_bounds(bounds).await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_window_bounds(&self, bounds: Bounds) -> Result<&Self> {
+        let (window_id, current) = self.window_for_target().await?;
+        let current_state = current.window_state.unwrap_or(WindowState::Normal);
+        let geometry = Bounds {
+            left: bounds.left,
+            top: bounds.top,
+            width: bounds.width,
+            height: bounds.height,
+            window_state: None,
+        };
+        let has_geometry = geometry != Bounds::default();
+        let state = bounds.window_state;
+
+        let needs_normal = current_state != WindowState::Normal
+            && (has_geometry || state.as_ref().is_some_and(|state| *state != current_state));
+        if needs_normal {
+            let normal = Bounds::builder().window_state(WindowState::Normal).build();
+            self.inner
+                .execute_browser(SetWindowBoundsParams::new(window_id, normal))
+                .await?;
+        }
+        if has_geometry {
+            self.inner
+                .execute_browser(SetWindowBoundsParams::new(window_id, geometry))
+                .await?;
+        }
+        match state {
+            Some(state) if state != WindowState::Normal || !needs_normal => {
+                let state = Bounds::builder().window_state(state).build();
+                self.inner
+                    .execute_browser(SetWindowBoundsParams::new(window_id, state))
+                    .await?;
+            }
+            _ => {}
+        }
+        Ok(self)
+    }
+
+    /// Zooms the page by the `factor`, like pinch-zooming on a touch screen.
+    ///
+    /// This sets the page scale factor via `Emulation.setPageScaleFactor`,
+    /// which magnifies the visual viewport without changing the layout. It
+    /// is independent from the viewport emulated with
+    /// `Emulation.setDeviceMetricsOverride`, whose `device_scale_factor`
+    /// changes the pixel density instead, which is what high resolution
+    /// screenshots need.
+    ///
+    /// While zoomed, points passed to the mouse helpers, like
+    /// [`Page::click`] and [`Element::click`], are still expected in
+    /// `getBoundingClientRect` (layout viewport) coordinates and converted
+    /// accordingly. Factors that are not positive are rejected.
+    pub async fn set_zoom(&self, factor: f64) -> Result<&Self> {
+        if !(factor.is_finite() && factor > 0.) {
+            return Err(CdpError::msg(format!(
+                "Zoom factor must be positive, got {factor}"
+            )));
+        }
+        self.inner.set_page_scale_factor(factor).await?;
+        Ok(self)
+    }
+
+    /// Returns the current page scale factor, see [`Page::set_zoom`].
+    pub async fn zoom(&self) -> Result<f64> {
+        Ok(self.layout_metrics().await?.css_visual_viewport.scale)
+    }
+
+    /// Resets the page scale factor to `1.0`.
+    pub async fn reset_zoom(&self) -> Result<&Self> {
+        self.inner.set_page_scale_factor(1.).await?;
+        Ok(self)
+    }
+
+    /// Emulates the given media type or media feature for CSS media queries
+    pub async fn emulate_media_features(&self, features: Vec<MediaFeature>) -> Result<&Self> {
+        self.execute(SetEmulatedMediaParams::builder().features(features).build())
+            .await?;
+        Ok(self)
+    }
+
+    /// Changes the CSS media type of the page
+    // Based on https://pptr.dev/api/puppeteer.page.emulatemediatype
+    pub async fn emulate_media_type(
+        &self,
+        media_type: impl Into<MediaTypeParams>,
+    ) -> Result<&Self> {
+        self.execute(
+            SetEmulatedMediaParams::builder()
+                .media(media_type.into())
+                .build(),
+        )
+        .await?;
+        Ok(self)
+    }
+
+    /// Overrides default host system timezone
+    ///
+    /// Needs Chrome 79 or newer, fails with
+    /// [`CdpError::UnsupportedByBrowser`] on older versions.
+    pub async fn emulate_timezone(
+        &self,
+        timezoune_id: impl Into<SetTimezoneOverrideParams>,
+    ) -> Result<&Self> {
+        let params = timezoune_id.into();
+        self.inner
+            .require_browser(params.identifier().as_ref(), 79)?;
+        self.execute(params).await?;
+        Ok(self)
+    }
+
+    /// Overrides default host system locale with the specified one
+    pub async fn emulate_locale(
+        &self,
+        locale: impl Into<SetLocaleOverrideParams>,
+    ) -> Result<&Self> {
+        self.execute(locale.into()).await?;
+        Ok(self)
+    }
+
+    /// Overrides the Geolocation Position or Error. Omitting any of the parameters emulates position unavailable.
+    pub async fn emulate_geolocation(
+        &self,
+        geolocation: impl Into<SetGeolocationOverrideParams>,
+    ) -> Result<&Self> {
+        self.execute(geolocation.into()).await?;
+        Ok(self)
+    }
+
+    /// Sets the virtual time `policy` of the page, see
+    /// [`VirtualTimePolicy`].
+    ///
+    /// With a `budget` the policy is applied until the budget of virtual time
+    /// elapsed, after which the virtual time is paused and an
+    /// `Emulation.virtualTimeBudgetExpired` event is fired.
+    ///
+    /// Returns the absolute time base of the virtual time in milliseconds.
+    ///
+    /// # Note
+    ///
+    /// The virtual time is reset on navigation, the policy must be set again
+    /// for the new document.
+    pub async fn set_virtual_time_policy(
+        &self,
+        policy: VirtualTimePolicy,
+        budget: Option<Duration>,
+    ) -> Result<f64> {
+        let mut params = SetVirtualTimePolicyParams::new(policy);
+        params.budget = budget.map(|budget| budget.as_secs_f64() * 1000.);
+        Ok(self.execute(params).await?.virtual_time_ticks_base)
+    }
+
+    /// Lets the virtual time advance by the `budget` and waits until it
+    /// elapsed, afterwards the virtual time is paused.
+    ///
+    /// Timers of the page fire as soon as their virtual deadline is reached,
+    /// so this completes much faster than real time.
+    ///
+    /// # Note
+    ///
+    /// The virtual time is reset on navigation.
+    ///
+    /// # Example assert that a timer ran without waiting for it
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use std::time::Duration;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.evaluate("setTimeout(() => window.done = true, 5000)").await?;
+    ///     page.advance_virtual_time(Duration::from_secs(5)).await?;
+    ///     let done: bool = page.evaluate("window.done === true").await?.into_value()?;
+    ///     assert!(done);
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn advance_virtual_time(&self, budget: Duration) -> Result<&Self> {
+        let mut expired = self
+            .event_listener::<EventVirtualTimeBudgetExpired>()
+            .await?;
+        self.set_virtual_time_policy(VirtualTimePolicy::Advance, Some(budget))
+            .await?;
+        expired
+            .next()
+            .await
+            .ok_or_else(|| CdpError::msg("Virtual time budget expiration was not received"))?;
+        Ok(self)
+    }
+
+    /// Sets the playback rate of CSS and web animations of the page, `1.0` is
+    /// the normal speed.
+    pub async fn set_animation_playback_rate(&self, rate: f64) -> Result<&Self> {
+        self.execute(browser_protocol::animation::EnableParams::default())
+            .await?;
+        self.execute(SetPlaybackRateParams::new(rate)).await?;
+        Ok(self)
+    }
+
+    /// Pauses all CSS and web animations of the page.
+    ///
+    /// See [`Page::set_animation_playback_rate`] to resume them.
+    pub async fn pause_animations(&self) -> Result<&Self> {
+        self.set_animation_playback_rate(0.).await
+    }
+
+    /// Reloads given page
+    ///
+    /// To reload ignoring cache run:
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide_cdp::cdp::browser_protocol::page::ReloadParams;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.execute(ReloadParams::builder().ignore_cache(true).build()).await?;
+    ///     page.wait_for_navigation().await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn reload(&self) -> Result<&Self> {
+        self.execute(ReloadParams::default()).await?;
+        self.wait_for_navigation().await
+    }
+
+    /// Enables log domain. Enabled by default.
+    ///
+    /// Sends the entries collected so far to the client by means of the
+    /// entryAdded notification.
+    ///
+    /// See https://chromedevtools.github.io/devtools-protocol/tot/Log#method-enable
+    pub async fn enable_log(&self) -> Result<&Self> {
+        self.execute(browser_protocol::log::EnableParams::default())
+            .await?;
+        Ok(self)
+    }
+
+    /// Disables log domain
+    ///
+    /// Prevents further log entries from being reported to the client
+    ///
+    /// See https://chromedevtools.github.io/devtools-protocol/tot/Log#method-disable
+    pub async fn disable_log(&self) -> Result<&Self> {
+        self.execute(browser_protocol::log::DisableParams::default())
+            .await?;
+        Ok(self)
+    }
+
+    /// Enables runtime domain. Activated by default.
     pub async fn enable_runtime(&self) -> Result<&Self> {
         self.execute(js_protocol::runtime::EnableParams::default())
             .await?;
@@ -745,6 +1752,7 @@ impl Page {
     pub async fn enable_debugger(&self) -> Result<&Self> {
         self.execute(js_protocol::debugger::EnableParams::default())
             .await?;
+        self.inner.set_debugger_enabled(true);
         Ok(self)
     }
 
@@ -752,6 +1760,7 @@ impl Page {
     pub async fn disable_debugger(&self) -> Result<&Self> {
         self.execute(js_protocol::debugger::DisableParams::default())
             .await?;
+        self.inner.set_debugger_enabled(false);
         Ok(self)
     }
 
@@ -789,6 +1798,70 @@ impl Page {
         Ok(self)
     }
 
+    /// Makes sure this page is the foreground tab before running visibility
+    /// sensitive code.
+    ///
+    /// Some scripts only make progress while the tab is visible, e.g. code
+    /// gated on `document.hidden`, `requestAnimationFrame` or an
+    /// `IntersectionObserver`. This activates the target, brings it to front
+    /// and enables focus emulation so `document.hasFocus()` holds even when
+    /// headless or when another window has the focus. It then waits until
+    /// `document.visibilityState` reports `visible`, giving up after
+    /// `timeout` with [`CdpError::Timeout`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use std::time::Duration;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.ensure_foreground(Duration::from_secs(5)).await?;
+    ///     let hidden: bool = page.evaluate("document.hidden").await?.into_value()?;
+    ///     assert!(!hidden);
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn ensure_foreground(&self, timeout: Duration) -> Result<&Self> {
+        self.activate().await?;
+        self.bring_to_front().await?;
+        self.execute(SetFocusEmulationEnabledParams::new(true))
+            .await?;
+
+        let visible: bool = self
+            .evaluate_function(format!(
+                "() => new Promise(resolve => {{
+                    if (document.visibilityState === 'visible') return resolve(true);
+                    const timer = setTimeout(() => resolve(false), {});
+                    document.addEventListener('visibilitychange', function listener() {{
+                        if (document.visibilityState !== 'visible') return;
+                        document.removeEventListener('visibilitychange', listener);
+                        clearTimeout(timer);
+                        resolve(true);
+                    }});
+                }})",
+                timeout.as_millis()
+            ))
+            .await?
+            .into_value()?;
+        if visible {
+            Ok(self)
+        } else {
+            Err(CdpError::Timeout)
+        }
+    }
+
+    /// Brings the page to the foreground with [`Page::ensure_foreground`],
+    /// then evaluates `evaluate` like [`Page::evaluate`].
+    pub async fn evaluate_in_foreground(
+        &self,
+        evaluate: impl Into<Evaluation>,
+        timeout: Duration,
+    ) -> Result<EvaluationResult> {
+        self.ensure_foreground(timeout).await?;
+        self.evaluate(evaluate).await
+    }
+
     /// Returns all cookies that match the tab's current URL.
     pub async fn get_cookies(&self) -> Result<Vec<Cookie>> {
         Ok(self
@@ -942,13 +2015,101 @@ impl Page {
         self.inner.layout_metrics().await
     }
 
-    /// This evaluates strictly as expression.
+    /// Takes a heap snapshot of the page's JavaScript heap and streams it into
+    /// the `writer`. Returns the number of bytes written.
     ///
-    /// Same as `Page::evaluate` but no fallback or any attempts to detect
-    /// whether the expression is actually a function. However you can
-    /// submit a function evaluation string:
+    /// The snapshot is transferred by chromium in `HeapProfiler.addHeapSnapshotChunk`
+    /// events, every chunk is written as soon as it arrives so that the
+    /// snapshot (which can be hundreds of MB) is never buffered completely.
     ///
-    /// # Example Evaluate function call as expression
+    /// # Note
+    ///
+    /// This enables the `HeapProfiler` domain, which remains enabled afterwards.
+    ///
+    /// # Example save a heap snapshot to a file
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let file = async_std::fs::File::create("page.heapsnapshot").await?;
+    ///     let size = page.take_heap_snapshot(file).await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn take_heap_snapshot<W: AsyncWrite + Unpin>(&self, mut writer: W) -> Result<u64> {
+        self.execute(heap_profiler::EnableParams::default()).await?;
+        let mut chunks = self.event_listener::<EventAddHeapSnapshotChunk>().await?;
+
+        let mut snapshot = Box::pin(
+            self.execute(
+                TakeHeapSnapshotParams::builder()
+                    .report_progress(false)
+                    .build(),
+            ),
+        )
+        .fuse();
+
+        let mut written = 0u64;
+        loop {
+            futures::select! {
+                res = snapshot => {
+                    res?;
+                    break;
+                }
+                chunk = chunks.next().fuse() => {
+                    let chunk = chunk.ok_or_else(|| CdpError::msg("Heap snapshot stream closed"))?;
+                    writer.write_all(chunk.chunk.as_bytes()).await?;
+                    written += chunk.chunk.len() as u64;
+                }
+            }
+        }
+
+        // All chunks are emitted before the response to `takeHeapSnapshot`, but the last
+        // ones may still be queued in the handler. Another round trip guarantees that they
+        // were forwarded to the event stream.
+        self.execute(GetHeapUsageParams::default()).await?;
+        while let Some(Some(chunk)) = chunks.next().now_or_never() {
+            writer.write_all(chunk.chunk.as_bytes()).await?;
+            written += chunk.chunk.len() as u64;
+        }
+        writer.flush().await?;
+
+        Ok(written)
+    }
+
+    /// Forces a garbage collection of the page's JavaScript heap.
+    pub async fn collect_garbage(&self) -> Result<&Self> {
+        self.execute(heap_profiler::CollectGarbageParams::default())
+            .await?;
+        Ok(self)
+    }
+
+    /// Returns the JavaScript heap usage of the page.
+    ///
+    /// See [`HeapUsage`]
+    pub async fn js_heap_usage(&self) -> Result<HeapUsage> {
+        let usage = self.execute(GetHeapUsageParams::default()).await?.result;
+        // `performance.memory` is non-standard and may be unavailable
+        let limit = self
+            .evaluate_expression("performance.memory && performance.memory.jsHeapSizeLimit")
+            .await?
+            .into_value()
+            .ok();
+        Ok(HeapUsage {
+            used: usage.used_size,
+            total: usage.total_size,
+            limit,
+        })
+    }
+
+    /// This evaluates strictly as expression.
+    ///
+    /// Same as `Page::evaluate` but no fallback or any attempts to detect
+    /// whether the expression is actually a function. However you can
+    /// submit a function evaluation string:
+    ///
+    /// # Example Evaluate function call as expression
     ///
     /// This will take the arguments `(1,2)` and will call the function
     ///
@@ -971,6 +2132,46 @@ impl Page {
         self.inner.evaluate_expression(evaluate).await
     }
 
+    /// Evaluates the expression and returns a live handle to whatever it
+    /// yields, like Puppeteer's `evaluateHandle`.
+    ///
+    /// In contrast to [`Page::evaluate_expression`] the result is always
+    /// returned by reference, regardless of `return_by_value`. Objects, arrays,
+    /// functions and DOM nodes carry an `object_id` that can be passed back
+    /// into further calls, primitives are returned as values. Convert the
+    /// result into a typed handle with [`EvaluationResult::into_js_object`],
+    /// [`EvaluationResult::into_element`] and the like, which is what
+    /// [`Page::js_object`], [`Page::js_array`] and [`Page::js_function`] do.
+    ///
+    /// # Example Pass the result into another call
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide_cdp::cdp::js_protocol::runtime::{CallArgument, CallFunctionOnParams};
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let handle = page.evaluate_handle("document.body").await?;
+    ///     let object_id = handle.object().object_id.clone().unwrap();
+    ///     let call = CallFunctionOnParams::builder()
+    ///         .function_declaration("function(body) { return body.childElementCount; }")
+    ///         .object_id(object_id.clone())
+    ///         .argument(CallArgument::builder().object_id(object_id).build())
+    ///         .return_by_value(true)
+    ///         .build()
+    ///         .unwrap();
+    ///     let count: usize = page.evaluate_function(call).await?.into_value()?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn evaluate_handle(
+        &self,
+        expression: impl Into<EvaluateParams>,
+    ) -> Result<EvaluationResult> {
+        let mut evaluate = expression.into();
+        evaluate.return_by_value = Some(false);
+        self.inner.evaluate_expression(evaluate).await
+    }
+
     /// Evaluates an expression or function in the page's context and returns
     /// the result.
     ///
@@ -1032,7 +2233,49 @@ impl Page {
     /// # }
     /// ```
     pub async fn evaluate(&self, evaluate: impl Into<Evaluation>) -> Result<EvaluationResult> {
-        match evaluate.into() {
+        let evaluate = evaluate.into();
+        self.inner
+            .retry_evaluation(None, &evaluate, || self.evaluate_once(evaluate.clone()))
+            .await
+    }
+
+    /// Same as [`Page::evaluate`] but retries transient failures according
+    /// to the `policy` instead of the page's
+    /// [retry policy](Page::set_retry_policy).
+    ///
+    /// Evaluations with an execution context id or a `this` object are
+    /// attempted once, their context does not come back.
+    ///
+    /// # Example read a value while the page may still navigate
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// use chromiumoxide::retry::RetryPolicy;
+    ///
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let title: String = page
+    ///         .evaluate_with_retry_policy("document.title", &RetryPolicy::new(3))
+    ///         .await?
+    ///         .into_value()?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn evaluate_with_retry_policy(
+        &self,
+        evaluate: impl Into<Evaluation>,
+        policy: &RetryPolicy,
+    ) -> Result<EvaluationResult> {
+        let evaluate = evaluate.into();
+        self.inner
+            .retry_evaluation(Some(policy), &evaluate, || {
+                self.evaluate_once(evaluate.clone())
+            })
+            .await
+    }
+
+    async fn evaluate_once(&self, evaluate: Evaluation) -> Result<EvaluationResult> {
+        match evaluate {
             Evaluation::Expression(mut expr) => {
                 if expr.context_id.is_none() {
                     expr.context_id = self.execution_context().await?;
@@ -1044,20 +2287,32 @@ impl Page {
                         None
                     }
                 });
-                let res = self.evaluate_expression(expr).await?;
+                let res = self.inner.evaluate_expression_once(expr).await?;
 
                 if res.object().r#type == RemoteObjectType::Function {
                     // expression was actually a function
                     if let Some(fallback) = fallback {
-                        return self.evaluate_function(fallback).await;
+                        return self.inner.evaluate_function_once(fallback.into()).await;
                     }
                 }
                 Ok(res)
             }
-            Evaluation::Function(fun) => Ok(self.evaluate_function(fun).await?),
+            Evaluation::Function(fun) => self.inner.evaluate_function_once(fun).await,
         }
     }
 
+    /// Evaluates the `script` like [`Page::evaluate`], reported under its
+    /// source URL.
+    ///
+    /// Exceptions are returned as [`CdpError::JavascriptException`] with
+    /// their locations in the lines of the script, even if the script is
+    /// wrapped into a [function](JsScript::function_body).
+    pub async fn evaluate_script(&self, script: &JsScript) -> Result<EvaluationResult> {
+        self.evaluate(script.evaluation()?)
+            .await
+            .map_err(|err| script.map_error(err))
+    }
+
     /// Eexecutes a function withinthe page's context and returns the result.
     ///
     /// # Example Evaluate a promise
@@ -1117,42 +2372,1368 @@ impl Page {
         self.inner.evaluate_function(evaluate).await
     }
 
-    /// Returns the default execution context identifier of this page that
-    /// represents the context for JavaScript execution.
-    pub async fn execution_context(&self) -> Result<Option<ExecutionContextId>> {
-        self.inner.execution_context().await
+    /// Evaluates the `expression` in the main world of every frame and
+    /// returns the values with the id of their frame, in the order of the
+    /// frame tree, e.g. to collect content spread across iframes in one call.
+    ///
+    /// Frames without an execution context, like frames that did not start
+    /// loading, and frames whose origin is not in the
+    /// [evaluation allowlist](Page::set_eval_origin_allowlist) are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let titles: Vec<(_, String)> = page.evaluate_all_frames("document.title").await?;
+    ///     for (frame_id, title) in titles {
+    ///         println!("{frame_id:?}: {title}");
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn evaluate_all_frames<T: DeserializeOwned>(
+        &self,
+        expression: impl Into<String>,
+    ) -> Result<Vec<(FrameId, T)>> {
+        self.inner.eval_all_frames(expression).await
+    }
+
+    /// Evaluates the expression with `this` bound to the object, like a
+    /// method of the object, e.g. `this.value.trim()` on the handle of an
+    /// input element.
+    ///
+    /// The options of the `EvaluateParams`, like `return_by_value` or
+    /// `await_promise`, apply as with [`Page::evaluate_expression`], except
+    /// for the context: the expression runs in the context of the object.
+    /// The expression is wrapped into a function, so it can't consist of
+    /// several statements.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let input = page.find_element("input").await?;
+    ///     let value: String = page
+    ///         .evaluate_with_this(&input.remote_object_id, "this.value.trim()")
+    ///         .await?
+    ///         .into_value()?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn evaluate_with_this(
+        &self,
+        this: &RemoteObjectId,
+        expression: impl Into<EvaluateParams>,
+    ) -> Result<EvaluationResult> {
+        let evaluate = expression.into();
+        let mut call = CallFunctionOnParams::new(format!(
+            "function() {{ return (\n{}\n); }}",
+            evaluate.expression
+        ));
+        call.object_id = Some(this.clone());
+        call.silent = evaluate.silent;
+        call.return_by_value = evaluate.return_by_value;
+        call.generate_preview = evaluate.generate_preview;
+        call.user_gesture = evaluate.user_gesture;
+        call.await_promise = evaluate.await_promise;
+        self.inner.evaluate_function(call).await
+    }
+
+    /// Same as [`Page::evaluate`], but fails with
+    /// [`CdpError::ValueTooLarge`] instead of returning a value whose JSON
+    /// encoding is larger than `max_bytes`.
+    ///
+    /// The value is kept in the page until its size is measured there, so an
+    /// accidentally huge value, like the `outerHTML` of a giant page, is never
+    /// transferred. Expressions are wrapped into a function, so they can't
+    /// consist of several statements.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::{CdpError, Result};
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     match page.evaluate_limited("document.documentElement.outerHTML", 1 << 20).await {
+    ///         Ok(html) => println!("{}", html.into_value::<String>()?),
+    ///         Err(CdpError::ValueTooLarge { size, .. }) => println!("skipped {size} bytes"),
+    ///         Err(err) => return Err(err),
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn evaluate_limited(
+        &self,
+        evaluate: impl Into<Evaluation>,
+        max_bytes: usize,
+    ) -> Result<EvaluationResult> {
+        let evaluate = evaluate.into();
+        let awaits_promise = evaluate.awaits_promise();
+        let (prefix, wait) = if awaits_promise {
+            ("async ", "await ")
+        } else {
+            ("", "")
+        };
+        // the value is kept in a container that is returned by reference
+        let mut call = match evaluate {
+            Evaluation::Expression(params) => {
+                let mut call = CallFunctionOnParams::new(format!(
+                    "{prefix}function() {{ return {{ value: {wait}(\n{}\n) }}; }}",
+                    params.expression
+                ));
+                call.execution_context_id = params.context_id;
+                call.silent = params.silent;
+                call.user_gesture = params.user_gesture;
+                call
+            }
+            Evaluation::Function(mut call) => {
+                call.function_declaration = format!(
+                    "{prefix}function(...args) {{ return {{ value: {wait}({}).apply(this, args) }}; }}",
+                    call.function_declaration
+                );
+                call
+            }
+        };
+        call.return_by_value = Some(false);
+        call.await_promise = Some(awaits_promise);
+        let container = self
+            .inner
+            .evaluate_function(call)
+            .await?
+            .object()
+            .object_id
+            .clone()
+            .ok_or_else(|| CdpError::msg("Failed to keep the value in the page"))?;
+
+        let res = self.take_limited_value(&container, max_bytes).await;
+        self.inner.release_best_effort(container).await;
+        res
+    }
+
+    /// Returns the value of the `container` by value if its JSON encoding
+    /// fits into `max_bytes`.
+    async fn take_limited_value(
+        &self,
+        container: &RemoteObjectId,
+        max_bytes: usize,
+    ) -> Result<EvaluationResult> {
+        let size = call_function_on(&self.inner, container, JSON_SIZE_FN, vec![], true).await?;
+        let size: usize = value_or_null(size)?;
+        if size > max_bytes {
+            return Err(CdpError::ValueTooLarge {
+                size,
+                limit: max_bytes,
+            });
+        }
+        let value = call_function_on(
+            &self.inner,
+            container,
+            "function() { return this.value; }",
+            vec![],
+            true,
+        )
+        .await?;
+        Ok(EvaluationResult::new(value))
+    }
+
+    /// Pauses script execution of the page with the `Debugger` domain until
+    /// the returned guard is dropped or resumed, e.g. to read several values
+    /// of rapidly changing state consistently.
+    ///
+    /// While paused, timers, event handlers and the rendering of the page
+    /// are on hold. Evaluations without an explicit context run on the
+    /// paused call frame, in the global scope of the main frame. Guards can
+    /// be nested, execution resumes when the last one is released. The
+    /// Debugger domain is then disabled again, unless it was enabled with
+    /// [`Page::enable_debugger`], so `debugger;` statements of the page
+    /// don't pause it afterwards. Fails with [`CdpError::Timeout`] if the
+    /// pause does not take effect within the request timeout.
+    ///
+    /// Promises of the page never settle while it is paused: evaluations
+    /// don't await promises by default then and fail with
+    /// [`CdpError::ScriptPaused`] if `await_promise` is set explicitly. DOM
+    /// commands that wait for the page, like navigations, don't complete
+    /// until execution resumes.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let pause = page.pause_script_execution().await?;
+    ///     let score: u64 = page.evaluate("game.score").await?.into_value()?;
+    ///     let level: u64 = page.evaluate("game.level").await?.into_value()?;
+    ///     pause.resume().await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn pause_script_execution(&self) -> Result<PauseGuard> {
+        self.inner.pause_script_execution().await?;
+        Ok(PauseGuard {
+            tab: Arc::clone(&self.inner),
+            active: true,
+        })
+    }
+
+    /// Gives the page transient user activation and runs the future with
+    /// every evaluation on this page treated as initiated by a user gesture,
+    /// so a sequence of calls that require activation, like opening popups,
+    /// playing media or entering fullscreen, shares one gesture context.
+    ///
+    /// The `user_gesture` flag of an evaluation only applies while the
+    /// evaluation runs, APIs that check activation later, e.g. after an
+    /// `await` or from an event handler, don't see it. So before running the
+    /// future, a keystroke of `F24`, a key without default action, is
+    /// dispatched through the `Input` domain, which activates the focused
+    /// frame like a real key press: `navigator.userActivation` reports it and
+    /// it lasts a few seconds or until an API consumes it, e.g. the first
+    /// `window.open`.
+    ///
+    /// Evaluations that set `user_gesture` explicitly keep their setting.
+    /// The scope applies to the page, so evaluations issued concurrently
+    /// from elsewhere while the future runs are affected as well.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.with_user_gesture(|| async {
+    ///         page.evaluate("document.querySelector('video').focus()").await?;
+    ///         page.evaluate("document.querySelector('video').requestFullscreen()").await?;
+    ///         page.evaluate("document.querySelector('video').play()").await?;
+    ///         Ok(())
+    ///     })
+    ///     .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn with_user_gesture<F, Fut, T>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.inner.press_key("F24").await?;
+        self.inner.with_user_gesture(f).await
+    }
+
+    /// Evaluates the expression and returns a live handle to the resulting
+    /// object.
+    ///
+    /// Fails if the expression does not evaluate to an object.
+    ///
+    /// # Example Inspect the values of an object by reference
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let object = page.js_object("({ body: document.body, title: document.title })").await?;
+    ///     for (key, value) in object.entries().await? {
+    ///         println!("{key}: {:?}", value.object().class_name);
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn js_object(&self, expression: impl Into<EvaluateParams>) -> Result<JsObject> {
+        let mut evaluate = expression.into();
+        evaluate.return_by_value = Some(false);
+        let object = self.inner.evaluate_expression(evaluate).await?;
+        let r#type = object.object().r#type.clone();
+        object
+            .into_js_object(self)
+            .ok_or_else(|| CdpError::msg(format!("Expected an object, found {}", r#type.as_ref())))
+    }
+
+    /// Creates a JavaScript `Map` from the entries and returns a live handle to
+    /// it.
+    ///
+    /// Unlike serializing a map to a plain object, the keys keep their type, so
+    /// numeric or object keys round trip, see [`JsObject::map_entries`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::collections::HashMap;
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let map = page.js_map([(1u32, "one"), (2, "two")]).await?;
+    ///     let entries: HashMap<u32, String> = map.map_entries().await?;
+    ///     assert_eq!(entries[&1], "one");
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn js_map<K: Serialize, V: Serialize>(
+        &self,
+        entries: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<JsObject> {
+        let entries = entries.into_iter().collect::<Vec<_>>();
+        let evaluate = EvaluateParams::builder()
+            .expression(format!("new Map({})", serde_json::to_string(&entries)?))
+            .return_by_value(false)
+            .build()
+            .unwrap();
+        let map = self.inner.evaluate_expression(evaluate).await?;
+        let remote_object_id = map
+            .object()
+            .object_id
+            .clone()
+            .ok_or_else(|| CdpError::msg("No object Id found for Map"))?;
+        Ok(
+            JsObject::new(Arc::clone(&self.inner), remote_object_id, "Map")
+                .with_description(map.object().description.clone()),
+        )
+    }
+
+    /// Evaluates the expression and returns a live handle to the resulting
+    /// `Headers` object.
+    ///
+    /// Fails if the expression does not evaluate to a `Headers` object.
+    ///
+    /// # Example Inspect the headers of a fetch response
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let headers = page.js_headers("fetch('/').then(res => res.headers)").await?;
+    ///     let content_type = headers.get("content-type").await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn js_headers(&self, expression: impl Into<EvaluateParams>) -> Result<JsHeaders> {
+        let mut evaluate = expression.into();
+        evaluate.return_by_value = Some(false);
+        let headers = self.inner.evaluate_expression(evaluate).await?;
+        let headers = headers.object();
+        if headers.class_name.as_deref() != Some("Headers") {
+            return Err(CdpError::msg(format!(
+                "Expected Headers, found {}",
+                headers
+                    .description
+                    .as_deref()
+                    .unwrap_or_else(|| headers.r#type.as_ref())
+            )));
+        }
+        let remote_object_id = headers
+            .object_id
+            .clone()
+            .ok_or_else(|| CdpError::msg("No object Id found for Headers"))?;
+        Ok(JsHeaders::new(Arc::clone(&self.inner), remote_object_id)
+            .with_description(headers.description.clone()))
+    }
+
+    /// Returns a live handle to the text selection of the page, `None` if
+    /// the document has no browsing context.
+    pub async fn selection(&self) -> Result<Option<JsSelection>> {
+        let selection = self.evaluate_handle("window.getSelection()").await?;
+        let selection = selection.object();
+        Ok(selection.object_id.clone().map(|id| {
+            JsSelection::new(Arc::clone(&self.inner), id)
+                .with_description(selection.description.clone())
+        }))
+    }
+
+    /// Evaluates the expression and returns a live handle to the resulting
+    /// `ReadableStream`.
+    ///
+    /// Fails if the expression does not evaluate to a `ReadableStream`.
+    pub async fn js_readable_stream(
+        &self,
+        expression: impl Into<EvaluateParams>,
+    ) -> Result<JsReadableStream> {
+        let mut evaluate = expression.into();
+        evaluate.return_by_value = Some(false);
+        let stream = self.inner.evaluate_expression(evaluate).await?;
+        let stream = stream.object();
+        if stream.class_name.as_deref() != Some("ReadableStream") {
+            return Err(CdpError::msg(format!(
+                "Expected ReadableStream, found {}",
+                stream
+                    .description
+                    .as_deref()
+                    .unwrap_or_else(|| stream.r#type.as_ref())
+            )));
+        }
+        let remote_object_id = stream
+            .object_id
+            .clone()
+            .ok_or_else(|| CdpError::msg("No object Id found for ReadableStream"))?;
+        Ok(
+            JsReadableStream::new(Arc::clone(&self.inner), remote_object_id)
+                .with_description(stream.description.clone()),
+        )
+    }
+
+    /// Evaluates the expression and returns a live handle to the resulting
+    /// promise without awaiting it.
+    ///
+    /// This allows to start a slow operation in the page, run other commands
+    /// in the meantime and await the result later, see [`JsPromise`].
+    ///
+    /// Fails if the expression does not evaluate to a promise.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let response = page
+    ///         .evaluate_promise("fetch('/api/items').then(r => r.json())")
+    ///         .await?;
+    ///     page.find_element("button").await?.click().await?;
+    ///     let items: Vec<serde_json::Value> = response.value().await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn evaluate_promise(
+        &self,
+        expression: impl Into<EvaluateParams>,
+    ) -> Result<JsPromise> {
+        let mut evaluate = expression.into();
+        evaluate.return_by_value = Some(false);
+        evaluate.await_promise = Some(false);
+        let promise = self.inner.evaluate_expression(evaluate).await?;
+        let promise = promise.object();
+        if promise.subtype != Some(RemoteObjectSubtype::Promise) {
+            return Err(CdpError::msg(format!(
+                "Expected Promise, found {}",
+                promise
+                    .description
+                    .as_deref()
+                    .unwrap_or_else(|| promise.r#type.as_ref())
+            )));
+        }
+        let remote_object_id = promise
+            .object_id
+            .clone()
+            .ok_or_else(|| CdpError::msg("No object Id found for Promise"))?;
+        Ok(JsPromise::new(Arc::clone(&self.inner), remote_object_id)
+            .with_description(promise.description.clone()))
+    }
+
+    /// Evaluates the expression and returns a live handle to the resulting
+    /// array.
+    ///
+    /// Fails if the expression does not evaluate to an array.
+    ///
+    /// # Example Mutate an array of the page in place
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let array = page.js_array("window.items = [1, 2]").await?;
+    ///     array.push(42).await?;
+    ///     let items: Vec<usize> = array.to_vec().await?;
+    ///     assert_eq!(items, vec![1, 2, 42]);
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn js_array(&self, expression: impl Into<EvaluateParams>) -> Result<JsArray> {
+        let mut evaluate = expression.into();
+        evaluate.return_by_value = Some(false);
+        self.inner
+            .evaluate_expression(evaluate)
+            .await?
+            .into_js_array(self)
+    }
+
+    /// Evaluates the expression and returns a live handle to the resulting
+    /// function.
+    ///
+    /// Fails if the expression does not evaluate to a function.
+    ///
+    /// # Example Inspect a handler of the page
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let onclick = page.js_function("document.querySelector('button').onclick").await?;
+    ///     println!("{}: {}", onclick.name().await?, onclick.to_source().await?);
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn js_function(&self, expression: impl Into<EvaluateParams>) -> Result<JsFunction> {
+        let mut evaluate = expression.into();
+        evaluate.return_by_value = Some(false);
+        self.inner
+            .evaluate_expression(evaluate)
+            .await?
+            .into_js_function(self)
+    }
+
+    /// Calls the `function` with a single array argument whose elements are
+    /// taken from `items`.
+    ///
+    /// In contrast to passing the items as `CallArgument` value, the iterator
+    /// is not materialized into one big JSON array. Instead the array is
+    /// created in the page first and the items are appended in chunks of
+    /// `chunk_size` elements, each chunk being sent as a separate
+    /// `CallFunctionOnParams` command. Once all chunks arrived, the `function`
+    /// is invoked with the reassembled array.
+    ///
+    /// # Note
+    ///
+    /// Only a single chunk is held in memory (as serialized JSON) on the rust
+    /// side at any time, but the page still holds the complete array. Smaller
+    /// chunks reduce the size of every message at the cost of more round
+    /// trips.
+    ///
+    /// # Example seed a large dataset
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let len: usize = page
+    ///         .evaluate_function_with_array(
+    ///             "(data) => { window.dataset = data; return data.length; }",
+    ///             0..1_000_000u32,
+    ///             10_000,
+    ///         )
+    ///         .await?
+    ///         .into_value()?;
+    ///     assert_eq!(len, 1_000_000);
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn evaluate_function_with_array<I>(
+        &self,
+        function: impl Into<String>,
+        items: I,
+        chunk_size: usize,
+    ) -> Result<EvaluationResult>
+    where
+        I: IntoIterator,
+        I::Item: Serialize,
+    {
+        let context_id = self.execution_context().await?;
+        let array = self.create_array(context_id).await?;
+        let res = self
+            .fill_array_and_call(
+                function.into(),
+                items,
+                chunk_size.max(1),
+                &array,
+                context_id,
+            )
+            .await;
+        self.inner.release_best_effort(array).await;
+        res
+    }
+
+    async fn fill_array_and_call<I>(
+        &self,
+        function: String,
+        items: I,
+        chunk_size: usize,
+        array: &RemoteObjectId,
+        context_id: Option<ExecutionContextId>,
+    ) -> Result<EvaluationResult>
+    where
+        I: IntoIterator,
+        I::Item: Serialize,
+    {
+        let mut items = items.into_iter();
+        loop {
+            let chunk = items
+                .by_ref()
+                .take(chunk_size)
+                .map(serde_json::to_value)
+                .collect::<serde_json::Result<Vec<_>>>()?;
+            if chunk.is_empty() {
+                break;
+            }
+            // fails with the exception if the push throws
+            call_function_on(
+                &self.inner,
+                array,
+                "function(chunk) { Array.prototype.push.apply(this, chunk); }",
+                vec![serde_json::Value::Array(chunk)],
+                true,
+            )
+            .await?;
+        }
+
+        let mut call = CallFunctionOnParams::builder()
+            .function_declaration(function)
+            .argument(CallArgument::builder().object_id(array.clone()).build())
+            .build()
+            .unwrap();
+        call.execution_context_id = context_id;
+        self.evaluate_function(call).await
+    }
+
+    /// Calls the `function` with a single `Uint8Array` argument containing the
+    /// `bytes`.
+    ///
+    /// The bytes are transferred in base64 encoded chunks of at most
+    /// `chunk_size` bytes each (rounded up to a multiple of 3), one call per
+    /// chunk, so no single message exceeds the chunk size. The chunks are
+    /// collected in the page and concatenated before the `function` is
+    /// invoked.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let data = std::fs::read("image.png")?;
+    ///     let len: usize = page
+    ///         .evaluate_function_with_bytes("(bytes) => bytes.length", &data, 1 << 20)
+    ///         .await?
+    ///         .into_value()?;
+    ///     assert_eq!(len, data.len());
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn evaluate_function_with_bytes(
+        &self,
+        function: impl AsRef<str>,
+        bytes: &[u8],
+        chunk_size: usize,
+    ) -> Result<EvaluationResult> {
+        // base64 chunks can only be decoded independently if they don't need padding
+        let chunk_size = (chunk_size.max(1) + 2) / 3 * 3;
+        let context_id = self.execution_context().await?;
+        let parts = self.create_array(context_id).await?;
+        let res = self
+            .fill_bytes_and_call(function.as_ref(), bytes, chunk_size, &parts, context_id)
+            .await;
+        self.inner.release_best_effort(parts).await;
+        res
+    }
+
+    async fn fill_bytes_and_call(
+        &self,
+        function: &str,
+        bytes: &[u8],
+        chunk_size: usize,
+        parts: &RemoteObjectId,
+        context_id: Option<ExecutionContextId>,
+    ) -> Result<EvaluationResult> {
+        // every chunk is a message of its own
+        for chunk in bytes.chunks(chunk_size) {
+            call_function_on(
+                &self.inner,
+                parts,
+                "function(chunk) {
+                    const binary = atob(chunk);
+                    const part = new Uint8Array(binary.length);
+                    for (let i = 0; i < binary.length; i++) {
+                        part[i] = binary.charCodeAt(i);
+                    }
+                    this.push(part);
+                }",
+                vec![utils::base64::encode(chunk).into()],
+                true,
+            )
+            .await?;
+        }
+
+        let mut call = CallFunctionOnParams::builder()
+            .function_declaration(format!(
+                "function(parts) {{
+                    const bytes = new Uint8Array(parts.reduce((len, part) => len + part.length, 0));
+                    let offset = 0;
+                    for (const part of parts) {{
+                        bytes.set(part, offset);
+                        offset += part.length;
+                    }}
+                    return ({function})(bytes);
+                }}"
+            ))
+            .argument(CallArgument::builder().object_id(parts.clone()).build())
+            .build()
+            .unwrap();
+        call.execution_context_id = context_id;
+        self.evaluate_function(call).await
+    }
+
+    /// Creates an empty array in the execution context and returns its id.
+    async fn create_array(&self, context_id: Option<ExecutionContextId>) -> Result<RemoteObjectId> {
+        let mut create = EvaluateParams::builder()
+            .expression("[]")
+            .return_by_value(false)
+            .build()
+            .unwrap();
+        create.context_id = context_id;
+        self.evaluate_expression(create)
+            .await?
+            .object()
+            .object_id
+            .clone()
+            .ok_or_else(|| CdpError::msg("Failed to create the array in the page"))
+    }
+
+    /// Evaluates the expression and returns the content of the resulting
+    /// `ArrayBuffer`, typed array (like `Uint8Array`) or `DataView` as bytes.
+    ///
+    /// Fails if the result is anything else.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let bytes = page
+    ///         .evaluate_bytes("fetch('/favicon.ico').then(res => res.arrayBuffer())")
+    ///         .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn evaluate_bytes(&self, expression: impl Into<EvaluateParams>) -> Result<Vec<u8>> {
+        let mut evaluate = expression.into();
+        evaluate.return_by_value = Some(false);
+        let object = self.inner.evaluate_expression(evaluate).await?;
+        let object_id = object.object().object_id.clone().ok_or_else(|| {
+            CdpError::msg(format!(
+                "Expected binary data, found {}",
+                object.object().r#type.as_ref()
+            ))
+        })?;
+
+        let encoded = self
+            .evaluate_function(
+                CallFunctionOnParams::builder()
+                    .object_id(object_id.clone())
+                    .function_declaration(format!(
+                        "function() {{
+                            const tag = Object.prototype.toString.call(this);
+                            const binary = ArrayBuffer.isView(this)
+                                || tag === '[object ArrayBuffer]'
+                                || tag === '[object SharedArrayBuffer]';
+                            return binary ? ({ENCODE_BASE64_FN})(this) : null;
+                        }}"
+                    ))
+                    .build()
+                    .unwrap(),
+            )
+            .await;
+        self.inner.release_best_effort(object_id).await;
+        let encoded: Option<String> = encoded?.into_value()?;
+        let encoded = encoded.ok_or_else(|| {
+            CdpError::msg(format!(
+                "Expected binary data, found {}",
+                object
+                    .object()
+                    .class_name
+                    .as_deref()
+                    .unwrap_or(object.object().r#type.as_ref())
+            ))
+        })?;
+        Ok(utils::base64::decode(encoded)?)
+    }
+
+    /// Returns the default execution context identifier of this page that
+    /// represents the context for JavaScript execution.
+    pub async fn execution_context(&self) -> Result<Option<ExecutionContextId>> {
+        self.inner.execution_context().await
+    }
+
+    /// Returns the secondary execution context identifier of this page that
+    /// represents the context for JavaScript execution for manipulating the
+    /// DOM.
+    ///
+    /// See `Page::set_contents`
+    pub async fn secondary_execution_context(&self) -> Result<Option<ExecutionContextId>> {
+        self.inner.secondary_execution_context().await
+    }
+
+    pub async fn frame_execution_context(
+        &self,
+        frame_id: FrameId,
+    ) -> Result<Option<ExecutionContextId>> {
+        self.inner.frame_execution_context(frame_id).await
+    }
+
+    /// Returns a live handle to the `document` of the frame, to query
+    /// elements of frames other than the main frame.
+    ///
+    /// Fails with [`CdpError::FrameContextUnavailable`] if the frame has no
+    /// execution context yet.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     for frame_id in page.frames().await? {
+    ///         let document = page.frame_document(frame_id).await?;
+    ///         let links = document.query_selector_all("a").await?;
+    ///         println!("{}: {} links", document.url().await?, links.len());
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn frame_document(&self, frame_id: FrameId) -> Result<JsDocument> {
+        let document = self.inner.frame_document_object(frame_id.clone()).await?;
+        let remote_object_id = document
+            .object_id
+            .ok_or_else(|| CdpError::FrameContextUnavailable(frame_id.clone()))?;
+        Ok(
+            JsDocument::new(Arc::clone(&self.inner), remote_object_id, frame_id)
+                .with_description(document.description),
+        )
+    }
+
+    /// Returns a live handle to the `document` of the main frame, see
+    /// [`Page::frame_document`].
+    pub async fn document(&self) -> Result<JsDocument> {
+        let main_frame = self
+            .mainframe()
+            .await?
+            .ok_or_else(|| CdpError::msg("Page has no main frame"))?;
+        self.frame_document(main_frame).await
+    }
+
+    /// Returns the first element in the document of the frame which matches
+    /// the given CSS selector, `None` if there is none.
+    ///
+    /// Fails with [`CdpError::FrameContextUnavailable`] if the frame has no
+    /// execution context yet.
+    pub async fn find_element_in_frame(
+        &self,
+        frame_id: FrameId,
+        selector: impl AsRef<str>,
+    ) -> Result<Option<Element>> {
+        match self
+            .inner
+            .query_selector_in_frame(frame_id, selector)
+            .await?
+        {
+            Some(node_id) => Ok(Some(Element::new(Arc::clone(&self.inner), node_id).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the first element matching the [`DeepSelector`], whose
+    /// segments separated by `>>>` select frames and then the element in the
+    /// innermost frame, like `iframe#payment >>> input[name=card]`. `None` if
+    /// there is no such element.
+    ///
+    /// The returned element belongs to the main world of its frame, so its
+    /// methods run in that frame. Frames of other processes, like
+    /// cross-origin frames with site isolation, are not reachable through the
+    /// page and fail with [`CdpError::CrossOriginFrame`], a frame that has not
+    /// started loading with [`CdpError::FrameContextUnavailable`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     if let Some(card) = page
+    ///         .deep_query_selector("iframe#payment >>> input[name=card]")
+    ///         .await?
+    ///     {
+    ///         card.click().await?.type_str("4242 4242 4242 4242").await?;
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn deep_query_selector(&self, selector: impl AsRef<str>) -> Result<Option<Element>> {
+        let selector = DeepSelector::parse(selector.as_ref())?;
+        let last = selector.segments().last().expect("at least one segment");
+        for frame_id in self.deep_selector_frames(&selector).await? {
+            if let Some(node_id) = self.inner.query_selector_in_frame(frame_id, last).await? {
+                return Ok(Some(Element::new(Arc::clone(&self.inner), node_id).await?));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns all elements matching the [`DeepSelector`] in all frames the
+    /// frame segments select, see [`Page::deep_query_selector`].
+    pub async fn deep_query_selector_all(&self, selector: impl AsRef<str>) -> Result<Vec<Element>> {
+        let selector = DeepSelector::parse(selector.as_ref())?;
+        let last = selector.segments().last().expect("at least one segment");
+        let mut node_ids = Vec::new();
+        for frame_id in self.deep_selector_frames(&selector).await? {
+            node_ids.extend(
+                self.inner
+                    .query_selector_all_in_frame(frame_id, last.as_str())
+                    .await?,
+            );
+        }
+        Element::from_nodes(&self.inner, &node_ids).await
+    }
+
+    /// The frames the last segment of the `selector` is matched in, in
+    /// document order.
+    async fn deep_selector_frames(&self, selector: &DeepSelector) -> Result<Vec<FrameId>> {
+        let main_frame = self
+            .mainframe()
+            .await?
+            .ok_or_else(|| CdpError::msg("Page has no main frame"))?;
+        let segments = selector.segments();
+        let mut frames = vec![main_frame];
+        for (segment, frame_selector) in segments[..segments.len() - 1].iter().enumerate() {
+            let reachable = self.frames().await?;
+            let mut children = Vec::new();
+            for frame_id in frames {
+                let owners = self
+                    .inner
+                    .query_selector_all_in_frame(frame_id, frame_selector.as_str())
+                    .await?;
+                for owner in owners {
+                    let Some(child) = self.inner.owned_frame(owner).await? else {
+                        continue;
+                    };
+                    if !reachable.contains(&child) {
+                        return Err(CdpError::CrossOriginFrame {
+                            segment,
+                            selector: frame_selector.clone(),
+                            frame_id: child,
+                        });
+                    }
+                    children.push(child);
+                }
+            }
+            frames = children;
+        }
+        Ok(frames)
+    }
+
+    pub async fn frame_secondary_execution_context(
+        &self,
+        frame_id: FrameId,
+    ) -> Result<Option<ExecutionContextId>> {
+        self.inner.frame_secondary_execution_context(frame_id).await
+    }
+
+    /// Returns all execution contexts currently known to this page, ordered
+    /// by their id.
+    ///
+    /// This includes the main and the secondary world of every frame as well
+    /// as isolated worlds, e.g. of extensions.
+    pub async fn execution_contexts(&self) -> Result<Vec<ExecutionContextInfo>> {
+        self.inner.execution_contexts().await
+    }
+
+    /// Resolves once the context of the `world` of the frame exists, the main
+    /// frame if `frame_id` is `None`.
+    ///
+    /// Code that runs right after a navigation can race the creation of the
+    /// new execution context; waiting for it first avoids that.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::handler::domworld::DOMWorldKind;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.goto("https://example.com").await?;
+    ///     let ctx = page
+    ///         .wait_for_execution_context(None, DOMWorldKind::Main)
+    ///         .await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_for_execution_context(
+        &self,
+        frame_id: Option<FrameId>,
+        world: DOMWorldKind,
+    ) -> Result<ExecutionContextId> {
+        // subscribe first so no creation can slip through between the checks
+        let mut created = self
+            .event_listener::<EventExecutionContextCreated>()
+            .await?;
+        loop {
+            if let Some(ctx) = self
+                .inner
+                .execution_context_for_world(frame_id.clone(), world)
+                .await?
+            {
+                return Ok(ctx);
+            }
+            if created.next().await.is_none() {
+                return Err(CdpError::NotFound);
+            }
+        }
+    }
+
+    /// Returns a stream of changes to the execution contexts of this page.
+    pub async fn execution_context_changes(
+        &self,
+    ) -> Result<impl Stream<Item = ExecutionContextChange> + Send + Unpin> {
+        let created = self
+            .event_listener::<EventExecutionContextCreated>()
+            .await?
+            .map(|ev| ExecutionContextChange::Created((&ev.context).into()));
+        let destroyed = self
+            .event_listener::<EventExecutionContextDestroyed>()
+            .await?
+            .map(|ev| ExecutionContextChange::Destroyed(ev.execution_context_unique_id.clone()));
+        let cleared = self
+            .event_listener::<EventExecutionContextsCleared>()
+            .await?
+            .map(|_| ExecutionContextChange::Cleared);
+        Ok(stream::select(stream::select(created, destroyed), cleared))
+    }
+
+    /// Evaluates given script in every frame upon creation (before loading
+    /// frame's scripts)
+    pub async fn evaluate_on_new_document(
+        &self,
+        script: impl Into<AddScriptToEvaluateOnNewDocumentParams>,
+    ) -> Result<ScriptIdentifier> {
+        Ok(self.execute(script.into()).await?.result.identifier)
+    }
+
+    /// Adds a script that is evaluated in every frame upon creation (before
+    /// loading frame's scripts), e.g. to install polyfills or overrides.
+    ///
+    /// The script is removed again when the returned [`InitScriptHandle`] is
+    /// dropped, use [`InitScriptHandle::forget`] to keep it installed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let script = page
+    ///         .add_init_script("window.matchMedia = () => ({ matches: false })")
+    ///         .await?;
+    ///     page.goto("https://example.com").await?;
+    ///     script.remove().await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn add_init_script(&self, source: impl Into<String>) -> Result<InitScriptHandle> {
+        self.inner.add_init_script(source).await
+    }
+
+    /// Posts the `data` to the window of the frame, or of the main frame if
+    /// `None`, with `window.postMessage(data, target_origin)`.
+    ///
+    /// The message is dispatched like one posted by the window itself, so
+    /// its `source` is the receiving window. Fails with
+    /// [`CdpError::FrameContextUnavailable`] if the frame has no execution
+    /// context yet.
+    pub async fn post_message(
+        &self,
+        frame_id: Option<FrameId>,
+        data: impl Serialize,
+        target_origin: &str,
+    ) -> Result<()> {
+        let context_id = match frame_id {
+            Some(frame_id) => self
+                .inner
+                .frame_execution_context(frame_id.clone())
+                .await?
+                .ok_or(CdpError::FrameContextUnavailable(frame_id))?,
+            None => self
+                .inner
+                .execution_context()
+                .await?
+                .ok_or_else(|| CdpError::msg("Main frame has no execution context"))?,
+        };
+        let post = EvaluateParams::builder()
+            .expression(format!(
+                "window.postMessage({}, {})",
+                serde_json::to_string(&data)?,
+                serde_json::to_string(target_origin)?
+            ))
+            .context_id(context_id)
+            .build()
+            .unwrap();
+        self.inner.evaluate_expression(post).await?;
+        Ok(())
+    }
+
+    /// Returns a stream of the messages the windows of this page receive
+    /// through `postMessage`, from any origin or only from `origin`.
+    ///
+    /// The listener is installed in the current documents and, through an
+    /// init script, in every new one, so it keeps listening across
+    /// navigations. It is removed when the stream is dropped.
+    ///
+    /// The data is received as JSON, structured-cloneable values that JSON
+    /// can't represent, like `Map` or `Blob`, are received as `null` or
+    /// empty objects.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use futures::StreamExt;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let mut messages = page.messages(Some("https://widget.example".to_string())).await?;
+    ///     page.goto("https://example.com/with-widget").await?;
+    ///     if let Some(message) = messages.next().await {
+    ///         println!("{} sent {}", message.origin, message.data);
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn messages(&self, origin: Option<String>) -> Result<MessageStream> {
+        static BINDINGS: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "__chromiumoxide_message_{}",
+            BINDINGS.fetch_add(1, Ordering::Relaxed)
+        );
+        let events = self.event_listener::<EventBindingCalled>().await?;
+        self.execute(AddBindingParams::new(name.clone())).await?;
+        let script = format!(
+            "(() => {{
+                const notify = globalThis[{binding}];
+                const origin = {origin};
+                if (typeof notify !== 'function') {{
+                    return;
+                }}
+                const source = (event) => {{
+                    if (event.source === window) {{
+                        return {{ kind: 'self' }};
+                    }}
+                    if (window !== window.parent && event.source === window.parent) {{
+                        return {{ kind: 'parent' }};
+                    }}
+                    if (window.opener && event.source === window.opener) {{
+                        return {{ kind: 'opener' }};
+                    }}
+                    for (let i = 0; i < window.frames.length; i++) {{
+                        if (event.source === window.frames[i]) {{
+                            return {{ kind: 'child', index: i }};
+                        }}
+                    }}
+                    return {{ kind: 'unknown' }};
+                }};
+                window.addEventListener('message', (event) => {{
+                    if (origin !== null && event.origin !== origin) {{
+                        return;
+                    }}
+                    let data = null;
+                    try {{
+                        data = JSON.parse(JSON.stringify(event.data) ?? 'null');
+                    }} catch (e) {{}}
+                    try {{
+                        notify(JSON.stringify({{
+                            data,
+                            origin: event.origin,
+                            url: location.href,
+                            isMainFrame: window === window.top,
+                            source: source(event),
+                        }}));
+                    }} catch (e) {{}}
+                }});
+            }})()",
+            binding = serde_json::to_string(&name)?,
+            origin = serde_json::to_string(&origin)?,
+        );
+        let init_script = self.add_init_script(script.clone()).await?;
+        // the binding was added to the current documents as well
+        for context in self.inner.execution_contexts().await? {
+            let install = EvaluateParams::builder()
+                .expression(script.clone())
+                .context_id(context.id)
+                .build()
+                .unwrap();
+            let _ = self.inner.evaluate_expression(install).await;
+        }
+        Ok(MessageStream {
+            events,
+            name,
+            tab: Arc::clone(&self.inner),
+            _script: init_script,
+        })
+    }
+
+    /// Records the streams the page obtains with `getUserMedia`, so they can
+    /// be inspected with [`Page::active_media_streams`].
+    ///
+    /// This patches `navigator.mediaDevices.getUserMedia` in the current
+    /// document and, through an init script, in every new one. The patch is
+    /// not undone in the current document when the returned handle is
+    /// dropped, but it keeps the promise of the original intact.
+    pub async fn record_media_streams(&self) -> Result<InitScriptHandle> {
+        let script = self.add_init_script(RECORD_MEDIA_STREAMS_SCRIPT).await?;
+        self.evaluate_expression(RECORD_MEDIA_STREAMS_SCRIPT)
+            .await?;
+        Ok(script)
+    }
+
+    /// Grants the current origin of the page access to the camera and
+    /// microphone, or all origins if the page has none yet, like
+    /// `about:blank`.
+    pub async fn grant_media_permissions(&self) -> Result<&Self> {
+        self.inner
+            .grant_permissions(vec![
+                PermissionType::VideoCapture,
+                PermissionType::AudioCapture,
+            ])
+            .await?;
+        Ok(self)
+    }
+
+    /// Reads the text of the clipboard through the async Clipboard API of
+    /// the page.
+    ///
+    /// The clipboard permissions are granted to the current origin and the
+    /// page is brought to front before reading, so this works in headless
+    /// and headful browsers alike. The Clipboard API is only available in
+    /// secure contexts.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.write_clipboard_text("copied from rust").await?;
+    ///     assert_eq!(page.read_clipboard_text().await?, "copied from rust");
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_clipboard_text(&self) -> Result<String> {
+        self.inner.read_clipboard_text().await
+    }
+
+    /// Replaces the content of the clipboard with the text, see
+    /// [`Page::read_clipboard_text`].
+    pub async fn write_clipboard_text(&self, text: impl AsRef<str>) -> Result<&Self> {
+        self.inner.write_clipboard_text(text).await?;
+        Ok(self)
+    }
+
+    /// Returns the active streams obtained with `getUserMedia` in the main
+    /// frame since [`Page::record_media_streams`] was called.
+    ///
+    /// Returns no streams if the recording was never started.
+    pub async fn active_media_streams(&self) -> Result<Vec<MediaStreamInfo>> {
+        Ok(self
+            .evaluate_function(ACTIVE_MEDIA_STREAMS_FN)
+            .await?
+            .into_value()?)
+    }
+
+    /// Stops all tracks of the recorded streams of the main frame, releasing
+    /// the devices, and returns the number of tracks that were live.
+    pub async fn stop_all_media_tracks(&self) -> Result<usize> {
+        Ok(self
+            .evaluate_function(STOP_MEDIA_TRACKS_FN)
+            .await?
+            .into_value()?)
     }
 
-    /// Returns the secondary execution context identifier of this page that
-    /// represents the context for JavaScript execution for manipulating the
-    /// DOM.
+    /// Returns a stream that yields whenever a document of this page, or
+    /// one of its frames, fired its `load` event.
     ///
-    /// See `Page::set_contents`
-    pub async fn secondary_execution_context(&self) -> Result<Option<ExecutionContextId>> {
-        self.inner.secondary_execution_context().await
-    }
-
-    pub async fn frame_execution_context(
-        &self,
-        frame_id: FrameId,
-    ) -> Result<Option<ExecutionContextId>> {
-        self.inner.frame_execution_context(frame_id).await
+    /// This installs an init script that reports the load event through a
+    /// binding, so every navigation is reported, including reloads and
+    /// navigations started by the page itself. The init script and the
+    /// binding are removed when the stream is dropped.
+    pub async fn new_documents(&self) -> Result<NewDocumentStream> {
+        static BINDINGS: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "__chromiumoxide_new_document_{}",
+            BINDINGS.fetch_add(1, Ordering::Relaxed)
+        );
+        let events = self.event_listener::<EventBindingCalled>().await?;
+        self.execute(AddBindingParams::new(name.clone())).await?;
+        let binding = serde_json::to_string(&name)?;
+        // the binding is captured right away, before scripts of the page can
+        // remove it
+        let script = self
+            .add_init_script(format!(
+                "(() => {{
+                    const notify = globalThis[{binding}];
+                    if (typeof notify !== 'function') {{
+                        return;
+                    }}
+                    const fire = () => notify(JSON.stringify({{
+                        url: location.href,
+                        isMainFrame: window === window.top,
+                    }}));
+                    if (document.readyState === 'complete') {{
+                        fire();
+                    }} else {{
+                        window.addEventListener('load', fire, {{ once: true }});
+                    }}
+                }})()"
+            ))
+            .await?;
+        Ok(NewDocumentStream {
+            events,
+            name,
+            tab: Arc::clone(&self.inner),
+            _script: script,
+        })
     }
 
-    pub async fn frame_secondary_execution_context(
+    /// Returns a future that calls the `handler` for every document of this
+    /// page that fired its `load` event, see [`Page::new_documents`].
+    ///
+    /// The returned future runs until the page is closed and needs to be
+    /// polled, e.g. by spawning it. The handler is awaited before the next
+    /// document is handled.
+    ///
+    /// # Example seed state on every navigation
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     let setup = page
+    ///         .on_new_document(|page, document| async move {
+    ///             if document.is_main_frame {
+    ///                 let _ = page.evaluate("localStorage.setItem('seen', '1')").await;
+    ///             }
+    ///         })
+    ///         .await?;
+    ///     tokio::spawn(setup);
+    ///     page.goto("https://example.com").await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn on_new_document<F, Fut>(
         &self,
-        frame_id: FrameId,
-    ) -> Result<Option<ExecutionContextId>> {
-        self.inner.frame_secondary_execution_context(frame_id).await
+        mut handler: F,
+    ) -> Result<impl Future<Output = ()> + Send + 'static>
+    where
+        F: FnMut(Page, NewDocument) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        let mut documents = self.new_documents().await?;
+        let page = self.clone();
+        Ok(async move {
+            while let Some(document) = documents.next().await {
+                handler(page.clone(), document).await;
+            }
+        })
     }
 
-    /// Evaluates given script in every frame upon creation (before loading
-    /// frame's scripts)
-    pub async fn evaluate_on_new_document(
-        &self,
-        script: impl Into<AddScriptToEvaluateOnNewDocumentParams>,
-    ) -> Result<ScriptIdentifier> {
-        Ok(self.execute(script.into()).await?.result.identifier)
+    /// Replaces the document of the main frame with the `html` via
+    /// `Page.setDocumentContent`, e.g. to serve previously captured content
+    /// in a blank page.
+    ///
+    /// In contrast to [`Page::set_content`] this does not run through
+    /// `document.write` and does not wait for the new document to load.
+    pub async fn set_document_content(&self, html: impl Into<String>) -> Result<&Self> {
+        let frame_id = self
+            .mainframe()
+            .await?
+            .ok_or_else(|| CdpError::msg("Page has no main frame"))?;
+        self.execute(SetDocumentContentParams::new(frame_id, html))
+            .await?;
+        Ok(self)
     }
 
     /// Set the content of the frame.
@@ -1196,6 +3777,281 @@ impl Page {
         self.wait_for_navigation().await
     }
 
+    /// Restricts script evaluation on this page to the origins of the
+    /// `allowlist`, `None` removes the restriction.
+    ///
+    /// Before every evaluation the origin of the target execution context is
+    /// looked up from the context descriptions chromium reported, without
+    /// an extra round trip. If it is not allowed, the evaluation fails with
+    /// [`CdpError::OriginNotAllowed`] and no command is sent. This guards
+    /// against running injected code after an unexpected redirect.
+    ///
+    /// Calls on remote objects, e.g. via [`Element`] or [`JsObject`], are
+    /// checked against the origin of the context the object was created in,
+    /// which chromium records in the object id. While script execution is
+    /// paused, the context of the paused call frame is checked.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::origin::EvalOriginAllowlist;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.set_eval_origin_allowlist(Some(EvalOriginAllowlist::new([
+    ///         "https://example.com",
+    ///         "https://*.example.com",
+    ///     ])));
+    ///     let title: String = page.evaluate("document.title").await?.into_value()?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub fn set_eval_origin_allowlist(&self, allowlist: Option<EvalOriginAllowlist>) -> &Self {
+        self.inner.set_eval_origin_allowlist(allowlist);
+        self
+    }
+
+    /// Returns the scroll position of the page's viewport, `scrollX` and
+    /// `scrollY`.
+    pub async fn scroll_position(&self) -> Result<(f64, f64)> {
+        Ok(self
+            .evaluate_expression("[window.scrollX, window.scrollY]")
+            .await?
+            .into_value()?)
+    }
+
+    /// Scrolls the page to `x`, `y` and waits until the scrolling came to a
+    /// halt, which matters for `smooth` scrolling that would otherwise still
+    /// be animating.
+    ///
+    /// Fails with [`CdpError::Timeout`] if the scroll position did not settle
+    /// within `timeout`.
+    pub async fn scroll_to_and_wait(
+        &self,
+        x: f64,
+        y: f64,
+        behavior: ScrollBehavior,
+        timeout: Duration,
+    ) -> Result<&Self> {
+        let options = serde_json::json!({
+            "left": x,
+            "top": y,
+            "behavior": behavior,
+        });
+        let settled: bool = self
+            .evaluate_function(format!(
+                "() => {{
+                    window.scrollTo({});
+                    return {};
+                }}",
+                options,
+                utils::wait_for_scroll_end("[window.scrollX, window.scrollY]", timeout)
+            ))
+            .await?
+            .into_value()?;
+        if settled {
+            Ok(self)
+        } else {
+            Err(CdpError::Timeout)
+        }
+    }
+
+    /// Keeps scrolling down by `step` pixels until the bottom is reached and
+    /// the document stopped growing, as is common for infinite scrolling.
+    ///
+    /// After every step this waits for `quiet_time` to give the page time to
+    /// load more content, and gives up after `max_iterations` steps. Returns
+    /// the final `scrollHeight` of the document.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use std::time::Duration;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.scroll_to_bottom(800., 50, Duration::from_millis(500)).await?;
+    ///     let items = page.find_elements(".item").await?;
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn scroll_to_bottom(
+        &self,
+        step: f64,
+        max_iterations: usize,
+        quiet_time: Duration,
+    ) -> Result<f64> {
+        const SCROLL_HEIGHT: &str = "document.scrollingElement.scrollHeight";
+        let mut height: f64 = self
+            .evaluate_expression(SCROLL_HEIGHT)
+            .await?
+            .into_value()?;
+        for _ in 0..max_iterations {
+            self.evaluate_function(format!("() => {{ window.scrollBy(0, {step}); }}"))
+                .await?;
+            futures_timer::Delay::new(quiet_time).await;
+
+            let (bottom, new_height): (f64, f64) = self
+                .evaluate_expression(format!(
+                    "[window.scrollY + window.innerHeight, {SCROLL_HEIGHT}]"
+                ))
+                .await?
+                .into_value()?;
+            if bottom >= new_height && new_height <= height {
+                return Ok(new_height);
+            }
+            height = new_height;
+        }
+        Ok(height)
+    }
+
+    /// Starts tracking the remote object handles of this page, like
+    /// [`Element`] and [`JsObject`], for [`Page::live_object_stats`].
+    ///
+    /// Once more than `warn_threshold` handles are alive, a warning is logged.
+    /// With the `object-backtrace` feature the creation site of every handle
+    /// is captured as well, which is expensive. Only handles created after
+    /// this call are tracked.
+    pub fn enable_object_diagnostics(&self, warn_threshold: Option<usize>) -> &Self {
+        self.inner.objects().enable(warn_threshold);
+        self
+    }
+
+    /// Stops tracking remote object handles and forgets the tracked ones.
+    pub fn disable_object_diagnostics(&self) -> &Self {
+        self.inner.objects().disable();
+        self
+    }
+
+    /// Returns the live tracked handles grouped by the class of their
+    /// objects, the largest groups first.
+    ///
+    /// Requires [`Page::enable_object_diagnostics`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.enable_object_diagnostics(Some(10_000));
+    ///     // ...
+    ///     for stat in page.live_object_stats() {
+    ///         println!("{}: {} (oldest {:?})", stat.class, stat.count, stat.oldest_age);
+    ///     }
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub fn live_object_stats(&self) -> Vec<ObjectStat> {
+        self.inner.objects().stats()
+    }
+
+    /// Releases the remote objects of all live tracked handles in the page.
+    ///
+    /// The handles stay around, but using them fails with
+    /// [`CdpError::StaleHandle`]. Returns the number of released objects.
+    /// Requires [`Page::enable_object_diagnostics`].
+    pub async fn release_all_objects(&self) -> Result<usize> {
+        let ids = self.inner.objects().release_all();
+        let released = ids.len();
+        for id in ids {
+            self.execute(ReleaseObjectParams::new(id)).await?;
+        }
+        Ok(released)
+    }
+
+    /// Enables caching the results of [`Page::evaluate_cached`] according
+    /// to the `policy`, which drops previously cached results.
+    ///
+    /// Meant for pure expressions that are evaluated over and over, like
+    /// feature detection or reading constant configuration.
+    pub fn enable_eval_cache(&self, policy: CachePolicy) -> &Self {
+        self.inner.eval_cache().enable(policy);
+        self
+    }
+
+    /// Disables the evaluation cache and drops the cached results.
+    pub fn disable_eval_cache(&self) -> &Self {
+        self.inner.eval_cache().disable();
+        self
+    }
+
+    /// Returns the hit and miss counters of the evaluation cache.
+    pub fn eval_cache_stats(&self) -> EvalCacheStats {
+        self.inner.eval_cache().stats()
+    }
+
+    /// The number of commands of this page waiting for the handler to submit
+    /// them to the browser.
+    ///
+    /// Once the queue depth configured with
+    /// [`BrowserConfigBuilder::command_queue_depth`](crate::browser::BrowserConfigBuilder::command_queue_depth)
+    /// is reached, new commands wait for capacity.
+    pub fn command_queue_depth(&self) -> usize {
+        self.inner.command_queue().depth()
+    }
+
+    /// Sets the policy that evaluations and element lookups are retried with
+    /// when they fail with a transient error, like an execution context
+    /// destroyed by a navigation.
+    ///
+    /// Pages start with [`RetryPolicy::none`], which attempts every
+    /// operation once. See [`RetryPolicy`] for what is never retried.
+    pub fn set_retry_policy(&self, policy: RetryPolicy) -> &Self {
+        self.inner.retry_state().set_policy(policy);
+        self
+    }
+
+    /// The policy set with [`Page::set_retry_policy`].
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.inner.retry_state().policy()
+    }
+
+    /// Returns the counters of the operations of this page that were retried.
+    pub fn retry_stats(&self) -> RetryStats {
+        self.inner.retry_state().stats()
+    }
+
+    /// Returns the counters of the commands this page sent to the browser.
+    pub fn command_stats(&self) -> CommandStats {
+        self.inner.command_queue().stats()
+    }
+
+    /// Same as [`Page::evaluate_expression`], but repeated evaluations are
+    /// answered from the cache enabled with [`Page::enable_eval_cache`].
+    ///
+    /// Results are cached per expression, parameters and execution context
+    /// and dropped when the main frame navigates, so a new document never
+    /// gets stale results. Only
+    /// results returned by value are cached, evaluations with
+    /// `return_by_value` set to `false` or with `user_gesture` are always
+    /// sent to the browser.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use chromiumoxide::page::Page;
+    /// # use chromiumoxide::error::Result;
+    /// # use chromiumoxide::js::cache::CachePolicy;
+    /// # async fn demo(page: Page) -> Result<()> {
+    ///     page.enable_eval_cache(CachePolicy::default());
+    ///     for _ in 0..100 {
+    ///         let supported: bool = page
+    ///             .evaluate_cached("'IntersectionObserver' in window")
+    ///             .await?
+    ///             .into_value()?;
+    ///     }
+    ///     assert_eq!(page.eval_cache_stats().misses, 1);
+    ///     # Ok(())
+    /// # }
+    /// ```
+    pub async fn evaluate_cached(
+        &self,
+        evaluate: impl Into<EvaluateParams>,
+    ) -> Result<EvaluationResult> {
+        self.inner.evaluate_cached(evaluate).await
+    }
+
     /// Returns the HTML content of the page
     pub async fn content(&self) -> Result<String> {
         Ok(self
@@ -1249,6 +4105,27 @@ impl Page {
     }
 }
 
+/// Resolves to the first event of the stream that matches the `predicate`.
+async fn next_matching<T: IntoEventKind + Unpin>(
+    mut events: EventStream<T>,
+    predicate: impl Fn(&T) -> bool,
+    timeout: Duration,
+) -> Result<Arc<T>> {
+    let matching = async {
+        while let Some(event) = events.next().await {
+            if predicate(&event) {
+                return Some(event);
+            }
+        }
+        None
+    };
+    match utils::with_timeout(matching, timeout).await {
+        Some(Some(event)) => Ok(event),
+        Some(None) => Err(CdpError::NotFound),
+        None => Err(CdpError::Timeout),
+    }
+}
+
 impl From<Arc<PageInner>> for Page {
     fn from(inner: Arc<PageInner>) -> Self {
         Self { inner }
@@ -1265,8 +4142,278 @@ pub(crate) fn validate_cookie_url(url: &str) -> Result<()> {
     }
 }
 
+/// Keeps script execution of a page paused, see
+/// [`Page::pause_script_execution`].
+///
+/// Script execution resumes once the last guard of the page is dropped or
+/// resumed.
+#[derive(Debug)]
+#[must_use = "script execution resumes when the guard is dropped"]
+pub struct PauseGuard {
+    tab: Arc<PageInner>,
+    active: bool,
+}
+
+impl PauseGuard {
+    /// Releases the guard and waits until script execution resumed, if this
+    /// was the last guard.
+    pub async fn resume(mut self) -> Result<()> {
+        self.active = false;
+        if self.tab.release_script_pause() {
+            self.tab.execute(ResumeParams::default()).await?;
+            if self.tab.owns_debugger() {
+                self.tab
+                    .execute(js_protocol::debugger::DisableParams::default())
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for PauseGuard {
+    fn drop(&mut self) {
+        if self.active && self.tab.release_script_pause() {
+            self.tab.execute_detached(ResumeParams::default());
+            if self.tab.owns_debugger() {
+                self.tab
+                    .execute_detached(js_protocol::debugger::DisableParams::default());
+            }
+        }
+    }
+}
+
+/// A script that is evaluated in every frame upon creation, see
+/// [`Page::add_init_script`].
+///
+/// The script is removed when this handle is dropped.
+#[derive(Debug)]
+pub struct InitScriptHandle {
+    identifier: Option<ScriptIdentifier>,
+    tab: Arc<PageInner>,
+}
+
+impl InitScriptHandle {
+    pub(crate) fn new(identifier: ScriptIdentifier, tab: Arc<PageInner>) -> Self {
+        Self {
+            identifier: Some(identifier),
+            tab,
+        }
+    }
+
+    /// The identifier of the script.
+    pub fn identifier(&self) -> &ScriptIdentifier {
+        self.identifier
+            .as_ref()
+            .expect("identifier is only taken on drop")
+    }
+
+    /// Removes the script and waits until it is removed.
+    pub async fn remove(mut self) -> Result<()> {
+        if let Some(identifier) = self.identifier.take() {
+            self.tab
+                .execute(RemoveScriptToEvaluateOnNewDocumentParams::new(identifier))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Keeps the script installed and returns its identifier, which can be
+    /// used to remove it manually via `Page.removeScriptToEvaluateOnNewDocument`.
+    pub fn forget(mut self) -> ScriptIdentifier {
+        self.identifier
+            .take()
+            .expect("identifier is only taken on drop")
+    }
+}
+
+impl Drop for InitScriptHandle {
+    fn drop(&mut self) {
+        if let Some(identifier) = self.identifier.take() {
+            self.tab.remove_init_script_detached(identifier);
+        }
+    }
+}
+
+/// A document that fired its `load` event, see [`Page::new_documents`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewDocument {
+    /// The URL of the document
+    pub url: String,
+    /// Whether the document belongs to the main frame of the page
+    pub is_main_frame: bool,
+    /// The execution context the document reported from
+    #[serde(skip, default = "NewDocument::unknown_context")]
+    pub execution_context_id: ExecutionContextId,
+}
+
+impl NewDocument {
+    fn unknown_context() -> ExecutionContextId {
+        ExecutionContextId::new(0)
+    }
+}
+
+/// A stream of the documents of a page that fired their `load` event, see
+/// [`Page::new_documents`].
+///
+/// The init script reporting the documents and the binding it reports
+/// through are removed when this is dropped.
+#[derive(Debug)]
+pub struct NewDocumentStream {
+    events: EventStream<EventBindingCalled>,
+    name: String,
+    tab: Arc<PageInner>,
+    _script: InitScriptHandle,
+}
+
+impl Stream for NewDocumentStream {
+    type Item = NewDocument;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            let Some(event) = ready!(self.events.poll_next_unpin(cx)) else {
+                return Poll::Ready(None);
+            };
+            if event.name != self.name {
+                continue;
+            }
+            match serde_json::from_str::<NewDocument>(&event.payload) {
+                Ok(mut document) => {
+                    document.execution_context_id = event.execution_context_id;
+                    return Poll::Ready(Some(document));
+                }
+                Err(err) => tracing::debug!("Invalid new document payload: {err}"),
+            }
+        }
+    }
+}
+
+impl Drop for NewDocumentStream {
+    fn drop(&mut self) {
+        self.tab
+            .execute_detached(RemoveBindingParams::new(self.name.clone()));
+    }
+}
+
+/// A message received through `postMessage`, see [`Page::messages`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceivedMessage {
+    /// The data of the message, as JSON
+    pub data: serde_json::Value,
+    /// The origin of the window that sent the message
+    pub origin: String,
+    /// The URL of the document that received the message
+    pub url: String,
+    /// Whether the receiving document belongs to the main frame of the page
+    pub is_main_frame: bool,
+    /// The window that sent the message, relative to the receiving one
+    pub source: MessageSource,
+    /// The execution context of the receiving document
+    #[serde(skip, default = "NewDocument::unknown_context")]
+    pub execution_context_id: ExecutionContextId,
+}
+
+/// The window that sent a [`ReceivedMessage`], relative to the receiving
+/// window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum MessageSource {
+    /// The receiving window itself, like messages of [`Page::post_message`]
+    #[serde(rename = "self")]
+    SameWindow,
+    /// The parent of the receiving frame
+    Parent,
+    /// The window that opened the receiving window
+    Opener,
+    /// The child frame at the index of `window.frames`
+    Child { index: usize },
+    /// A window that is not related to the receiving one, or none
+    Unknown,
+}
+
+/// A stream of the messages received by the windows of a page, see
+/// [`Page::messages`].
+///
+/// Dropping the stream removes the listener from new documents and the
+/// binding it reports through.
+#[derive(Debug)]
+pub struct MessageStream {
+    events: EventStream<EventBindingCalled>,
+    name: String,
+    tab: Arc<PageInner>,
+    _script: InitScriptHandle,
+}
+
+impl Stream for MessageStream {
+    type Item = ReceivedMessage;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            let Some(event) = ready!(self.events.poll_next_unpin(cx)) else {
+                return Poll::Ready(None);
+            };
+            if event.name != self.name {
+                continue;
+            }
+            match serde_json::from_str::<ReceivedMessage>(&event.payload) {
+                Ok(mut message) => {
+                    message.execution_context_id = event.execution_context_id;
+                    return Poll::Ready(Some(message));
+                }
+                Err(err) => tracing::debug!("Invalid message payload: {err}"),
+            }
+        }
+    }
+}
+
+impl Drop for MessageStream {
+    fn drop(&mut self) {
+        self.tab
+            .execute_detached(RemoveBindingParams::new(self.name.clone()));
+    }
+}
+
+/// A font face of a document, see [`Page::loaded_fonts`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FontFaceInfo {
+    /// The font family, without quotes
+    pub family: String,
+    /// The weight descriptor, like `"normal"`, `"700"` or `"100 900"`
+    pub weight: String,
+    /// The style descriptor, like `"normal"` or `"italic"`
+    pub style: String,
+    /// The loading status of the font face
+    pub status: FontFaceStatus,
+}
+
+/// The loading status of a font face, `FontFace.status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FontFaceStatus {
+    /// The font face is not used by the document yet
+    Unloaded,
+    Loading,
+    Loaded,
+    /// The font file could not be loaded or parsed
+    Error,
+}
+
+/// Usage of the JavaScript heap of a page in bytes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeapUsage {
+    /// Used heap size.
+    pub used: f64,
+    /// Allocated heap size.
+    pub total: f64,
+    /// The maximum size the heap can grow to, if reported by the browser
+    /// (`performance.memory.jsHeapSizeLimit`).
+    pub limit: Option<f64>,
+}
+
 /// Page screenshot parameters with extra options.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct ScreenshotParams {
     /// Chrome DevTools Protocol screenshot options.
     pub cdp_params: CaptureScreenshotParams,
@@ -1274,6 +4421,15 @@ pub struct ScreenshotParams {
     pub full_page: Option<bool>,
     /// Make the background transparent (png only).
     pub omit_background: Option<bool>,
+    /// Capture a full page screenshot in viewport sized tiles that are
+    /// stitched together (png only, needs `full_page`).
+    pub stitched: Option<bool>,
+    /// Hide fixed and sticky elements in all but the first tile of a stitched
+    /// screenshot.
+    pub hide_fixed_elements: Option<bool>,
+    /// Clip the screenshot to the bounds of this element, resolved at capture
+    /// time.
+    pub clip_element: Option<RemoteObjectId>,
 }
 
 impl ScreenshotParams {
@@ -1285,6 +4441,14 @@ impl ScreenshotParams {
         self.full_page.unwrap_or(false)
     }
 
+    pub(crate) fn stitched(&self) -> bool {
+        self.stitched.unwrap_or(false)
+    }
+
+    pub(crate) fn hide_fixed_elements(&self) -> bool {
+        self.hide_fixed_elements.unwrap_or(false)
+    }
+
     pub(crate) fn omit_background(&self) -> bool {
         self.omit_background.unwrap_or(false)
             && self
@@ -1301,6 +4465,9 @@ pub struct ScreenshotParamsBuilder {
     cdp_params: CaptureScreenshotParams,
     full_page: Option<bool>,
     omit_background: Option<bool>,
+    stitched: Option<bool>,
+    hide_fixed_elements: Option<bool>,
+    clip_element: Option<RemoteObjectId>,
 }
 
 impl ScreenshotParamsBuilder {
@@ -1310,7 +4477,9 @@ impl ScreenshotParamsBuilder {
         self
     }
 
-    /// Compression quality from range [0..100] (jpeg only).
+    /// Compression quality from range [0..100] (jpeg and webp only).
+    ///
+    /// Taking the screenshot fails if the format is png.
     pub fn quality(mut self, quality: impl Into<i64>) -> Self {
         self.cdp_params.quality = Some(quality.into());
         self
@@ -1322,13 +4491,33 @@ impl ScreenshotParamsBuilder {
         self
     }
 
+    /// Capture the screenshot of the region of the `element` only.
+    ///
+    /// The bounds of the element are resolved when the screenshot is taken,
+    /// so this overrides any [`ScreenshotParamsBuilder::clip`].
+    pub fn clip_element(mut self, element: &Element) -> Self {
+        self.clip_element = Some(element.remote_object_id.clone());
+        self
+    }
+
+    /// Optimize the image encoding for speed rather than size (defaults to
+    /// false).
+    pub fn optimize_for_speed(mut self, optimize_for_speed: impl Into<bool>) -> Self {
+        self.cdp_params.optimize_for_speed = Some(optimize_for_speed.into());
+        self
+    }
+
     /// Capture the screenshot from the surface, rather than the view (defaults to true).
     pub fn from_surface(mut self, from_surface: impl Into<bool>) -> Self {
         self.cdp_params.from_surface = Some(from_surface.into());
         self
     }
 
-    /// Capture the screenshot beyond the viewport (defaults to false).
+    /// Capture the screenshot beyond the viewport.
+    ///
+    /// Full page and element screenshots do so by default if the browser
+    /// supports it, instead of resizing the viewport. Set to `false` to
+    /// resize the viewport regardless.
     pub fn capture_beyond_viewport(mut self, capture_beyond_viewport: impl Into<bool>) -> Self {
         self.cdp_params.capture_beyond_viewport = Some(capture_beyond_viewport.into());
         self
@@ -1346,11 +4535,35 @@ impl ScreenshotParamsBuilder {
         self
     }
 
+    /// Capture a full page screenshot in viewport sized tiles that are
+    /// stitched together instead of resizing the viewport to the whole page.
+    ///
+    /// This works for pages that exceed the maximum texture size of the GPU
+    /// (e.g. pages taller than ~16k pixels), for which a regular full page
+    /// screenshot fails or contains blank regions. The tiles are stitched on
+    /// a canvas of the page, so the image can't exceed the size limit of
+    /// canvases (32767 pixels tall in Chrome). Only png is supported and
+    /// [`ScreenshotParamsBuilder::full_page`] must be enabled too.
+    pub fn stitched(mut self, stitched: impl Into<bool>) -> Self {
+        self.stitched = Some(stitched.into());
+        self
+    }
+
+    /// Hide fixed and sticky elements in all but the first tile of a stitched
+    /// screenshot, so that headers don't repeat in every tile.
+    pub fn hide_fixed_elements(mut self, hide_fixed_elements: impl Into<bool>) -> Self {
+        self.hide_fixed_elements = Some(hide_fixed_elements.into());
+        self
+    }
+
     pub fn build(self) -> ScreenshotParams {
         ScreenshotParams {
             cdp_params: self.cdp_params,
             full_page: self.full_page,
             omit_background: self.omit_background,
+            stitched: self.stitched,
+            hide_fixed_elements: self.hide_fixed_elements,
+            clip_element: self.clip_element,
         }
     }
 }
@@ -0,0 +1,353 @@
+//! Retrying operations that failed with transient CDP errors, see
+//! [`Page::set_retry_policy`](crate::Page::set_retry_policy).
+
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use crate::error::{CdpError, Result};
+
+/// Messages of CDP errors that go away once the page settles, like a node
+/// that is looked up while its document is replaced or an execution context
+/// that is replaced by a navigation.
+///
+/// All but `Execution context was destroyed` are reported before a command
+/// had any effect. That one is also reported if the context is destroyed
+/// while a promise is awaited, after the script ran, so evaluations that
+/// await promises are not retried on it.
+pub const TRANSIENT_ERROR_MESSAGES: &[&str] = &[
+    "Execution context was destroyed",
+    "Cannot find context with specified id",
+    "Cannot find default execution context",
+    "Could not find node with given id",
+    "No node with given id found",
+];
+
+/// The JSON-RPC code the browser reports the [`TRANSIENT_ERROR_MESSAGES`]
+/// with.
+pub const SERVER_ERROR_CODE: i64 = -32000;
+
+/// Whether the `err` is one of the [`TRANSIENT_ERROR_MESSAGES`], the default
+/// classifier of a [`RetryPolicy`].
+pub fn is_transient_error(err: &CdpError) -> bool {
+    let message = match err {
+        CdpError::Chrome(err) if err.code == SERVER_ERROR_CODE => &err.message,
+        CdpError::ChromeMessage(message) => message,
+        _ => return false,
+    };
+    TRANSIENT_ERROR_MESSAGES
+        .iter()
+        .any(|transient| message.contains(transient))
+}
+
+/// Whether the `err` reports that the execution context was destroyed.
+fn is_context_destroyed(err: &CdpError) -> bool {
+    let message = match err {
+        CdpError::Chrome(err) => &err.message,
+        CdpError::ChromeMessage(message) => message,
+        _ => return false,
+    };
+    message.contains(TRANSIENT_ERROR_MESSAGES[0])
+}
+
+type Classifier = Arc<dyn Fn(&CdpError) -> bool + Send + Sync>;
+
+/// How often and how long apart operations that failed with a transient
+/// error are attempted.
+///
+/// The policy of a page applies to its evaluations and to
+/// [`Page::find_element`](crate::Page::find_element) and
+/// [`Page::find_elements`](crate::Page::find_elements), which look up the
+/// document again on every attempt.
+///
+/// An evaluation runs in the current execution context of every attempt. So
+/// evaluations that name their context, by its id or by a `this` object, as
+/// well as the functions element and object handles call are never retried:
+/// their context is gone for good. An evaluation that awaits a promise is
+/// not retried once its context was destroyed, the script may have run
+/// already. Input events, like clicks and key presses, are never retried as
+/// they are not idempotent, nor are
+/// [`Element::dispatch_event`](crate::element::Element::dispatch_event)
+/// and CDP commands sent with [`Page::execute`](crate::Page::execute).
+///
+/// Evaluations that must not run twice should use [`RetryPolicy::none`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use chromiumoxide::page::Page;
+/// # use chromiumoxide::error::Result;
+/// use std::time::Duration;
+/// use chromiumoxide::retry::RetryPolicy;
+///
+/// # async fn demo(page: Page) -> Result<()> {
+///     page.set_retry_policy(
+///         RetryPolicy::new(5).backoff(Duration::from_millis(100), Duration::from_secs(2)),
+///     );
+///     # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    classifier: Classifier,
+}
+
+impl RetryPolicy {
+    /// Attempts an operation up to `max_attempts` times, at least once.
+    pub fn new(max_attempts: usize) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(1),
+            classifier: Arc::new(is_transient_error),
+        }
+    }
+
+    /// Attempts every operation once.
+    pub fn none() -> Self {
+        Self::new(1)
+    }
+
+    /// Waits `initial` before the first retry, doubling the wait for every
+    /// further retry up to `max`.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Retries the errors for which `classifier` returns `true` instead of
+    /// the ones of [`is_transient_error`].
+    pub fn retry_if(
+        mut self,
+        classifier: impl Fn(&CdpError) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.classifier = Arc::new(classifier);
+        self
+    }
+
+    pub fn max_attempts(&self) -> usize {
+        self.max_attempts
+    }
+
+    /// Whether the `err` is retried.
+    pub fn is_retryable(&self, err: &CdpError) -> bool {
+        (self.classifier)(err)
+    }
+
+    /// The wait before the `retry`th retry, starting at 1.
+    fn delay(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_backoff)
+    }
+
+    /// Runs the `operation` until it succeeds, fails with an error that is
+    /// not retryable or was attempted [`RetryPolicy::max_attempts`] times.
+    /// An operation that `awaits_promise` is not retried after its execution
+    /// context was destroyed.
+    ///
+    /// Once out of attempts the error is [`CdpError::RetriesExhausted`]
+    /// unless only one attempt was allowed.
+    pub(crate) async fn run<T, F, Fut>(
+        &self,
+        state: &RetryState,
+        awaits_promise: bool,
+        mut operation: F,
+    ) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempts = Vec::new();
+        loop {
+            let err = match operation().await {
+                Ok(value) => {
+                    if !attempts.is_empty() {
+                        state.recovered.fetch_add(1, Ordering::Relaxed);
+                    }
+                    return Ok(value);
+                }
+                Err(err)
+                    if self.is_retryable(&err)
+                        && !(awaits_promise && is_context_destroyed(&err)) =>
+                {
+                    err
+                }
+                Err(err) => return Err(err),
+            };
+            attempts.push(err.to_string());
+            if attempts.len() >= self.max_attempts {
+                if self.max_attempts == 1 {
+                    return Err(err);
+                }
+                state.exhausted.fetch_add(1, Ordering::Relaxed);
+                return Err(CdpError::RetriesExhausted {
+                    attempts,
+                    source: Box::new(err),
+                });
+            }
+            if attempts.len() == 1 {
+                state.retried.fetch_add(1, Ordering::Relaxed);
+            }
+            state.retries.fetch_add(1, Ordering::Relaxed);
+            futures_timer::Delay::new(self.delay(attempts.len() as u32)).await;
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Attempts every operation once, like [`RetryPolicy::none`].
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The counters of retried operations, see
+/// [`Page::retry_stats`](crate::Page::retry_stats).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetryStats {
+    /// Operations that were attempted more than once
+    pub retried: u64,
+    /// Attempts after the first one, over all operations
+    pub retries: u64,
+    /// Retried operations that eventually succeeded
+    pub recovered: u64,
+    /// Retried operations that ran out of attempts
+    pub exhausted: u64,
+}
+
+/// The retry policy of a page and the counters of its retried operations.
+#[derive(Debug, Default)]
+pub(crate) struct RetryState {
+    policy: RwLock<RetryPolicy>,
+    retried: AtomicU64,
+    retries: AtomicU64,
+    recovered: AtomicU64,
+    exhausted: AtomicU64,
+}
+
+impl RetryState {
+    pub fn policy(&self) -> RetryPolicy {
+        self.policy.read().unwrap().clone()
+    }
+
+    pub fn set_policy(&self, policy: RetryPolicy) {
+        *self.policy.write().unwrap() = policy;
+    }
+
+    pub fn stats(&self) -> RetryStats {
+        RetryStats {
+            retried: self.retried.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+            recovered: self.recovered.load(Ordering::Relaxed),
+            exhausted: self.exhausted.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transient() -> CdpError {
+        CdpError::Chrome(chromiumoxide_types::Error {
+            code: SERVER_ERROR_CODE,
+            message: "Execution context was destroyed.".to_string(),
+        })
+    }
+
+    #[test]
+    fn classifies_transient_errors() {
+        assert!(is_transient_error(&transient()));
+        assert!(is_transient_error(&CdpError::msg(
+            "Could not find node with given id"
+        )));
+        assert!(!is_transient_error(&CdpError::msg("Node is detached")));
+        assert!(!is_transient_error(&CdpError::Timeout));
+
+        assert!(is_context_destroyed(&transient()));
+        assert!(is_context_destroyed(&CdpError::msg(
+            "Execution context was destroyed."
+        )));
+        assert!(!is_context_destroyed(&CdpError::msg(
+            "Could not find node with given id"
+        )));
+    }
+
+    #[test]
+    fn backs_off_exponentially() {
+        let policy =
+            RetryPolicy::new(10).backoff(Duration::from_millis(10), Duration::from_millis(50));
+        let delays: Vec<_> = (1..=5)
+            .map(|retry| policy.delay(retry).as_millis())
+            .collect();
+        assert_eq!(delays, [10, 20, 40, 50, 50]);
+    }
+
+    #[test]
+    fn retries_until_success_or_exhaustion() {
+        let policy = RetryPolicy::new(3).backoff(Duration::ZERO, Duration::ZERO);
+        let state = RetryState::default();
+
+        let mut calls = 0;
+        let value = futures::executor::block_on(policy.run(&state, false, || {
+            calls += 1;
+            let result = if calls < 2 {
+                Err(transient())
+            } else {
+                Ok(calls)
+            };
+            async move { result }
+        }))
+        .unwrap();
+        assert_eq!(value, 2);
+
+        let err = futures::executor::block_on(
+            policy.run(&state, false, || async { Err::<(), _>(transient()) }),
+        )
+        .unwrap_err();
+        let CdpError::RetriesExhausted { attempts, .. } = err else {
+            panic!("expected exhausted retries, got {err:?}");
+        };
+        assert_eq!(attempts.len(), 3);
+
+        let err = futures::executor::block_on(
+            policy.run(&state, false, || async { Err::<(), _>(CdpError::Timeout) }),
+        )
+        .unwrap_err();
+        assert!(matches!(err, CdpError::Timeout));
+
+        // the script may have run before its context was destroyed
+        let err = futures::executor::block_on(
+            policy.run(&state, true, || async { Err::<(), _>(transient()) }),
+        )
+        .unwrap_err();
+        assert!(matches!(err, CdpError::Chrome(_)));
+
+        assert_eq!(
+            state.stats(),
+            RetryStats {
+                retried: 2,
+                retries: 3,
+                recovered: 1,
+                exhausted: 1,
+            }
+        );
+    }
+}
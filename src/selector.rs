@@ -0,0 +1,107 @@
+//! Selectors that pierce frames, see
+//! [`Page::deep_query_selector`](crate::Page::deep_query_selector).
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{CdpError, Result};
+
+/// Separates the segments of a [`DeepSelector`].
+pub const FRAME_SEPARATOR: &str = ">>>";
+
+/// A CSS selector split into segments at [`FRAME_SEPARATOR`], like
+/// `iframe#payment >>> input[name=card]`.
+///
+/// Every segment but the last selects the `<iframe>` or `<frame>` elements
+/// in whose documents the next segment is matched. The separator is not
+/// recognized inside quotes, so attribute values may contain it.
+///
+/// # Example
+///
+/// ```
+/// use chromiumoxide::selector::DeepSelector;
+///
+/// let selector: DeepSelector = "iframe#outer >>> iframe >>> button".parse().unwrap();
+/// assert_eq!(selector.segments(), ["iframe#outer", "iframe", "button"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DeepSelector {
+    segments: Vec<String>,
+}
+
+impl DeepSelector {
+    /// Splits the `selector` into its segments, failing if one is empty.
+    pub fn parse(selector: &str) -> Result<Self> {
+        let mut segments = Vec::new();
+        let mut quote = None;
+        let mut start = 0;
+        let mut chars = selector.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match (quote, c) {
+                (Some(_), '\\') => {
+                    chars.next();
+                }
+                (Some(q), c) if c == q => quote = None,
+                (None, '"' | '\'') => quote = Some(c),
+                (None, '>') if selector[i..].starts_with(FRAME_SEPARATOR) => {
+                    segments.push(selector[start..i].trim().to_string());
+                    start = i + FRAME_SEPARATOR.len();
+                    chars.nth(FRAME_SEPARATOR.len() - 2);
+                }
+                _ => {}
+            }
+        }
+        segments.push(selector[start..].trim().to_string());
+        if let Some(index) = segments.iter().position(String::is_empty) {
+            return Err(CdpError::msg(format!(
+                "Segment {index} of the deep selector `{selector}` is empty"
+            )));
+        }
+        Ok(Self { segments })
+    }
+
+    /// The CSS selectors of the segments, outermost frame first.
+    pub fn segments(&self) -> &[String] {
+        &self.segments
+    }
+}
+
+impl FromStr for DeepSelector {
+    type Err = CdpError;
+
+    fn from_str(selector: &str) -> Result<Self> {
+        Self::parse(selector)
+    }
+}
+
+impl fmt::Display for DeepSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.segments.join(&format!(" {FRAME_SEPARATOR} ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_outside_of_quotes() {
+        let selector =
+            DeepSelector::parse(r#"iframe[title=">>>"] >>>a[title='it\'s >>>']"#).unwrap();
+        assert_eq!(
+            selector.segments(),
+            [r#"iframe[title=">>>"]"#, r"a[title='it\'s >>>']"]
+        );
+        assert_eq!(
+            DeepSelector::parse("div > p").unwrap().segments(),
+            ["div > p"]
+        );
+    }
+
+    #[test]
+    fn rejects_empty_segments() {
+        assert!(DeepSelector::parse("iframe >>> ").is_err());
+        assert!(DeepSelector::parse(">>> input").is_err());
+        assert!(DeepSelector::parse("").is_err());
+    }
+}
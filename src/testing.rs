@@ -0,0 +1,284 @@
+//! Helpers to run tests against a real browser and deterministic pages.
+//!
+//! Requires the `testing` feature. Tests share a single headless browser per
+//! process, which is launched by the first test that needs it and driven on a
+//! background runtime, so tests can use plain `#[test]` functions, see
+//! [`browser_test!`](crate::browser_test).
+//!
+//! ```no_run
+//! use chromiumoxide::testing::StaticServer;
+//!
+//! chromiumoxide::browser_test! {
+//!     async fn reads_the_title(page) {
+//!         let (server, _) = StaticServer::serve([(
+//!             "/",
+//!             "text/html",
+//!             "<title>fixture</title>",
+//!         )])?;
+//!         page.goto_fixture(&server, "/").await?;
+//!         assert_eq!(page.get_title().await?.as_deref(), Some("fixture"));
+//!         Ok(())
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::thread::JoinHandle;
+
+use futures::StreamExt;
+
+use crate::error::Result;
+use crate::{Browser, BrowserConfig, Page};
+
+/// Runs the future to completion on the runtime the crate is built for.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "async-std-runtime")] {
+            async_std::task::block_on(future)
+        } else if #[cfg(feature = "tokio-runtime")] {
+            runtime().block_on(future)
+        }
+    }
+}
+
+#[cfg(all(feature = "tokio-runtime", not(feature = "async-std-runtime")))]
+fn runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .expect("failed to build the test runtime")
+    })
+}
+
+/// Returns the headless browser shared by all tests of the process,
+/// launching it on first use.
+///
+/// # Panics
+///
+/// If the browser can not be launched.
+pub fn shared_browser() -> &'static Browser {
+    static BROWSER: OnceLock<Browser> = OnceLock::new();
+    BROWSER.get_or_init(|| {
+        block_on(async {
+            let config = BrowserConfig::builder()
+                .no_sandbox()
+                .build()
+                .expect("invalid browser config");
+            let (browser, mut handler) = Browser::launch(config)
+                .await
+                .expect("failed to launch the test browser");
+            let drive = async move { while handler.next().await.is_some() {} };
+            cfg_if::cfg_if! {
+                if #[cfg(feature = "async-std-runtime")] {
+                    async_std::task::spawn(drive);
+                } else if #[cfg(feature = "tokio-runtime")] {
+                    runtime().spawn(drive);
+                }
+            }
+            browser
+        })
+    })
+}
+
+/// Opens a new blank page in the [`shared_browser`].
+pub async fn new_page() -> Result<Page> {
+    shared_browser().new_page("about:blank").await
+}
+
+/// Defines a `#[test]` that runs its body with a new page of the
+/// [`shared_browser`](crate::testing::shared_browser), which is closed
+/// afterwards.
+///
+/// The body returns a `Result<(), Box<dyn Error>>`, the test fails if it
+/// returns an error.
+#[macro_export]
+macro_rules! browser_test {
+    ($(#[$attr:meta])* async fn $name:ident($page:ident) $body:block) => {
+        $(#[$attr])*
+        #[test]
+        fn $name() {
+            $crate::testing::block_on(async {
+                let $page = $crate::testing::new_page()
+                    .await
+                    .expect("failed to open a test page");
+                let page = $page.clone();
+                let result: ::std::result::Result<(), ::std::boxed::Box<dyn ::std::error::Error>> =
+                    async move $body.await;
+                let _ = page.close().await;
+                result.unwrap();
+            })
+        }
+    };
+}
+
+/// A minimal HTTP server that serves fixed responses for tests.
+///
+/// The server runs on background threads and stops when this is dropped.
+#[derive(Debug)]
+pub struct StaticServer {
+    addr: SocketAddr,
+    shutdown: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[derive(Debug)]
+struct Route {
+    content_type: String,
+    body: Vec<u8>,
+}
+
+impl StaticServer {
+    /// Serves the `routes`, given as `(path, content_type, body)`, on a free
+    /// port of the loopback interface and returns the server with its base
+    /// URL, e.g. `http://127.0.0.1:41234`.
+    ///
+    /// Requests for other paths are answered with `404 Not Found`. Query
+    /// strings are ignored when matching paths.
+    pub fn serve<I, P, C, B>(routes: I) -> io::Result<(Self, String)>
+    where
+        I: IntoIterator<Item = (P, C, B)>,
+        P: Into<String>,
+        C: Into<String>,
+        B: Into<Vec<u8>>,
+    {
+        let routes: Arc<HashMap<String, Route>> = Arc::new(
+            routes
+                .into_iter()
+                .map(|(path, content_type, body)| {
+                    (
+                        path.into(),
+                        Route {
+                            content_type: content_type.into(),
+                            body: body.into(),
+                        },
+                    )
+                })
+                .collect(),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let stop = Arc::clone(&shutdown);
+        let thread = std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                let routes = Arc::clone(&routes);
+                std::thread::spawn(move || {
+                    if let Err(err) = respond(stream, &routes) {
+                        tracing::debug!("Failed to serve test fixture: {err}");
+                    }
+                });
+            }
+        });
+
+        let server = Self {
+            addr,
+            shutdown,
+            thread: Some(thread),
+        };
+        let base_url = server.base_url();
+        Ok((server, base_url))
+    }
+
+    /// The base URL of the server, without trailing slash.
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// The URL of the `path` on this server.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url(), path.trim_start_matches('/'))
+    }
+}
+
+impl Drop for StaticServer {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+        // wake up the blocking accept
+        let _ = TcpStream::connect(self.addr);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Answers a single request, the connection is closed afterwards.
+fn respond(stream: TcpStream, routes: &HashMap<String, Route>) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // skip the headers
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let path = target.split(['?', '#']).next().unwrap_or("/");
+    let mut stream = stream;
+    match routes.get(path) {
+        Some(route) => {
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                route.content_type,
+                route.body.len()
+            )?;
+            stream.write_all(&route.body)?;
+        }
+        None => {
+            stream.write_all(
+                b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            )?;
+        }
+    }
+    stream.flush()
+}
+
+impl Page {
+    /// Navigates to the `path` of the test `server`.
+    pub async fn goto_fixture(&self, server: &StaticServer, path: &str) -> Result<&Self> {
+        self.goto(server.url(path)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    #[test]
+    fn serve_routes() {
+        let (server, base_url) =
+            StaticServer::serve([("/index.html", "text/html", "<p>hi</p>")]).unwrap();
+        assert_eq!(server.url("/index.html"), format!("{base_url}/index.html"));
+
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(server.addr).unwrap();
+            write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response = get("/index.html?query");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Type: text/html\r\n"));
+        assert!(response.ends_with("\r\n\r\n<p>hi</p>"));
+        assert!(get("/missing").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
@@ -14,6 +14,17 @@ pub(crate) async fn write<P: AsRef<Path> + Unpin, C: AsRef<[u8]>>(
     }
 }
 
+/// Read a file into a string with configured runtime
+pub(crate) async fn read_to_string<P: AsRef<Path> + Unpin>(path: P) -> std::io::Result<String> {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "async-std-runtime")] {
+            async_std::fs::read_to_string(path.as_ref()).await
+        } else if #[cfg(feature = "tokio-runtime")] {
+            tokio::fs::read_to_string(path.as_ref()).await
+        }
+    }
+}
+
 /// Canonicalize path
 ///
 /// Chromium sandboxing does not support Window UNC paths which are used by Rust
@@ -54,6 +65,53 @@ pub(crate) async fn canonicalize_except_snap(path: PathBuf) -> std::io::Result<P
     })
 }
 
+/// Resolves to `None` if `fut` did not complete within `timeout`.
+pub(crate) async fn with_timeout<F: std::future::Future>(
+    fut: F,
+    timeout: std::time::Duration,
+) -> Option<F::Output> {
+    let fut = std::pin::pin!(fut);
+    match futures::future::select(fut, futures_timer::Delay::new(timeout)).await {
+        futures::future::Either::Left((output, _)) => Some(output),
+        futures::future::Either::Right(_) => None,
+    }
+}
+
+/// Returns a JS expression for a promise that resolves to `true` once the
+/// position returned by the `sample` expression (`[x, y]`) stayed the same for
+/// two animation frames or a `scrollend` event fired, and to `false` after
+/// `timeout`.
+pub(crate) fn wait_for_scroll_end(sample: &str, timeout: std::time::Duration) -> String {
+    format!(
+        "new Promise(resolve => {{
+            let last = null, stable = 0, done = false;
+            const finish = settled => {{
+                if (done) return;
+                done = true;
+                clearTimeout(timer);
+                removeEventListener('scrollend', onScrollEnd, true);
+                resolve(settled);
+            }};
+            const onScrollEnd = () => finish(true);
+            addEventListener('scrollend', onScrollEnd, true);
+            const timer = setTimeout(() => finish(false), {timeout});
+            const step = () => {{
+                if (done) return;
+                const pos = {sample};
+                if (last && pos[0] === last[0] && pos[1] === last[1]) {{
+                    if (++stable >= 2) return finish(true);
+                }} else {{
+                    stable = 0;
+                }}
+                last = pos;
+                requestAnimationFrame(step);
+            }};
+            requestAnimationFrame(step);
+        }})",
+        timeout = timeout.as_millis(),
+    )
+}
+
 pub(crate) mod base64 {
     use base64::engine::general_purpose::STANDARD;
     use base64::{DecodeError, Engine};
@@ -62,6 +120,11 @@ pub(crate) mod base64 {
     pub fn decode<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, DecodeError> {
         STANDARD.decode(input)
     }
+
+    /// Encode base64 using the standard alphabet and padding
+    pub fn encode<T: AsRef<[u8]>>(input: T) -> String {
+        STANDARD.encode(input)
+    }
 }
 
 /// Creates a javascript function string as `(<function>)("<param 1>", "<param
@@ -99,6 +162,49 @@ pub fn is_likely_js_function(function: impl AsRef<str>) -> bool {
     false
 }
 
+/// Whether the dotted version `have`, like `1.3` or `120.0.6099.71`, is at
+/// least `needs`. Missing or non-numeric segments count as `0`.
+pub(crate) fn version_at_least(have: &str, needs: &str) -> bool {
+    fn segments(version: &str) -> Vec<u64> {
+        version
+            .trim()
+            .split('.')
+            .map(|segment| segment.parse().unwrap_or(0))
+            .collect()
+    }
+    let (have, needs) = (segments(have), segments(needs));
+    for i in 0..have.len().max(needs.len()) {
+        let (have, needs) = (
+            have.get(i).copied().unwrap_or(0),
+            needs.get(i).copied().unwrap_or(0),
+        );
+        if have != needs {
+            return have > needs;
+        }
+    }
+    true
+}
+
+/// Converts a `data-*` key from Rust's `snake_case`, or the attribute's
+/// `kebab-case`, to the `camelCase` property name used by `dataset`.
+///
+/// `user_id` and `user-id` -> `userId`, `camelCase` keys are kept.
+pub(crate) fn dataset_key(key: &str) -> String {
+    let mut camel = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        if c == '_' || c == '-' {
+            upper = true;
+        } else if upper {
+            camel.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
+
 /// This attempts to strip any leading pair of parentheses from the input
 ///
 /// `()=>` -> `=>`
@@ -130,6 +236,15 @@ fn skip_args(input: &mut &str) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn dataset_keys() {
+        assert_eq!(dataset_key("user_id"), "userId");
+        assert_eq!(dataset_key("user-id"), "userId");
+        assert_eq!(dataset_key("userId"), "userId");
+        assert_eq!(dataset_key("sort_order_2"), "sortOrder2");
+        assert_eq!(dataset_key("id"), "id");
+    }
+
     #[test]
     fn is_js_function() {
         assert!(is_likely_js_function("function abc() {}"));
@@ -139,4 +254,14 @@ mod tests {
         assert!(is_likely_js_function("((abc), (def)) => {}"));
         assert!(is_likely_js_function("() => Promise.resolve(100 / 25)"));
     }
+
+    #[test]
+    fn compare_versions() {
+        assert!(version_at_least("1.3", "1.3"));
+        assert!(version_at_least("1.3", "1.2.9"));
+        assert!(version_at_least("120.0.6099.71", "120"));
+        assert!(version_at_least("1.10", "1.9"));
+        assert!(!version_at_least("1.3", "1.3.1"));
+        assert!(!version_at_least("79.0", "120"));
+    }
 }
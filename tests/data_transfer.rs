@@ -0,0 +1,103 @@
+#![cfg(feature = "testing")]
+
+use chromiumoxide::browser_test;
+use chromiumoxide::js::data_transfer::DataTransferInit;
+
+/// A drop zone and an editor that read the files they receive, like the
+/// upload widgets of web apps do, and resolve `window.received` with the
+/// first one.
+const DROP_ZONE: &str = r#"
+    <div id="zone" style="width: 200px; height: 200px"></div>
+    <div id="editor" contenteditable></div>
+    <script>
+        let receive;
+        window.received = new Promise(resolve => receive = resolve);
+        const record = async (event, files) => {
+            event.preventDefault();
+            const file = files[0];
+            const bytes = new Uint8Array(await file.arrayBuffer());
+            receive({
+                type: event.type,
+                name: file.name,
+                mime: file.type,
+                length: bytes.length,
+                sum: bytes.reduce((sum, byte) => (sum + byte) % 65521, 0),
+            });
+        };
+        const zone = document.querySelector('#zone');
+        zone.addEventListener('dragover', event => event.preventDefault());
+        zone.addEventListener('drop', event => record(event, event.dataTransfer.files));
+        document.querySelector('#editor')
+            .addEventListener('paste', event => record(event, event.clipboardData.files));
+    </script>
+"#;
+
+/// The bytes of a fake PNG image, with the PNG signature and every byte
+/// value.
+fn image() -> (Vec<u8>, u32) {
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    png.extend((0..64 * 1024).map(|i| (i % 256) as u8));
+    let sum = png.iter().fold(0, |sum, &byte| (sum + byte as u32) % 65521);
+    (png, sum)
+}
+
+browser_test! {
+    async fn pasted_images_reach_the_page(page) {
+        page.set_content(DROP_ZONE).await?;
+        let (png, sum) = image();
+        let canceled = page
+            .find_element("#editor")
+            .await?
+            .dispatch_paste(DataTransferInit::new().file("image.png", "image/png", &png))
+            .await?;
+        assert!(canceled);
+
+        let received: serde_json::Value = page
+            .evaluate("window.received")
+            .await?
+            .into_value()?;
+        assert_eq!(
+            received,
+            serde_json::json!({
+                "type": "paste",
+                "name": "image.png",
+                "mime": "image/png",
+                "length": png.len(),
+                "sum": sum,
+            })
+        );
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn dropped_images_reach_the_page(page) {
+        page.set_content(DROP_ZONE).await?;
+        let (png, sum) = image();
+        let canceled = page
+            .find_element("#zone")
+            .await?
+            .dispatch_drop(
+                DataTransferInit::new().file("image.png", "image/png", &png),
+                None,
+            )
+            .await?;
+        assert!(canceled);
+
+        let received: serde_json::Value = page
+            .evaluate("window.received")
+            .await?
+            .into_value()?;
+        assert_eq!(
+            received,
+            serde_json::json!({
+                "type": "drop",
+                "name": "image.png",
+                "mime": "image/png",
+                "length": png.len(),
+                "sum": sum,
+            })
+        );
+        Ok(())
+    }
+}
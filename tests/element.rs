@@ -0,0 +1,126 @@
+#![cfg(feature = "testing")]
+
+use std::time::Duration;
+
+use chromiumoxide::browser_test;
+
+browser_test! {
+    async fn element_debug_shows_class_and_selector(page) {
+        page.set_content(r#"<div id="main" class="container">Sign in</div>"#)
+            .await?;
+        let element = page.find_element("div").await?;
+        assert_eq!(
+            format!("{element:?}"),
+            "Element(HTMLDivElement div#main.container)"
+        );
+        assert_eq!(element.to_string(), "div#main.container");
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn type_text_dispatches_key_events(page) {
+        page.set_content("<input>").await?;
+        page.evaluate(
+            "window.keys = [];
+            document.querySelector('input').addEventListener('keydown', (e) => keys.push(e.key));",
+        )
+        .await?;
+        let input = page.find_element("input").await?;
+        input.type_text("Hi é").await?;
+        assert_eq!(input.property("value").await?, Some("Hi é".into()));
+        let keys: Vec<String> = page.evaluate("window.keys").await?.into_value()?;
+        assert_eq!(keys, vec!["H", "i", " "]);
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn selection_range_replaces_part_of_value(page) {
+        page.set_content(r#"<input value="hello world"><input type="number">"#)
+            .await?;
+        let input = page.find_element("input").await?;
+        assert_eq!(input.set_selection_range(6, 11).await?, "world");
+        input.type_text("there").await?;
+        assert_eq!(input.property("value").await?, Some("hello there".into()));
+        assert_eq!(input.select_text().await?, "hello there");
+
+        let number = page.find_element("input[type=number]").await?;
+        assert!(number.select_text().await.is_err());
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn class_list_manipulates_classes(page) {
+        page.set_content(r#"<div class="a b"></div>"#).await?;
+        let div = page.find_element("div").await?;
+        let classes = div.class_list().await?;
+        classes.add(["c", "a"]).await?;
+        classes.remove(["b"]).await?;
+        assert_eq!(classes.to_vec().await?, vec!["a", "c"]);
+        assert!(!classes.toggle("a", None).await?);
+        assert!(classes.toggle("d", Some(true)).await?);
+        assert!(classes.replace("c", "e").await?);
+        assert!(!classes.contains("c").await?);
+        assert_eq!(classes.length().await?, 2);
+        assert_eq!(classes.item(1).await?.as_deref(), Some("d"));
+        assert_eq!(classes.item(5).await?, None);
+        assert_eq!(div.attribute("class").await?.as_deref(), Some("e d"));
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn wait_until_removed_observes_ancestors(page) {
+        use std::time::Duration;
+
+        page.set_content(r#"<div id="modal"><p>Saved</p></div><span>kept</span>"#)
+            .await?;
+        let text = page.find_element("#modal p").await?;
+        page.evaluate("setTimeout(() => document.querySelector('#modal').remove(), 100)")
+            .await?;
+        text.wait_until_removed(Duration::from_secs(5)).await?;
+
+        let kept = page.find_element("span").await?;
+        assert!(matches!(
+            kept.wait_until_removed(Duration::from_millis(100)).await,
+            Err(chromiumoxide::error::CdpError::Timeout)
+        ));
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn handles_print_a_preview(page) {
+        let array = page.js_array("[1, 2, 3]").await?;
+        assert_eq!(array.to_string(), "Array(3)");
+        assert!(format!("{array:?}").starts_with("JsArray(Array(3) "));
+
+        let function = page
+            .js_function("(function add(a, b) {\n  return a + b;\n})")
+            .await?;
+        assert_eq!(function.to_string(), "function add(a, b) {…");
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn dropped_attribute_waits_disconnect_their_observer(page) {
+        page.set_content("<div></div>").await?;
+        let element = page.find_element("div").await?;
+        let pending = "globalThis[Symbol.for('chromiumoxide.waits')]?.size ?? 0";
+        let wait = Box::pin(element.wait_for_class("ready", true, Duration::from_secs(30)));
+        let installed = Box::pin(async {
+            while page.evaluate(pending).await?.into_value::<usize>()? == 0 {}
+            Ok::<_, chromiumoxide::error::CdpError>(())
+        });
+        // the wait is dropped once its observer is installed
+        match futures::future::select(wait, installed).await {
+            futures::future::Either::Right((installed, _)) => installed?,
+            futures::future::Either::Left(_) => panic!("the wait settled"),
+        }
+        while page.evaluate(pending).await?.into_value::<usize>()? != 0 {}
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+#![cfg(feature = "testing")]
+
+use std::time::Duration;
+
+use chromiumoxide::browser_test;
+use chromiumoxide::cdp::js_protocol::runtime::{
+    CallArgument, CallFunctionOnParams, EvaluateParams,
+};
+use chromiumoxide::error::CdpError;
+use chromiumoxide::js::cache::CachePolicy;
+use chromiumoxide::js::JsOptional;
+use chromiumoxide::origin::EvalOriginAllowlist;
+use chromiumoxide::retry::RetryPolicy;
+
+browser_test! {
+    async fn evaluate_non_finite_numbers(page) {
+        let infinity: f64 = page
+            .evaluate("Number.POSITIVE_INFINITY")
+            .await?
+            .into_value()?;
+        assert_eq!(infinity, f64::INFINITY);
+
+        let negative: f64 = page.evaluate("-1 / 0").await?.into_value()?;
+        assert_eq!(negative, f64::NEG_INFINITY);
+
+        let nan: f64 = page.evaluate("NaN").await?.into_value()?;
+        assert!(nan.is_nan());
+
+        let big: u64 = page.evaluate("2n ** 64n - 1n").await?.into_value()?;
+        assert_eq!(big, u64::MAX);
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn js_typeof_of_objects(page) {
+        let object = page.js_object("({})").await?;
+        assert_eq!(object.js_typeof().await?, "object");
+        let callable = page.js_object("class Point {}").await?;
+        assert_eq!(callable.js_typeof().await?, "function");
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn freeze_and_seal_objects(page) {
+        let object = page.js_object("({ a: 1 })").await?;
+        assert!(!object.is_sealed().await?);
+        object.seal().await?;
+        assert!(object.is_sealed().await?);
+        assert!(!object.is_frozen().await?);
+        object.freeze().await?;
+        assert!(object.is_frozen().await?);
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn evaluate_with_this_binds_receiver(page) {
+        page.set_content(r#"<input value="  hello  ">"#).await?;
+        let input = page.find_element("input").await?;
+        let value: String = page
+            .evaluate_with_this(&input.remote_object_id, "this.value.trim()")
+            .await?
+            .into_value()?;
+        assert_eq!(value, "hello");
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn entries_page_bounds_large_objects(page) {
+        use chromiumoxide::js::PropertyOptions;
+
+        let object = page
+            .js_object("Object.fromEntries(Array.from({ length: 5000 }, (_, i) => [i, i]))")
+            .await?;
+        let first = object
+            .entries_page(PropertyOptions::new().limit(100))
+            .await?;
+        assert_eq!(first.entries.len(), 100);
+        assert_eq!(first.total, 5000);
+        assert!(first.truncated);
+        assert_eq!(first.entries[0].0, "0");
+
+        let last = object
+            .entries_page(PropertyOptions::new().offset(4950).limit(100))
+            .await?;
+        assert_eq!(last.entries.len(), 50);
+        assert!(!last.truncated);
+        let value: u32 = last.entries[49].1.clone()?.into_value()?;
+        assert_eq!(value, 4999);
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn script_from_file_names_exception_frames(page) {
+        use chromiumoxide::js::script::JsScript;
+
+        let path = std::env::temp_dir().join("chromiumoxide-failing-script.js");
+        std::fs::write(&path, "const a = 1;\n\nthrow new Error('boom');\n")?;
+        let script = JsScript::from_file(&path).await?.function_body(true);
+        let result = page.evaluate_script(&script).await;
+        let _ = std::fs::remove_file(&path);
+
+        let Err(CdpError::JavascriptException(details)) = result else {
+            panic!("expected an exception, got {result:?}");
+        };
+        let frames = details.stack_trace.expect("stack trace").call_frames;
+        let frame = &frames[0];
+        assert!(
+            frame.url.ends_with("/chromiumoxide-failing-script.js"),
+            "{}",
+            frame.url
+        );
+        // zero based, the wrapping function's line is not counted
+        assert_eq!(frame.line_number, 2);
+        let description = details.exception.and_then(|e| e.description).unwrap_or_default();
+        assert!(description.contains("chromiumoxide-failing-script.js:3:"), "{description}");
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn retries_skip_explicit_context(page) {
+        page.set_retry_policy(RetryPolicy::new(3).backoff(Duration::ZERO, Duration::ZERO));
+        let context_id = page.execution_context().await?;
+        page.goto("about:blank").await?;
+        let stale = EvaluateParams::builder()
+            .expression("1")
+            .context_id(context_id.unwrap())
+            .build()
+            .unwrap();
+
+        // the context is gone for good, another attempt can't succeed
+        let err = page.evaluate_expression(stale).await.unwrap_err();
+        assert!(matches!(err, CdpError::Chrome(_)), "{err:?}");
+        assert_eq!(page.retry_stats().retries, 0);
+
+        let one: u32 = page.evaluate("1").await?.into_value()?;
+        assert_eq!(one, 1);
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn optional_arguments_pass_undefined(page) {
+        let call = |argument: CallArgument| {
+            CallFunctionOnParams::builder()
+                .function_declaration("(value = 'default') => value")
+                .argument(argument)
+                .build()
+                .unwrap()
+        };
+        let undefined: String = page
+            .evaluate_function(call(JsOptional::<String>(None).into_argument()?))
+            .await?
+            .into_value()?;
+        assert_eq!(undefined, "default");
+
+        let null = CallArgument::builder()
+            .value(serde_json::json!(None::<String>))
+            .build();
+        let null: Option<String> = page.evaluate_function(call(null)).await?.into_value()?;
+        assert_eq!(null, None);
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn eval_cache_is_dropped_on_navigation(page) {
+        page.enable_eval_cache(CachePolicy::default());
+        page.goto("data:text/html,<title>first</title>").await?;
+        let first: String = page.evaluate_cached("document.title").await?.into_value()?;
+        page.goto("data:text/html,<title>second</title>").await?;
+        let second: String = page.evaluate_cached("document.title").await?.into_value()?;
+        assert_eq!((first.as_str(), second.as_str()), ("first", "second"));
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn bytes_round_trip_in_chunks(page) {
+        let data: Vec<u8> = (0..5 * 1024 * 1024).map(|i| (i * 7 % 251) as u8).collect();
+        let len: usize = page
+            .evaluate_function_with_bytes(
+                "(bytes) => { window.roundTrip = bytes; return bytes.length; }",
+                &data,
+                256 * 1024,
+            )
+            .await?
+            .into_value()?;
+        assert_eq!(len, data.len());
+        assert_eq!(page.evaluate_bytes("window.roundTrip").await?, data);
+
+        assert!(page.evaluate_bytes("({ length: 3 })").await.is_err());
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn array_push_exceptions_fail_the_call(page) {
+        // spreading a million arguments exceeds the call stack
+        let result = page
+            .evaluate_function_with_array("(array) => array.length", 0..1_000_000, 1_000_000)
+            .await;
+        assert!(
+            matches!(result, Err(CdpError::JavascriptException(_))),
+            "{result:?}"
+        );
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn limited_values_are_measured_in_the_page(page) {
+        let result = page.evaluate_limited("'x'.repeat(1000)", 100).await;
+        assert!(
+            matches!(result, Err(CdpError::ValueTooLarge { size: 1002, limit: 100 })),
+            "{result:?}"
+        );
+
+        let value: String = page
+            .evaluate_limited("Promise.resolve('chromiumoxide')", 15)
+            .await?
+            .into_value()?;
+        assert_eq!(value, "chromiumoxide");
+
+        let sum: u32 = page
+            .evaluate_limited(
+                CallFunctionOnParams::builder()
+                    .function_declaration("(a, b) => a + b")
+                    .argument(CallArgument::builder().value(1).build())
+                    .argument(CallArgument::builder().value(2).build())
+                    .build()
+                    .unwrap(),
+                1,
+            )
+            .await?
+            .into_value()?;
+        assert_eq!(sum, 3);
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn caught_exceptions_are_described(page) {
+        let result = page
+            .evaluate_handle("(() => { try { null.x; } catch (e) { return e; } })()")
+            .await?;
+        assert!(result.exception().is_some_and(|e| e.starts_with("TypeError")));
+        assert!(page.evaluate_handle("[1, 2]").await?.exception().is_none());
+
+        let array = page.evaluate_handle("[1, 2]").await?.into_js_array(&page)?;
+        let items: Vec<u32> = array.to_vec().await?;
+        assert_eq!(items, [1, 2]);
+        assert!(page
+            .evaluate_handle("({})")
+            .await?
+            .into_js_function(&page)
+            .is_err());
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn object_calls_respect_the_origin_allowlist(page) {
+        page.set_content("<div></div>").await?;
+        let element = page.find_element("div").await?;
+        page.set_eval_origin_allowlist(Some(EvalOriginAllowlist::new(["https://example.com"])));
+        assert!(matches!(
+            element.inner_text().await,
+            Err(CdpError::OriginNotAllowed(_))
+        ));
+        page.set_eval_origin_allowlist(None);
+        element.inner_text().await?;
+        Ok(())
+    }
+}
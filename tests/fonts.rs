@@ -0,0 +1,25 @@
+#![cfg(feature = "testing")]
+
+use std::time::Duration;
+
+use chromiumoxide::browser_test;
+use chromiumoxide::page::FontFaceStatus;
+
+browser_test! {
+    async fn wait_for_fonts_settles_on_failed_font(page) {
+        page.set_content(
+            r#"<style>
+                @font-face { font-family: "Missing"; src: url("http://127.0.0.1:9/missing.woff2"); }
+                p { font-family: "Missing", serif; }
+            </style>
+            <p>Hello</p>"#,
+        )
+        .await?;
+        page.wait_for_fonts(Duration::from_secs(10)).await?;
+        let fonts = page.loaded_fonts().await?;
+        assert_eq!(fonts.len(), 1);
+        assert_eq!(fonts[0].family, "Missing");
+        assert_eq!(fonts[0].status, FontFaceStatus::Error);
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "testing")]
+
+use chromiumoxide::browser_test;
+use chromiumoxide::form::{FieldStatus, FieldTarget, FormField};
+
+browser_test! {
+    async fn fill_form_reports_per_field(page) {
+        page.set_content(
+            r#"<form>
+                <input id="email" type="email" required>
+                <input id="terms" type="checkbox">
+                <select id="plan">
+                    <option value="free">Free</option>
+                    <option value="pro">Professional</option>
+                </select>
+                <input id="avatar" type="file">
+                <div id="host"></div>
+            </form>
+            <script>
+                document.querySelector('#host')
+                    .attachShadow({ mode: 'open' })
+                    .innerHTML = '<input id="nick" value="a">';
+            </script>"#,
+        )
+        .await?;
+        let avatar = std::env::temp_dir().join("chromiumoxide-fill-form.txt");
+        std::fs::write(&avatar, "avatar")?;
+        let terms = page.find_element("#terms").await?;
+
+        let report = page
+            .fill_form(vec![
+                FormField::text("#email", "not an email"),
+                FormField::check(&terms, true),
+                FormField::select_label("#plan", "Professional"),
+                FormField::file("#avatar", [&avatar]),
+                FormField::text(FieldTarget::Deep("#nick".into()), "b").clear_first(false),
+                FormField::text("#missing", "x"),
+                FormField::text("#terms", "x"),
+            ])
+            .await?;
+        let _ = std::fs::remove_file(&avatar);
+
+        let status: Vec<_> = report.fields.iter().map(|field| &field.status).collect();
+        assert_eq!(status[..5], [&FieldStatus::Filled; 5]);
+        assert_eq!(status[5], &FieldStatus::NotFound);
+        assert!(matches!(status[6], FieldStatus::Failed(_)));
+        assert!(report.fields[0].validation_message.is_some());
+        assert_eq!(report.invalid().count(), 1);
+
+        let values: Vec<String> = page
+            .evaluate(
+                "[
+                    document.querySelector('#plan').value,
+                    document.querySelector('#avatar').files[0].name,
+                    document.querySelector('#host').shadowRoot.querySelector('#nick').value,
+                    String(document.querySelector('#terms').checked),
+                ]",
+            )
+            .await?
+            .into_value()?;
+        assert_eq!(values, ["pro", "chromiumoxide-fill-form.txt", "ab", "true"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "testing")]
+
+use chromiumoxide::browser_test;
+
+browser_test! {
+    async fn fragment_scripts_do_not_run(page) {
+        page.set_content("<ul></ul>").await?;
+        let fragment = page
+            .document()
+            .await?
+            .create_fragment_from_html(
+                "<li>one</li><script>window.executed = true</script><li>two<li>three",
+            )
+            .await?;
+        // the unclosed list items are recovered from
+        assert_eq!(fragment.child_element_count().await?, 4);
+
+        let list = page.find_element("ul").await?;
+        list.append_fragment(&fragment).await?;
+        assert_eq!(fragment.child_element_count().await?, 0);
+        assert_eq!(list.find_elements("li").await?.len(), 3);
+
+        let executed: bool = page
+            .evaluate("window.executed === true")
+            .await?
+            .into_value()?;
+        assert!(!executed);
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "testing")]
+
+use chromiumoxide::browser_test;
+
+browser_test! {
+    async fn deep_query_selector_pierces_nested_frames(page) {
+        let inner = r#"<button class='pay'>Pay</button><button class='pay'>Later</button>"#;
+        let outer = format!(r#"<iframe id="inner" srcdoc="{inner}"></iframe>"#).replace('"', "&quot;");
+        page.set_content(format!(
+            r#"<button class="pay">Top</button><iframe id="outer" srcdoc="{outer}"></iframe>"#
+        ))
+        .await?;
+
+        let button = page
+            .deep_query_selector("iframe#outer >>> iframe#inner >>> button.pay")
+            .await?
+            .expect("button in the inner frame");
+        assert_eq!(button.inner_text().await?.as_deref(), Some("Pay"));
+        // evaluated in the inner frame's document
+        let frame_id: String = button
+            .call_js_fn("function() { return this.ownerDocument.defaultView.frameElement.id; }", false)
+            .await?
+            .result
+            .value
+            .and_then(|value| value.as_str().map(str::to_string))
+            .unwrap_or_default();
+        assert_eq!(frame_id, "inner");
+
+        let buttons = page
+            .deep_query_selector_all("#outer >>> #inner >>> button")
+            .await?;
+        assert_eq!(buttons.len(), 2);
+        assert!(page
+            .deep_query_selector("#outer >>> #missing >>> button")
+            .await?
+            .is_none());
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn evaluate_all_frames_collects_in_tree_order(page) {
+        page.set_content(
+            r#"<title>top</title>
+            <iframe srcdoc="<title>first</title><iframe srcdoc='<title>nested</title>'></iframe>"></iframe>
+            <iframe srcdoc="<title>second</title>"></iframe>"#,
+        )
+        .await?;
+        let titles: Vec<(_, String)> = page.evaluate_all_frames("document.title").await?;
+        let titles: Vec<_> = titles.into_iter().map(|(_, title)| title).collect();
+        assert_eq!(titles, ["top", "first", "nested", "second"]);
+        let main_frame = page.mainframe().await?;
+        let counts: Vec<(_, u32)> = page.evaluate_all_frames("frames.length").await?;
+        assert_eq!(Some(&counts[0].0), main_frame.as_ref());
+        assert_eq!(counts[0].1, 2);
+        Ok(())
+    }
+}
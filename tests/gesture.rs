@@ -0,0 +1,29 @@
+#![cfg(feature = "testing")]
+
+use chromiumoxide::browser_test;
+
+browser_test! {
+    async fn with_user_gesture_activates_page(page) {
+        let activated: bool = page
+            .evaluate("navigator.userActivation.hasBeenActive")
+            .await?
+            .into_value()?;
+        assert!(!activated);
+
+        let active: bool = page
+            .with_user_gesture(|| async {
+                // checked after an await, where the `userGesture` flag of the
+                // evaluation no longer applies
+                page.evaluate(
+                    "new Promise(resolve => setTimeout(resolve, 0))
+                        .then(() => navigator.userActivation.isActive)",
+                )
+                .await?
+                .into_value()
+                .map_err(Into::into)
+            })
+            .await?;
+        assert!(active);
+        Ok(())
+    }
+}
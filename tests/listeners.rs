@@ -0,0 +1,57 @@
+#![cfg(feature = "testing")]
+
+use chromiumoxide::browser_test;
+
+browser_test! {
+    async fn listener_census_diff_reveals_leaks(page) {
+        page.set_content(
+            r#"<button id="open">Open</button>
+            <script>
+                function onResize() {}
+                document.querySelector('#open').addEventListener('click', () => {
+                    // leaks a listener on every click
+                    window.addEventListener('resize', onResize.bind(null));
+                });
+            </script>"#,
+        )
+        .await?;
+        let before = page.event_listener_census(None).await?;
+        assert!(!before.truncated);
+        assert_eq!(before.by_type.get("click"), Some(&1));
+
+        let button = page.find_element("#open").await?;
+        for _ in 0..3 {
+            button.click().await?;
+        }
+        let after = page.event_listener_census(None).await?;
+        let diff = before.diff(&after);
+        assert_eq!(diff.added_by_type.get("resize"), Some(&3));
+        assert!(diff.removed_by_type.is_empty());
+        assert_eq!(after.total, before.total + 3);
+
+        let scoped = page
+            .event_listener_census(Some(vec![button.remote_object_id.clone()]))
+            .await?;
+        assert_eq!(scoped.targets, 1);
+        assert_eq!(scoped.total, 1);
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn new_document_bindings_are_removed_on_drop(page) {
+        const BINDINGS: &str = "Object.getOwnPropertyNames(window)
+            .filter(name => name.startsWith('__chromiumoxide_new_document_'))
+            .length";
+        let documents = page.new_documents().await?;
+        page.reload().await?;
+        let bindings: usize = page.evaluate(BINDINGS).await?.into_value()?;
+        assert_eq!(bindings, 1);
+
+        drop(documents);
+        page.reload().await?;
+        let bindings: usize = page.evaluate(BINDINGS).await?.into_value()?;
+        assert_eq!(bindings, 0);
+        Ok(())
+    }
+}
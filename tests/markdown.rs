@@ -0,0 +1,83 @@
+#![cfg(feature = "testing")]
+
+use chromiumoxide::browser_test;
+use chromiumoxide::js::markdown::{MarkdownOptions, TRUNCATION_MARKER};
+
+const ARTICLE: &str = concat!(
+    r#"<html><head><base href="https://example.com/"></head><body>"#,
+    r#"<nav><a href="/">Home</a></nav>"#,
+    r#"<article>"#,
+    r#"<h1>Hello <em>world</em></h1>"#,
+    r#"<p>Some <strong>bold</strong> text with <a href="/docs">a link</a> and <code>code()</code>.</p>"#,
+    r#"<ul><li>One</li><li>Two<ol><li>Nested</li></ol></li></ul>"#,
+    r#"<blockquote><p>Quoted</p></blockquote>"#,
+    r#"<pre><code class="language-rust">fn main() {}
+</code></pre>"#,
+    r#"<table><tr><th>Name</th><th>Qty</th></tr><tr><td>Apple</td><td>1</td></tr></table>"#,
+    r#"<img src="/cat.png" alt="A cat">"#,
+    r#"</article>"#,
+    r#"<footer>Copyright</footer>"#,
+    r#"</body></html>"#,
+);
+
+const EXPECTED: &str = "# Hello _world_
+
+Some **bold** text with [a link](https://example.com/docs) and `code()`.
+
+- One
+- Two
+  1. Nested
+
+> Quoted
+
+```rust
+fn main() {}
+```
+
+| Name | Qty |
+| --- | --- |
+| Apple | 1 |
+
+![A cat](https://example.com/cat.png)";
+
+browser_test! {
+    async fn article_to_markdown(page) {
+        page.set_content(ARTICLE).await?;
+        let body = page.find_element("body").await?;
+        let markdown = body
+            .to_markdown(MarkdownOptions::new().skip_landmarks(true))
+            .await?;
+        assert_eq!(markdown, EXPECTED);
+
+        let with_landmarks = body.to_markdown(MarkdownOptions::new()).await?;
+        assert!(with_landmarks.starts_with("[Home](https://example.com/)"));
+        assert!(with_landmarks.ends_with("Copyright"));
+
+        let truncated = body
+            .to_markdown(MarkdownOptions::new().skip_landmarks(true).max_length(20))
+            .await?;
+        assert_eq!(truncated, format!("# Hello _world_{TRUNCATION_MARKER}"));
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn deeply_nested_markdown(page) {
+        // built with the DOM, the HTML parser limits the nesting depth
+        page.evaluate(
+            "let parent = document.body;
+            for (let i = 0; i < 5000; i++) {
+                parent = parent.appendChild(document.createElement('div'));
+            }
+            parent.textContent = 'deep';",
+        )
+        .await?;
+        let markdown = page
+            .find_element("body")
+            .await?
+            .to_markdown(MarkdownOptions::new())
+            .await?;
+        assert_eq!(markdown, "deep");
+        Ok(())
+    }
+}
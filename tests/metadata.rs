@@ -0,0 +1,41 @@
+#![cfg(feature = "testing")]
+
+use chromiumoxide::browser_test;
+use chromiumoxide::metadata::JsonLd;
+
+browser_test! {
+    async fn metadata_resolves_urls_and_keeps_first_tag(page) {
+        page.set_content(
+            r#"<html lang="en"><head>
+                <base href="https://example.com/blog/">
+                <title>Post</title>
+                <link rel="canonical" href="post">
+                <link rel="icon" href="/favicon.ico">
+                <link rel="shortcut icon" href="/favicon.ico">
+                <meta property="og:title" content="First">
+                <meta property="og:title" content="Second">
+                <meta property="og:image" content="cover.png">
+                <script type="application/ld+json">{"@type": "BlogPosting"}</script>
+                <script type="application/ld+json">{ broken</script>
+            </head><body></body></html>"#,
+        )
+        .await?;
+        let metadata = page.metadata().await?;
+        assert_eq!(metadata.title, "Post");
+        assert_eq!(metadata.lang.as_deref(), Some("en"));
+        assert_eq!(
+            metadata.canonical.as_deref(),
+            Some("https://example.com/blog/post")
+        );
+        assert_eq!(metadata.icons, vec!["https://example.com/favicon.ico"]);
+        assert_eq!(metadata.open_graph.title.as_deref(), Some("First"));
+        assert_eq!(
+            metadata.open_graph.image.as_deref(),
+            Some("https://example.com/blog/cover.png")
+        );
+        assert_eq!(metadata.twitter.card, None);
+        assert!(metadata.json_ld[0].value().is_some());
+        assert!(matches!(metadata.json_ld[1], JsonLd::Invalid { .. }));
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "testing")]
+
+use std::time::Duration;
+
+use chromiumoxide::browser_test;
+use chromiumoxide::cdp::js_protocol::runtime::EvaluateParams;
+use chromiumoxide::error::CdpError;
+
+browser_test! {
+    async fn pause_freezes_timers(page) {
+        page.evaluate("window.ticks = 0; setInterval(() => window.ticks++, 5); 0")
+            .await?;
+        let pause = page.pause_script_execution().await?;
+        let nested = page.pause_script_execution().await?;
+        let before: u64 = page.evaluate("window.ticks").await?.into_value()?;
+        std::thread::sleep(Duration::from_millis(100));
+        let after: u64 = page.evaluate("window.ticks").await?.into_value()?;
+        assert_eq!(before, after);
+
+        let awaiting = EvaluateParams::builder()
+            .expression("Promise.resolve(1)")
+            .await_promise(true)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            page.evaluate_expression(awaiting).await,
+            Err(CdpError::ScriptPaused)
+        ));
+
+        drop(nested);
+        pause.resume().await?;
+        std::thread::sleep(Duration::from_millis(100));
+        let resumed: u64 = page.evaluate("window.ticks").await?.into_value()?;
+        assert!(resumed > after);
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn resumed_page_ignores_debugger_statements(page) {
+        page.pause_script_execution().await?.resume().await?;
+        let value: u32 = page.evaluate("debugger; 1").await?.into_value()?;
+        assert_eq!(value, 1);
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn concurrent_pauses_wait_for_the_first(page) {
+        let (first, second) = futures::try_join!(
+            page.pause_script_execution(),
+            page.pause_script_execution()
+        )?;
+        let value: u32 = page.evaluate("1").await?.into_value()?;
+        assert_eq!(value, 1);
+        drop(first);
+        second.resume().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "testing")]
+
+use chromiumoxide::browser_test;
+use chromiumoxide::page::ScreenshotParams;
+use chromiumoxide::testing::shared_browser;
+
+browser_test! {
+    async fn screenshot_all_captures_open_pages(page) {
+        page.set_content("<h1>first</h1>").await?;
+        let second = shared_browser().new_page("about:blank").await?;
+        second.set_content("<h1>second</h1>").await?;
+
+        let screenshots = shared_browser()
+            .screenshot_all(ScreenshotParams::default(), 2)
+            .await?;
+        for target_id in [page.target_id(), second.target_id()] {
+            let (_, png) = screenshots
+                .iter()
+                .find(|(id, _)| id == target_id)
+                .expect("page was not captured");
+            let png = png.as_ref().expect("screenshot failed");
+            assert!(png.starts_with(b"\x89PNG"));
+        }
+        second.close().await?;
+        Ok(())
+    }
+}
+
+browser_test! {
+    async fn stitched_screenshots_have_no_gaps(page) {
+        use chromiumoxide::cdp::browser_protocol::emulation::{
+            ClearDeviceMetricsOverrideParams, SetDeviceMetricsOverrideParams,
+        };
+
+        // a fractional ratio puts tile boundaries between CSS pixels
+        page.execute(SetDeviceMetricsOverrideParams::new(800, 600, 1.5, false))
+            .await?;
+        page.set_content(
+            r#"<body style="margin: 0"><div style="height: 2500px; background: red"></div></body>"#,
+        )
+        .await?;
+
+        assert!(page
+            .screenshot(ScreenshotParams::builder().stitched(true).build())
+            .await
+            .is_err());
+        let png = page
+            .screenshot(
+                ScreenshotParams::builder()
+                    .full_page(true)
+                    .stitched(true)
+                    .build(),
+            )
+            .await?;
+        let height = u32::from_be_bytes(png[20..24].try_into().unwrap());
+        assert_eq!(height, 3750);
+
+        // rows the tiles don't cover stay transparent
+        let gaps: Vec<u32> = page
+            .evaluate_function_with_bytes(
+                "async (bytes) => {
+                    const image = await createImageBitmap(new Blob([bytes]));
+                    const canvas = new OffscreenCanvas(image.width, image.height);
+                    const context = canvas.getContext('2d');
+                    context.drawImage(image, 0, 0);
+                    const column = context.getImageData(0, 0, 1, image.height).data;
+                    const gaps = [];
+                    for (let row = 0; row < image.height; row++) {
+                        if (column[row * 4] !== 255 || column[row * 4 + 3] !== 255) {
+                            gaps.push(row);
+                        }
+                    }
+                    return gaps;
+                }",
+                &png,
+                1024 * 1024,
+            )
+            .await?
+            .into_value()?;
+        assert!(gaps.is_empty(), "{gaps:?}");
+
+        page.execute(ClearDeviceMetricsOverrideParams {}).await?;
+        Ok(())
+    }
+}